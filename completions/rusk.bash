@@ -446,10 +446,10 @@ _rusk_completion() {
     if [ $rusk_idx -ge 0 ] && [ $COMP_CWORD -eq $((rusk_idx + 1)) ]; then
         # Only full subcommand names: short aliases (a e m …) still get Tab → long name, not flags (-h/--help).
         case "$cur" in
-            add|edit|mark|del|list|restore|completions)
+            add|edit|mark|del|list|restore|tags|block|unblock|recur|wait|undo|redo|log|doctor|init|lists|config|merge-driver|sync|renumber|archive|unarchive|completions)
                 ;;
             *)
-                COMPREPLY=($(compgen -W "add edit mark del list restore completions a e m d l r c" -- "$cur"))
+                COMPREPLY=($(compgen -W "add edit mark del list restore tags block unblock recur wait undo redo log doctor init lists config merge-driver sync renumber archive unarchive completions a e m d l r c" -- "$cur"))
                 return 0
                 ;;
         esac
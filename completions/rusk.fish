@@ -727,6 +727,23 @@ complete -c rusk -f -n '__fish_use_subcommand' -a 'mark' -d 'Mark tasks as done/
 complete -c rusk -f -n '__fish_use_subcommand' -a 'del' -d 'Delete tasks by id(s)'
 complete -c rusk -f -n '__fish_use_subcommand' -a 'list' -d 'List all tasks'
 complete -c rusk -f -n '__fish_use_subcommand' -a 'restore' -d 'Restore from backup'
+complete -c rusk -f -n '__fish_use_subcommand' -a 'tags' -d 'List tags with task counts'
+complete -c rusk -f -n '__fish_use_subcommand' -a 'block' -d 'Block a task on other tasks'
+complete -c rusk -f -n '__fish_use_subcommand' -a 'unblock' -d 'Remove blockers from a task'
+complete -c rusk -f -n '__fish_use_subcommand' -a 'recur' -d 'Set or remove a repeat rule'
+complete -c rusk -f -n '__fish_use_subcommand' -a 'wait' -d 'Hide a task until a start date'
+complete -c rusk -f -n '__fish_use_subcommand' -a 'undo' -d 'Undo the last change'
+complete -c rusk -f -n '__fish_use_subcommand' -a 'redo' -d 'Redo an undone change'
+complete -c rusk -f -n '__fish_use_subcommand' -a 'log' -d 'Show recent changes'
+complete -c rusk -f -n '__fish_use_subcommand' -a 'doctor' -d 'Check the database for problems and repair them'
+complete -c rusk -f -n '__fish_use_subcommand' -a 'init' -d 'Create a task database in this directory'
+complete -c rusk -f -n '__fish_use_subcommand' -a 'lists' -d 'Show the lists in the database directory'
+complete -c rusk -f -n '__fish_use_subcommand' -a 'config' -d 'Show or change settings'
+complete -c rusk -f -n '__fish_use_subcommand' -a 'merge-driver' -d 'Three-way merge of task databases (used by git)'
+complete -c rusk -f -n '__fish_use_subcommand' -a 'sync' -d 'Merge with another task database in both directions'
+complete -c rusk -f -n '__fish_use_subcommand' -a 'renumber' -d 'Reassign IDs 1..n and show which changed'
+complete -c rusk -f -n '__fish_use_subcommand' -a 'archive' -d 'Move tasks to the archive instead of deleting them'
+complete -c rusk -f -n '__fish_use_subcommand' -a 'unarchive' -d 'Bring archived tasks back'
 complete -c rusk -f -n '__fish_use_subcommand' -a 'completions' -d 'Install shell completions'
 
 # Aliases
@@ -745,6 +762,8 @@ complete -c rusk -f -n '__fish_use_subcommand' -a '-V' -d 'Show version'
 complete -c rusk -f -n '__fish_use_subcommand' -a '--version' -d 'Show version'
 complete -c rusk -f -n '__fish_use_subcommand' -s h -l help -d 'Show help'
 complete -c rusk -f -n '__fish_use_subcommand' -s V -l version -d 'Show version'
+complete -c rusk -n '__fish_use_subcommand' -l db -r -F -d 'Database file or directory'
+complete -c rusk -f -n '__fish_use_subcommand' -l list -r -d 'Named list to use'
 
 # ============================================================================
 # Add Command Completions
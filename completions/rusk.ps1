@@ -410,7 +410,7 @@ Register-ArgumentCompleter -Native -CommandName rusk -ScriptBlock {
 
     # Complete commands (when only "rusk" is typed)
     if ($tokens.Count -eq 1) {
-        $commands = @('add', 'a', 'edit', 'e', 'mark', 'm', 'del', 'd', 'list', 'l', 'restore', 'r', 'completions', 'c')
+        $commands = @('add', 'a', 'edit', 'e', 'mark', 'm', 'del', 'd', 'list', 'l', 'restore', 'r', 'tags', 'block', 'unblock', 'recur', 'wait', 'undo', 'redo', 'log', 'doctor', 'init', 'lists', 'config', 'merge-driver', 'sync', 'renumber', 'archive', 'unarchive', 'completions', 'c')
         if ([string]::IsNullOrEmpty($wordToComplete)) {
             $filtered = $commands
         } else {
@@ -425,8 +425,8 @@ Register-ArgumentCompleter -Native -CommandName rusk -ScriptBlock {
     }
 
     # First arg after rusk: complete unless it's already a full subcommand name (aliases expand via Tab, not to -h/--help).
-    $fullSubcommands = @('add', 'edit', 'mark', 'del', 'list', 'restore', 'completions')
-    $allSubcommands = @('add', 'a', 'edit', 'e', 'mark', 'm', 'del', 'd', 'list', 'l', 'restore', 'r', 'completions', 'c')
+    $fullSubcommands = @('add', 'edit', 'mark', 'del', 'list', 'restore', 'tags', 'block', 'unblock', 'recur', 'wait', 'undo', 'redo', 'log', 'doctor', 'init', 'lists', 'config', 'merge-driver', 'sync', 'renumber', 'archive', 'unarchive', 'completions')
+    $allSubcommands = @('add', 'a', 'edit', 'e', 'mark', 'm', 'del', 'd', 'list', 'l', 'restore', 'r', 'tags', 'block', 'unblock', 'recur', 'wait', 'undo', 'redo', 'log', 'doctor', 'init', 'lists', 'config', 'merge-driver', 'sync', 'renumber', 'archive', 'unarchive', 'completions', 'c')
     if ($tokens.Count -eq 2) {
         $first = _rusk_token_text $tokens[1]
         if (-not [string]::IsNullOrEmpty($first) -and ($fullSubcommands -notcontains $first)) {
@@ -0,0 +1,60 @@
+//! Archived tasks (`rusk archive`, `rusk list --archived`, `rusk unarchive`).
+//!
+//! Each list keeps its archive in `<db file>.archive`, in the same format as the database
+//! itself. Archived tasks keep their UUIDs; their IDs only have to be unique within the archive,
+//! so a task whose ID is already taken there gets the next one above the highest.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use uuid::Uuid;
+
+use crate::atomic_write;
+use crate::db_format::{self, DbMeta};
+use crate::model::{Task, TaskId};
+
+/// Archive path for the database at `db_path` (`tasks.json` → `tasks.json.archive`).
+pub fn archive_path(db_path: &Path) -> PathBuf {
+    db_format::sibling_path(db_path, ".archive")
+}
+
+/// Tasks in the archive at `path`, oldest first; empty when there is no archive yet.
+pub fn read(path: &Path) -> Result<(Vec<Task>, DbMeta)> {
+    db_format::read_file(path)
+}
+
+fn write(path: &Path, tasks: &[Task], meta: &DbMeta) -> Result<()> {
+    let data = db_format::encode(tasks, meta).context("Failed to serialize the archive")?;
+    atomic_write::write_atomic(path, data.as_bytes()).context("Failed to write the archive")
+}
+
+/// Appends `tasks` to the archive, replacing earlier copies with the same UUID. Returns the IDs
+/// they have in the archive.
+pub fn add(path: &Path, tasks: Vec<Task>) -> Result<Vec<TaskId>> {
+    let (mut archived, meta) = read(path)?;
+    archived.retain(|a| !tasks.iter().any(|t| t.uuid == a.uuid));
+    let mut ids = Vec::with_capacity(tasks.len());
+    for mut task in tasks {
+        if task.id == 0 || archived.iter().any(|a| a.id == task.id) {
+            let highest = archived.iter().map(|a| a.id).max().unwrap_or(0);
+            task.id = highest
+                .checked_add(1)
+                .with_context(|| format!("Maximum task ID ({}) reached", TaskId::MAX))?;
+        }
+        ids.push(task.id);
+        archived.push(task);
+    }
+    write(path, &archived, &meta)?;
+    Ok(ids)
+}
+
+/// Drops the tasks with these UUIDs from the archive.
+pub fn remove(path: &Path, uuids: &[Uuid]) -> Result<()> {
+    let (mut archived, meta) = read(path)?;
+    let before = archived.len();
+    archived.retain(|a| !uuids.contains(&a.uuid));
+    if archived.len() != before {
+        write(path, &archived, &meta)?;
+    }
+    Ok(())
+}
@@ -1,5 +1,10 @@
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
 
+use crate::cli::ListSort;
+use crate::storage::RenumberOrder;
+
 #[cfg(feature = "completions")]
 use crate::completions::Shell;
 
@@ -9,6 +14,8 @@ Date value for -d / --date (see `rusk add --help`):
   Relative    Offset from today's local date. Chain segments with no spaces.
               Suffixes: d=days, w=weeks, m=months, q=quarters (3 months), y=years.
               Examples: 2d, 2w, 5m, 3q, 2y, 10d5w, 12d2q1y.
+  Time        Optional 24-hour time after @ or a space (quote the space form),
+              e.g. 2d@14:00, \"31-12-2026 18:00\"; @9:30 alone means today.
   Clear       Pass _ to remove the date from a task (e.g. -d _).
   Subcommand  Pass -h or --help as the date value for this command's help (e.g. -d -h).\n";
 
@@ -16,7 +23,8 @@ pub const EDIT_SUBCOMMAND_LONG_HELP: &str = "\
 Interactive edit (`rusk edit <id>`) uses the TUI: the due date (if any) is only the \
 first whitespace-delimited token at the start of the first line of the task text \
 (absolute, relative from today, leading `+` on a relative offset from the task's \
-current due date — today if none — or `_` to clear). A valid date token is highlighted in color; \
+current due date — today if none — or `_` to clear), optionally followed by a time \
+such as `14:00` or joined with `@` (`2d@14:00`). A valid date token is highlighted in color; \
 see Ctrl+G / F1 in the editor for the full date syntax. \
 One-shot date or text+date without opening the TUI: `rusk edit <id> -d <date>` (same \
 relative rules as in the TUI; `_` clears). Bare `-d` / `--date` (no value) is not \
@@ -25,10 +33,10 @@ supported. For new tasks, use `rusk add -d`.\n";
 /// Root `--help` tail (after subcommands/options). Omits `completions` when that feature is off so
 /// distro builds (`--no-default-features`) match the available CLI and static files in `completions/`.
 #[cfg(feature = "completions")]
-const CLI_ROOT_AFTER_LONG_HELP: &str = "Running `rusk` without a COMMAND is equivalent to `rusk list`. Use `rusk list -c` / `--compact` for a compact single-line view.\n\nDue dates: `rusk add -d ...` for new tasks, `rusk add` with no text for the TUI, or the interactive editor (`rusk edit <id>`) — first line at the start, see `rusk edit --help` and EDITOR.md. Pass `_` to clear where `-d` is supported. See `rusk add --help` for date syntax.\n\nEnvironment:\n  RUSK_DB        Optional path to the tasks database file or directory.\n  RUSK_NO_COLOR  Disable ANSI colors when set to any non-empty value (NO_COLOR is also respected).\n\nShell tab completion:\n  rusk completions install <shell> [<shell> ...]\n  rusk completions show <shell>\n";
+const CLI_ROOT_AFTER_LONG_HELP: &str = "Running `rusk` without a COMMAND is equivalent to `rusk list`. Use `rusk list -c` / `--compact` for a compact single-line view.\n\nDue dates: `rusk add -d ...` for new tasks, `rusk add` with no text for the TUI, or the interactive editor (`rusk edit <id>`) — first line at the start, see `rusk edit --help` and EDITOR.md. Pass `_` to clear where `-d` is supported. See `rusk add --help` for date syntax.\n\nEnvironment:\n  RUSK_DB        Optional path to the tasks database file or directory (--db takes precedence).\n  RUSK_CONFIG    Optional path to the user-wide config file (see `rusk config list`).\n  RUSK_NO_COLOR  Disable ANSI colors when set to any non-empty value (NO_COLOR is also respected).\n\nShell tab completion:\n  rusk completions install <shell> [<shell> ...]\n  rusk completions show <shell>\n";
 
 #[cfg(not(feature = "completions"))]
-const CLI_ROOT_AFTER_LONG_HELP: &str = "Running `rusk` without a COMMAND is equivalent to `rusk list`. Use `rusk list -c` / `--compact` for a compact single-line view.\n\nDue dates: `rusk add -d ...` for new tasks, `rusk add` with no text for the TUI, or the interactive editor (`rusk edit <id>`) — first line at the start, see `rusk edit --help` and EDITOR.md. Pass `_` to clear where `-d` is supported. See `rusk add --help` for date syntax.\n\nEnvironment:\n  RUSK_DB        Optional path to the tasks database file or directory.\n  RUSK_NO_COLOR  Disable ANSI colors when set to any non-empty value (NO_COLOR is also respected).\n";
+const CLI_ROOT_AFTER_LONG_HELP: &str = "Running `rusk` without a COMMAND is equivalent to `rusk list`. Use `rusk list -c` / `--compact` for a compact single-line view.\n\nDue dates: `rusk add -d ...` for new tasks, `rusk add` with no text for the TUI, or the interactive editor (`rusk edit <id>`) — first line at the start, see `rusk edit --help` and EDITOR.md. Pass `_` to clear where `-d` is supported. See `rusk add --help` for date syntax.\n\nEnvironment:\n  RUSK_DB        Optional path to the tasks database file or directory (--db takes precedence).\n  RUSK_CONFIG    Optional path to the user-wide config file (see `rusk config list`).\n  RUSK_NO_COLOR  Disable ANSI colors when set to any non-empty value (NO_COLOR is also respected).\n";
 
 #[derive(Parser)]
 #[command(
@@ -38,6 +46,18 @@ const CLI_ROOT_AFTER_LONG_HELP: &str = "Running `rusk` without a COMMAND is equi
     after_long_help = CLI_ROOT_AFTER_LONG_HELP
 )]
 pub struct Cli {
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Use this database file (or directory holding tasks.json) instead of the nearest .rusk. Goes before COMMAND"
+    )]
+    pub db: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Use the named list next to the default one (stored as NAME.json), e.g. rusk --list work add ... Goes before COMMAND"
+    )]
+    pub list: Option<String>,
     #[command(subcommand)]
     pub command: Option<Command>,
 }
@@ -61,13 +81,38 @@ pub enum Command {
             long,
             value_name = "DATE",
             allow_hyphen_values = true,
-            help = "Due date: DD-MM-YYYY (slashes/dots ok, 1-7-25 ok), or relative from today (2d, 3q, 10d5w, …). See `rusk add --help` for full syntax. Pass `-d -h` for this command's help"
+            help = "Due date: DD-MM-YYYY (slashes/dots ok, 1-7-25 ok), or relative from today (2d, 3q, 10d5w, …), with an optional time (2d@14:00). See `rusk add --help` for full syntax. Pass `-d -h` for this command's help"
         )]
         date: Option<String>,
+        #[arg(
+            long,
+            visible_alias = "start",
+            value_name = "DATE",
+            help = "Start date (same syntax as --date, without a time): the task stays out of `rusk list` until then, e.g. --wait 2m"
+        )]
+        wait: Option<String>,
+        #[arg(
+            long,
+            value_name = "ID",
+            help = "Add the task as a subtask of task ID (short ID or UUID prefix), e.g. rusk add --parent 3 write tests"
+        )]
+        parent: Option<String>,
+        #[arg(
+            long,
+            value_name = "INTERVAL",
+            help = "Repeat the task: marking it done adds the next occurrence this far ahead (relative date syntax, e.g. 1w, 1m, 2w3d)"
+        )]
+        every: Option<String>,
+        #[arg(
+            long,
+            requires = "every",
+            help = "Count the next occurrence from the day the task is completed instead of from its due date"
+        )]
+        from_completion: bool,
     },
     #[command(
         visible_alias = "d",
-        about = "Delete tasks by ID, or all completed ones with --done. Examples: rusk del 3; rusk del 1,2,3; rusk del --done",
+        about = "Delete tasks by ID, or all completed ones with --done. A task with subtasks needs --cascade or --reparent. Examples: rusk del 3; rusk del 1,2,3; rusk del --done; rusk del 3 --cascade",
         help_template = "{about-section}\n\nUsage: rusk del [OPTIONS] [IDS]...\n\n{all-args}"
     )]
     Del {
@@ -79,6 +124,45 @@ pub enum Command {
         ids: Vec<String>,
         #[arg(long, help = "Delete all completed tasks (ignores IDS)")]
         done: bool,
+        #[arg(
+            long,
+            conflicts_with = "reparent",
+            help = "Also delete the subtasks of the given tasks"
+        )]
+        cascade: bool,
+        #[arg(
+            long,
+            help = "Keep the subtasks of the given tasks and move them up to the deleted task's parent"
+        )]
+        reparent: bool,
+    },
+    #[command(
+        about = "Move tasks, with their subtasks, to the archive next to the database instead of deleting them. Examples: rusk archive 3; rusk archive 1,2; rusk archive --done"
+    )]
+    Archive {
+        #[arg(
+            trailing_var_arg = true,
+            value_name = "IDS",
+            help = "Task IDs: comma-separated (e.g. 1,2,3); without commas only the first ID is used"
+        )]
+        ids: Vec<String>,
+        #[arg(
+            long,
+            help = "Archive all completed tasks whose subtasks are completed too (ignores IDS)"
+        )]
+        done: bool,
+    },
+    #[command(
+        about = "Bring archived tasks back, with their archived subtasks. IDS are the ones shown by rusk list --archived. Example: rusk unarchive 12"
+    )]
+    Unarchive {
+        #[arg(
+            trailing_var_arg = true,
+            required = true,
+            value_name = "IDS",
+            help = "Archive IDs: comma-separated (e.g. 1,2,3); without commas only the first ID is used"
+        )]
+        ids: Vec<String>,
     },
     #[command(
         visible_alias = "m",
@@ -88,9 +172,11 @@ pub enum Command {
         #[arg(
             short,
             long,
-            help = "Toggle the priority flag instead of the done flag. Priority is preserved across done/undone toggles"
+            value_name = "LEVEL",
+            num_args = 0..=1,
+            help = "Set the priority instead of the done flag: high, medium, low or none (h/m/l/n). Without LEVEL, toggles between high and none. Priority is preserved across done/undone toggles"
         )]
-        priority: bool,
+        priority: Option<Option<String>>,
         #[arg(
             value_name = "IDS",
             help = "Task IDs: comma-separated (e.g. 1,2,3); without commas only the first ID is used"
@@ -125,12 +211,253 @@ pub enum Command {
             help = "Compact view: show only the first line of each task (no wrap/paragraph continuations); strip trailing punctuation on that line"
         )]
         compact: bool,
+        #[arg(
+            long,
+            conflicts_with = "compact",
+            help = "Full view even when the config sets list.mode = \"compact\""
+        )]
+        full: bool,
+        #[arg(
+            short = 'u',
+            long,
+            help = "Show the first 8 characters of each task's stable UUID. Any unique prefix (4+ hex chars) can be used in place of an ID, e.g. rusk mark 3fa8"
+        )]
+        uuid: bool,
+        #[arg(
+            short = 't',
+            long,
+            help = "Show when each task was created, last updated and completed (e.g. created 3d ago · done 1h ago)"
+        )]
+        timestamps: bool,
+        #[arg(
+            long = "tag",
+            value_name = "TAG",
+            allow_hyphen_values = true,
+            help = "Only tasks tagged #TAG (repeatable; all must match). Prefix with - or ! to hide tasks with that tag, e.g. --tag backend --tag -blocked"
+        )]
+        tag: Vec<String>,
+        #[arg(
+            long,
+            help = "Hide tasks that are waiting on an open blocker (see rusk block)"
+        )]
+        hide_blocked: bool,
+        #[arg(
+            long,
+            value_enum,
+            value_name = "KEY",
+            help = "Order tasks instead of showing them in the order they were added. due: by due date and time, undated last; priority: highest first, then by due date"
+        )]
+        sort: Option<ListSort>,
+        #[arg(
+            long,
+            conflicts_with = "all",
+            help = "Show only tasks whose start date (--wait) is still in the future"
+        )]
+        waiting: bool,
+        #[arg(
+            short = 'a',
+            long,
+            help = "Also show tasks whose start date (--wait) is still in the future"
+        )]
+        all: bool,
+        #[arg(
+            long,
+            help = "Show the archived tasks instead (see rusk archive), with their archive IDs"
+        )]
+        archived: bool,
+    },
+    #[command(about = "List all #tags with the number of tasks using each")]
+    Tags,
+    #[command(
+        about = "Set how a task repeats, or stop it repeating with _. Examples: rusk recur 3 1m; rusk recur 3 2w --from-completion; rusk recur 3 _"
+    )]
+    Recur {
+        #[arg(value_name = "ID", help = "Task ID (short ID or UUID prefix)")]
+        id: String,
+        #[arg(
+            value_name = "INTERVAL",
+            help = "Relative offset between occurrences (e.g. 1w, 1m, 1y), or _ to remove the rule"
+        )]
+        every: String,
+        #[arg(
+            long,
+            help = "Count the next occurrence from the completion day instead of the due date"
+        )]
+        from_completion: bool,
+    },
+    #[command(
+        about = "Hide a task from `rusk list` until a start date, or show it again with _. Examples: rusk wait 3 2w; rusk wait 3 1-3-27; rusk wait 3 +1w; rusk wait 3 _"
+    )]
+    Wait {
+        #[arg(value_name = "ID", help = "Task ID (short ID or UUID prefix)")]
+        id: String,
+        #[arg(
+            value_name = "DATE",
+            allow_hyphen_values = true,
+            help = "Start date (see `rusk add --help`); a leading + counts from the current start date; _ removes it"
+        )]
+        date: String,
+    },
+    #[command(
+        about = "Mark a task as blocked until other tasks are done (shown with ⊘ in the list). Example: rusk block 5 --on 2,3"
+    )]
+    Block {
+        #[arg(
+            value_name = "ID",
+            help = "Task that has to wait (short ID or UUID prefix)"
+        )]
+        id: String,
+        #[arg(
+            long,
+            value_name = "IDS",
+            required = true,
+            help = "Tasks it waits for, comma-separated (e.g. 2,3)"
+        )]
+        on: String,
+    },
+    #[command(
+        about = "Remove blockers from a task. Examples: rusk unblock 5; rusk unblock 5 --from 2"
+    )]
+    Unblock {
+        #[arg(value_name = "ID", help = "Blocked task (short ID or UUID prefix)")]
+        id: String,
+        #[arg(
+            long,
+            value_name = "IDS",
+            help = "Only remove these blockers, comma-separated (default: all)"
+        )]
+        from: Option<String>,
+    },
+    #[command(
+        about = "Undo the last change (or the last N), e.g. an accidental `del --done`. Examples: rusk undo; rusk undo 3"
+    )]
+    Undo {
+        #[arg(
+            value_name = "N",
+            default_value_t = 1,
+            help = "Number of changes to undo"
+        )]
+        n: usize,
+    },
+    #[command(about = "Redo changes reverted by `rusk undo`. Examples: rusk redo; rusk redo 2")]
+    Redo {
+        #[arg(
+            value_name = "N",
+            default_value_t = 1,
+            help = "Number of changes to redo"
+        )]
+        n: usize,
+    },
+    #[command(
+        about = "Reassign IDs 1..n, in list order or by due date or priority, and show which IDs changed. The mapping is kept: rusk renumber --history. Undo with rusk undo"
+    )]
+    Renumber {
+        #[arg(
+            long,
+            value_enum,
+            value_name = "KEY",
+            default_value_t = RenumberOrder::Position,
+            help = "Order of the new IDs. position: as listed now; due: by due date and time, undated last; priority: highest first, then by due date"
+        )]
+        by: RenumberOrder,
+        #[arg(
+            long,
+            conflicts_with = "by",
+            help = "Show the ID mappings of past renumberings instead, newest first"
+        )]
+        history: bool,
+    },
+    #[command(about = "Show recent changes with their time and what they did (newest first)")]
+    Log {
+        #[arg(
+            short = 'n',
+            long,
+            value_name = "N",
+            default_value_t = 20,
+            help = "Number of entries to show"
+        )]
+        limit: usize,
+    },
+    #[command(
+        about = "Create a task database in this directory (.rusk/tasks.json, or .rusk/NAME.json with --list NAME; the --db file with --db). rusk run here or in any subdirectory uses it"
+    )]
+    Init {
+        #[arg(
+            long,
+            help = "Also set up `rusk merge-driver` for .rusk/*.json in .gitattributes and the git config, so task databases committed with the code merge cleanly"
+        )]
+        git: bool,
+    },
+    #[command(
+        about = "Three-way merge of task databases, run by git (see rusk init --git). Writes the result over OURS; exits with status 1 when conflicts are left in it"
+    )]
+    MergeDriver {
+        #[arg(value_name = "BASE", help = "Common ancestor (git's %O)")]
+        base: PathBuf,
+        #[arg(
+            value_name = "OURS",
+            help = "Current branch, overwritten with the result (git's %A)"
+        )]
+        ours: PathBuf,
+        #[arg(value_name = "THEIRS", help = "Branch being merged in (git's %B)")]
+        theirs: PathBuf,
+    },
+    #[command(
+        about = "Show or change settings in config.toml (project: next to the database; --global: user-wide). Examples: rusk config list; rusk config get list.mode; rusk config set list.mode compact"
+    )]
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    #[command(
+        about = "Show the lists in the database directory with their open and total task counts (* marks the one in use). Switch with rusk --list NAME ..."
+    )]
+    Lists,
+    #[command(
+        about = "Merge this database with another tasks.json in both directions. Deletions carry over; a task changed on both sides keeps the newer change. Example: rusk sync ~/Dropbox/rusk/tasks.json"
+    )]
+    Sync {
+        #[arg(
+            value_name = "PATH",
+            help = "The other database file, or a directory holding tasks.json"
+        )]
+        other: PathBuf,
+    },
+    #[command(
+        about = "Check the database for duplicate or zero IDs, empty texts, impossible dates and leftover files. Examples: rusk doctor; rusk doctor --fix"
+    )]
+    Doctor {
+        #[arg(
+            long,
+            help = "Renumber duplicate IDs, move invalid entries to a quarantine file and remove leftover files"
+        )]
+        fix: bool,
     },
     #[command(
         visible_alias = "r",
-        about = "Restore task database from the automatic backup (.json.backup)"
+        about = "Restore the task database from a backup (newest by default). Examples: rusk restore; rusk restore --list; rusk restore 3; rusk restore --from old.json"
     )]
-    Restore,
+    Restore {
+        #[arg(
+            value_name = "N",
+            help = "Backup number from `rusk restore --list` (1 = newest)"
+        )]
+        n: Option<usize>,
+        #[arg(
+            short,
+            long,
+            conflicts_with_all = ["n", "from"],
+            help = "List backups with their age, task count and what restoring each would change"
+        )]
+        list: bool,
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with = "n",
+            help = "Restore from this file instead of a backup"
+        )]
+        from: Option<std::path::PathBuf>,
+    },
     #[cfg(feature = "completions")]
     #[command(
         visible_alias = "c",
@@ -142,6 +469,36 @@ pub enum Command {
     },
 }
 
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    #[command(about = "Show every setting with its value and where the value comes from")]
+    List,
+    #[command(about = "Print the value in effect for KEY")]
+    Get {
+        #[arg(
+            value_name = "KEY",
+            help = "Setting such as list.mode (see rusk config list)"
+        )]
+        key: String,
+    },
+    #[command(about = "Set KEY to VALUE in the project config, or the user config with --global")]
+    Set {
+        #[arg(
+            value_name = "KEY",
+            help = "Setting such as list.mode (see rusk config list)"
+        )]
+        key: String,
+        #[arg(value_name = "VALUE", allow_hyphen_values = true)]
+        value: String,
+        #[arg(
+            short,
+            long,
+            help = "Write the user-wide config instead of the one next to the database"
+        )]
+        global: bool,
+    },
+}
+
 #[cfg(feature = "completions")]
 #[derive(Subcommand)]
 pub enum CompletionAction {
@@ -0,0 +1,187 @@
+//! Crash-safe replacement of the database file.
+//!
+//! New content goes to a uniquely named temporary file in the same directory, which is flushed to
+//! disk and then renamed over the target; the directory is flushed afterwards so the rename
+//! itself survives a power loss. If any step before the rename fails, the temporary file is
+//! removed and the target is left exactly as it was. There is deliberately no fallback to
+//! copying or writing the target in place: a truncating write that dies halfway loses the whole
+//! database.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use colored::*;
+
+/// Steps of [`write_atomic`], in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SaveStep {
+    CreateTemp,
+    WriteTemp,
+    SyncTemp,
+    Rename,
+    SyncDir,
+}
+
+#[cfg(test)]
+thread_local! {
+    static FAIL_AT: std::cell::Cell<Option<SaveStep>> = const { std::cell::Cell::new(None) };
+}
+
+/// Makes the next [`write_atomic`] calls on this thread fail at `step` (`None` to stop).
+#[cfg(test)]
+pub(crate) fn fail_at(step: Option<SaveStep>) {
+    FAIL_AT.with(|f| f.set(step));
+}
+
+/// Runs one step, failing it instead when a test asked for that.
+fn step<T>(step: SaveStep, op: impl FnOnce() -> std::io::Result<T>) -> std::io::Result<T> {
+    #[cfg(test)]
+    if FAIL_AT.with(|f| f.get()) == Some(step) {
+        return Err(std::io::Error::other(format!(
+            "injected failure at {step:?}"
+        )));
+    }
+    let _ = step;
+    op()
+}
+
+/// Unique temporary path next to `path`, so concurrent writers never share one.
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let unique = uuid::Uuid::new_v4().simple().to_string();
+    path.with_file_name(format!(
+        ".{name}.{}.{}.tmp",
+        std::process::id(),
+        &unique[..12]
+    ))
+}
+
+fn dir_of(path: &Path) -> &Path {
+    match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    }
+}
+
+/// Replaces `path` with `data` durably (see the module docs). The parent directory must exist.
+pub fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    let temp = temp_path(path);
+    if let Err(e) = write_temp(&temp, data) {
+        let _ = fs::remove_file(&temp);
+        return Err(e).with_context(|| {
+            format!(
+                "Failed to write '{}'; '{}' was not modified",
+                temp.display(),
+                path.display()
+            )
+        });
+    }
+    if let Err(e) = step(SaveStep::Rename, || fs::rename(&temp, path)) {
+        let _ = fs::remove_file(&temp);
+        return Err(e).with_context(|| {
+            format!(
+                "Failed to move the new database into place at '{}'; it was not modified",
+                path.display()
+            )
+        });
+    }
+    // The new content is in place; a failed directory flush only weakens the crash guarantee.
+    if let Err(e) = step(SaveStep::SyncDir, || sync_dir(dir_of(path))) {
+        eprintln!(
+            "{}",
+            format!(
+                "Warning: Saved, but could not flush '{}' to disk: {e}",
+                dir_of(path).display()
+            )
+            .yellow()
+        );
+    }
+    Ok(())
+}
+
+fn write_temp(temp: &Path, data: &[u8]) -> std::io::Result<()> {
+    let mut file = step(SaveStep::CreateTemp, || {
+        OpenOptions::new().write(true).create_new(true).open(temp)
+    })?;
+    step(SaveStep::WriteTemp, || file.write_all(data))?;
+    step(SaveStep::SyncTemp, || file.sync_all())
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> std::io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+/// Directories cannot be opened for flushing on Windows; renames there are journaled by NTFS.
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SaveStep, fail_at, write_atomic};
+    use std::fs;
+
+    fn leftovers(dir: &std::path::Path) -> Vec<String> {
+        fs::read_dir(dir)
+            .unwrap()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .filter(|n| n.ends_with(".tmp"))
+            .collect()
+    }
+
+    #[test]
+    fn replaces_content_without_leftovers() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.json");
+        fs::write(&path, "old").unwrap();
+        write_atomic(&path, b"new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert!(leftovers(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn failure_before_rename_keeps_original() {
+        for at in [
+            SaveStep::CreateTemp,
+            SaveStep::WriteTemp,
+            SaveStep::SyncTemp,
+            SaveStep::Rename,
+        ] {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("tasks.json");
+            fs::write(&path, "old").unwrap();
+            fail_at(Some(at));
+            let result = write_atomic(&path, b"new");
+            fail_at(None);
+            let err = result.expect_err(&format!("{at:?} should fail the write"));
+            assert!(
+                format!("{err:#}").contains("not modified"),
+                "{at:?}: {err:#}"
+            );
+            assert_eq!(fs::read_to_string(&path).unwrap(), "old", "{at:?}");
+            assert!(leftovers(dir.path()).is_empty(), "{at:?}");
+        }
+    }
+
+    #[test]
+    fn failed_dir_sync_still_saves() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.json");
+        fail_at(Some(SaveStep::SyncDir));
+        let result = write_atomic(&path, b"new");
+        fail_at(None);
+        result.unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+    }
+
+    #[test]
+    fn temp_names_are_unique() {
+        let path = std::path::Path::new("/tmp/x/tasks.json");
+        assert_ne!(super::temp_path(path), super::temp_path(path));
+    }
+}
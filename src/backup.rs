@@ -0,0 +1,197 @@
+//! Rotating, timestamped backups of the database file.
+//!
+//! Every save first copies the current file to `<db file>.backup-<YYYYMMDD-HHMMSS-mmm>` in the
+//! same directory and then prunes all but the newest [`backup_retention`] copies. A `.json.backup`
+//! left by older rusk versions is listed alongside them (dated by its modification time) and is
+//! never pruned.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDateTime, SubsecRound};
+
+use crate::atomic_write;
+use crate::db_format;
+use crate::model::Task;
+
+/// Backups kept when `RUSK_BACKUP_COUNT` is not set.
+pub const DEFAULT_BACKUP_RETENTION: usize = 10;
+
+const STAMP_FORMAT: &str = "%Y%m%d-%H%M%S-%3f";
+
+/// One backup file on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupEntry {
+    pub path: PathBuf,
+    /// When the backup was taken (local time).
+    pub taken_at: NaiveDateTime,
+    /// The single-file `.json.backup` of older versions.
+    pub legacy: bool,
+}
+
+/// How restoring a backup would change the current tasks (see [`diff_tasks`]).
+#[derive(Debug, Clone, Default)]
+pub struct RestoreDiff {
+    /// Tasks in the backup that the current database does not have.
+    pub added: Vec<Task>,
+    /// Current tasks the backup does not have.
+    pub removed: Vec<Task>,
+    /// `(current, from backup)` pairs whose text, due, done state or priority differ.
+    pub changed: Vec<(Task, Task)>,
+}
+
+impl RestoreDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Number of backups to keep, from `RUSK_BACKUP_COUNT` (0 disables backups).
+pub fn backup_retention() -> usize {
+    std::env::var("RUSK_BACKUP_COUNT")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_BACKUP_RETENTION)
+}
+
+fn file_name(db_path: &Path) -> String {
+    db_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "tasks.json".to_string())
+}
+
+fn backup_prefix(db_path: &Path) -> String {
+    format!("{}.backup-", file_name(db_path))
+}
+
+/// Path of the single backup file written by older rusk versions.
+pub fn legacy_backup_path(db_path: &Path) -> PathBuf {
+    db_format::sibling_path(db_path, ".backup")
+}
+
+/// All backups of `db_path`, newest first.
+pub fn list_backups(db_path: &Path) -> Result<Vec<BackupEntry>> {
+    let dir = match db_path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let prefix = backup_prefix(db_path);
+    let mut entries = Vec::new();
+    let read_dir = match fs::read_dir(&dir) {
+        Ok(r) => r,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(entries),
+        Err(e) => return Err(e).context("Failed to list backups"),
+    };
+    for entry in read_dir.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Some(stamp) = name.strip_prefix(&prefix)
+            && let Ok(taken_at) = NaiveDateTime::parse_from_str(stamp, STAMP_FORMAT)
+        {
+            entries.push(BackupEntry {
+                path: entry.path(),
+                taken_at,
+                legacy: false,
+            });
+        }
+    }
+    let legacy = legacy_backup_path(db_path);
+    if let Ok(modified) = fs::metadata(&legacy).and_then(|m| m.modified()) {
+        entries.push(BackupEntry {
+            path: legacy,
+            taken_at: DateTime::<Local>::from(modified)
+                .naive_local()
+                .trunc_subsecs(3),
+            legacy: true,
+        });
+    }
+    // Timestamped names have millisecond precision; on a tie the legacy file is older.
+    entries.sort_by(|a, b| b.taken_at.cmp(&a.taken_at).then(a.legacy.cmp(&b.legacy)));
+    Ok(entries)
+}
+
+/// Copies the current database into a new timestamped backup, written as durably as the
+/// database itself, and prunes old ones down to `keep`. Skipped when the file does not exist, `keep` is 0, or the newest backup already has
+/// the same content. Returns the new backup's path.
+pub fn create_backup(db_path: &Path, keep: usize) -> Result<Option<PathBuf>> {
+    if keep == 0 || !db_path.exists() {
+        return Ok(None);
+    }
+    let current = fs::read(db_path).context("Failed to read the database file")?;
+    let existing = list_backups(db_path)?;
+    if let Some(newest) = existing.iter().find(|e| !e.legacy)
+        && fs::read(&newest.path).is_ok_and(|data| data == current)
+    {
+        return Ok(None);
+    }
+
+    let mut taken_at = Local::now().naive_local();
+    let mut path = backup_path_at(db_path, taken_at);
+    while path.exists() {
+        taken_at += chrono::Duration::milliseconds(1);
+        path = backup_path_at(db_path, taken_at);
+    }
+    atomic_write::write_atomic(&path, &current)
+        .with_context(|| format!("Failed to write backup '{}'", path.display()))?;
+
+    prune_backups(db_path, keep)?;
+    Ok(Some(path))
+}
+
+fn backup_path_at(db_path: &Path, taken_at: NaiveDateTime) -> PathBuf {
+    db_path.with_file_name(format!(
+        "{}{}",
+        backup_prefix(db_path),
+        taken_at.format(STAMP_FORMAT)
+    ))
+}
+
+/// Deletes timestamped backups beyond the newest `keep`.
+pub fn prune_backups(db_path: &Path, keep: usize) -> Result<()> {
+    let generations: Vec<BackupEntry> = list_backups(db_path)?
+        .into_iter()
+        .filter(|e| !e.legacy)
+        .collect();
+    for old in generations.iter().skip(keep) {
+        fs::remove_file(&old.path)
+            .with_context(|| format!("Failed to remove old backup '{}'", old.path.display()))?;
+    }
+    Ok(())
+}
+
+/// What replacing `current` with `target` would change. Tasks are matched by UUID, then by
+/// short ID (backups from before UUIDs existed get fresh ones on every load).
+pub fn diff_tasks(current: &[Task], target: &[Task]) -> RestoreDiff {
+    let mut diff = RestoreDiff::default();
+    let mut matched = vec![false; current.len()];
+    let by_uuid = |t: &Task| current.iter().position(|c| c.uuid == t.uuid);
+    let by_id = |t: &Task, matched: &[bool]| {
+        current.iter().enumerate().position(|(i, c)| {
+            !matched[i] && c.id == t.id && !target.iter().any(|o| o.uuid == c.uuid)
+        })
+    };
+    for task in target {
+        match by_uuid(task).or_else(|| by_id(task, &matched)) {
+            Some(i) => {
+                matched[i] = true;
+                if current[i].text != task.text
+                    || current[i].date != task.date
+                    || current[i].time != task.time
+                    || current[i].done != task.done
+                    || current[i].priority != task.priority
+                {
+                    diff.changed.push((current[i].clone(), task.clone()));
+                }
+            }
+            None => diff.added.push(task.clone()),
+        }
+    }
+    diff.removed = current
+        .iter()
+        .zip(&matched)
+        .filter(|(_, m)| !**m)
+        .map(|(c, _)| c.clone())
+        .collect();
+    diff
+}
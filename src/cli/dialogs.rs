@@ -8,6 +8,8 @@ use crossterm::{
 };
 use std::io::{self, Write};
 
+use crate::model::TaskId;
+
 use super::HandlerCLI;
 
 impl HandlerCLI {
@@ -49,7 +51,7 @@ impl HandlerCLI {
         }
     }
 
-    pub(crate) fn print_delete_confirmation_dialog(task_text: &str, task_id: u8) -> String {
+    pub(crate) fn print_delete_confirmation_dialog(task_text: &str, task_id: TaskId) -> String {
         let max_line_width = Self::get_max_line_width();
         const LEFT_MARGIN: usize = 4;
         const RIGHT_MARGIN: usize = 4;
@@ -1,10 +1,11 @@
-//! Crash-safe draft autosave for the interactive editor.
+//! Crash-safe draft autosave for the interactive editor. How often it saves is the
+//! `editor.autosave_secs` setting (see [`crate::config`]).
 
 use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
-const AUTOSAVE_INTERVAL_MS: u64 = 3000;
+use crate::{config, db_format};
 
 /// Optional inputs the caller can plumb into the editor:
 /// the colored first-line prompt, the autosave destination and its task key.
@@ -32,7 +33,10 @@ pub(super) fn tick(
     let Some(draft_path) = &extras.draft_path else {
         return;
     };
-    if last_autosave.elapsed() < Duration::from_millis(AUTOSAVE_INTERVAL_MS) {
+    let Some(interval) = config::current().autosave_interval() else {
+        return;
+    };
+    if last_autosave.elapsed() < interval {
         return;
     }
     let joined = lines.join("\n");
@@ -55,8 +59,10 @@ pub(super) fn cleanup(extras: &EditorExtras) {
     }
 }
 
-pub fn path_for(dir: &Path) -> PathBuf {
-    dir.join("editor.draft")
+/// Draft file of the database at `db_path` (`work.json` → `work.json.draft`), so each list
+/// keeps its own unsaved edits.
+pub fn path_for(db_path: &Path) -> PathBuf {
+    db_format::sibling_path(db_path, ".draft")
 }
 
 pub fn write(path: &Path, key: &str, text: &str) -> Result<()> {
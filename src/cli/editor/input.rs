@@ -414,10 +414,8 @@ pub(super) fn handle_mouse(
             state.goto(r, c, vw);
             history.break_run();
         }
-        MouseEventKind::Up(MouseButton::Left) => {
-            if state.anchor == Some((state.row, state.col)) {
-                state.anchor = None;
-            }
+        MouseEventKind::Up(MouseButton::Left) if state.anchor == Some((state.row, state.col)) => {
+            state.anchor = None;
         }
         MouseEventKind::ScrollUp => {
             let (vwm, av, vlen) = view::layout_metrics_for_buffer(&state.lines, ctx.prompt_width);
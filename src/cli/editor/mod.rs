@@ -221,8 +221,8 @@ impl HandlerCLI {
     }
 
     #[cfg(feature = "interactive")]
-    pub(crate) fn draft_path_for(dir: &std::path::Path) -> std::path::PathBuf {
-        draft::path_for(dir)
+    pub(crate) fn draft_path_for(db_path: &std::path::Path) -> std::path::PathBuf {
+        draft::path_for(db_path)
     }
 
     #[cfg(feature = "interactive")]
@@ -110,6 +110,10 @@ const HELP_ROWS: &[HelpRow] = &[
         "+2w, +10d5w",
         "relative to current due date (today if none)",
     ),
+    HelpRow::Pair(
+        "2d@14:00, 2d 14:00",
+        "optional due time (24-hour); @9:30 = today",
+    ),
     HelpRow::Pair("_", "clear the due date"),
     HelpRow::Note("Recognized tokens are colored: green = today/future, red = past."),
 ];
@@ -168,30 +168,21 @@ pub fn split_multi_line_prefill(prefill: &str) -> Vec<String> {
     normalized.split('\n').map(|s| s.to_string()).collect()
 }
 
-/// Character length of a leading date prefix on the first logical line.
-/// Returns 0 if the leading whitespace-delimited token is not a valid CLI date.
+/// Character length of a leading date prefix on the first logical line, including an optional
+/// time (`2d@14:00`, `31-12-2026 18:00`). Returns 0 if the line does not start with a valid CLI date.
 /// `relative_edit_base` is the task due date before edit (`+`-prefixed relative tokens).
 pub fn leading_date_char_len(line: &str, relative_edit_base: Option<NaiveDate>) -> usize {
-    let token: String = line.chars().take_while(|c| !c.is_whitespace()).collect();
-    if token.is_empty() {
-        return 0;
-    }
-    if crate::parse_cli_date_for_edit(&token, relative_edit_base).is_ok() {
-        token.chars().count()
-    } else {
-        0
-    }
+    crate::parse_leading_due(line, relative_edit_base, None).map_or(0, |(_, _, len)| len)
 }
 
-/// `true` if the first line starts with a valid CLI date strictly before today.
+/// `true` if the first line starts with a valid CLI date strictly before today, or today at a
+/// time that has already passed.
 pub fn leading_date_is_past(line: &str, relative_edit_base: Option<NaiveDate>) -> bool {
-    let token: String = line.chars().take_while(|c| !c.is_whitespace()).collect();
-    if token.is_empty() {
-        return false;
-    }
-    match crate::parse_cli_date_for_edit(&token, relative_edit_base) {
-        Ok(d) => d < Local::now().date_naive(),
-        Err(_) => false,
+    let now = Local::now().naive_local();
+    match crate::parse_leading_due(line, relative_edit_base, None) {
+        Some((d, Some(t), _)) => d.and_time(t) < now,
+        Some((d, None, _)) => d < now.date(),
+        None => false,
     }
 }
 
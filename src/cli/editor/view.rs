@@ -1,5 +1,5 @@
 //! Rendering of the alternate-screen editor: soft-wrap visuals, selection
-//! and date-header coloring, footer with scroll indicators and dirty marker.
+//! date-header and tag coloring, footer with scroll indicators and dirty marker.
 
 use anyhow::Result;
 use chrono::NaiveDate;
@@ -13,6 +13,7 @@ use crossterm::{
 use std::io::{self, Write};
 
 use super::text_ops;
+use crate::parser::tags::tag_char_spans;
 
 pub(super) const ML_FOOTER: &str = "^S save  ·  ^G help  ·  Esc cancel";
 /// Lower the footer vs the prior 5-row band (wide: less padding under footer; compact: more gap above).
@@ -149,8 +150,7 @@ fn editor_text_layout(term_cols: usize, prompt_width: usize) -> (usize, usize) {
     let side = prompt_width.max(ML_MIN_HPAD);
     let visible_width = term_cols
         .saturating_sub(2 * side)
-        .max(1)
-        .min(ML_MAX_TEXT_COLS);
+        .clamp(1, ML_MAX_TEXT_COLS);
     let text_left = term_cols.saturating_sub(visible_width) / 2;
     let content_left = text_left.saturating_sub(prompt_width);
     (visible_width, content_left)
@@ -178,7 +178,8 @@ pub(super) fn compute_visuals(lines: &[String], vw: usize) -> Vec<(usize, String
 }
 
 /// Print one visual chunk with selection + optional validation colors and
-/// a bold date highlight (green, or red if the leading date is before today) on the first line.
+/// a bold date highlight (green, or red if the leading date is before today) on the first line,
+/// and `#tag` tokens highlighted anywhere in the buffer.
 #[allow(clippy::too_many_arguments)]
 fn print_visual_chunk(
     stdout: &mut io::Stdout,
@@ -233,7 +234,26 @@ fn print_visual_chunk(
     };
     let date_past = buf_idx == 0 && text_ops::leading_date_is_past(&lines[0], relative_date_base);
 
-    let emit = |stdout: &mut io::Stdout, text: &str, selected: bool, is_date: bool| -> Result<()> {
+    // Tag spans clipped to this chunk; tags are not colored in validated (single-line) prompts.
+    let tags_in_chunk: Vec<(usize, usize)> = if full_text_valid.is_none() {
+        tag_char_spans(&lines[buf_idx])
+            .into_iter()
+            .filter_map(|(s, e)| {
+                let a = s.max(start_char);
+                let b = e.min(chunk_end);
+                (a < b).then(|| (a - start_char, b - start_char))
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let emit = |stdout: &mut io::Stdout,
+                text: &str,
+                selected: bool,
+                is_date: bool,
+                is_tag: bool|
+     -> Result<()> {
         if text.is_empty() {
             return Ok(());
         }
@@ -245,6 +265,8 @@ fn print_visual_chunk(
             } else {
                 stdout.queue(Print(text.green().bold()))?;
             }
+        } else if is_tag {
+            stdout.queue(Print(text.bright_blue()))?;
         } else {
             match full_text_valid {
                 Some(true) => stdout.queue(Print(text.green()))?,
@@ -255,7 +277,7 @@ fn print_visual_chunk(
         Ok(())
     };
 
-    // Break the chunk at date / selection boundaries so each run is
+    // Break the chunk at date / tag / selection boundaries so each run is
     // homogeneous and can be printed with a single color.
     let mut stops: Vec<usize> = vec![0, chunk_len];
     if date_end_in_chunk > 0 && date_end_in_chunk < chunk_len {
@@ -269,6 +291,10 @@ fn print_visual_chunk(
             stops.push(b);
         }
     }
+    for &(a, b) in &tags_in_chunk {
+        stops.push(a);
+        stops.push(b);
+    }
     stops.sort_unstable();
     stops.dedup();
 
@@ -283,7 +309,8 @@ fn print_visual_chunk(
             None => false,
         };
         let is_date = date_end_in_chunk > 0 && b <= date_end_in_chunk;
-        emit(stdout, &text, selected, is_date)?;
+        let is_tag = tags_in_chunk.iter().any(|&(ts, te)| a >= ts && b <= te);
+        emit(stdout, &text, selected, is_date, is_tag)?;
     }
     Ok(())
 }
@@ -1,5 +1,12 @@
+use std::fmt::Write;
+
+use chrono::{DateTime, Utc};
 use colored::*;
 
+use crate::config;
+use crate::model::{Priority, Task, TaskId};
+use crate::parser::tags::tag_char_spans;
+
 use super::HandlerCLI;
 
 impl HandlerCLI {
@@ -32,6 +39,98 @@ impl HandlerCLI {
             .unwrap_or_else(|| "empty".to_string())
     }
 
+    /// `DD-MM-YYYY`, followed by ` HH:MM` when the task has a due time; `empty` without a date.
+    /// The same text is accepted back by `--date` and the editor's first line.
+    #[doc(hidden)]
+    pub fn format_due_for_display(
+        date: Option<chrono::NaiveDate>,
+        time: Option<chrono::NaiveTime>,
+    ) -> String {
+        match (date, time) {
+            (Some(d), Some(t)) => format!("{} {}", d.format("%d-%m-%Y"), t.format("%H:%M")),
+            _ => Self::format_date_for_display(date),
+        }
+    }
+
+    /// Compact due date used by `list` and `add` output, e.g. `7-jan-26` or `7-jan-26 09:30`,
+    /// or the `display.date_format` pattern when one is configured and can format a date.
+    pub(crate) fn format_short_due(
+        date: chrono::NaiveDate,
+        time: Option<chrono::NaiveTime>,
+    ) -> String {
+        if let Some(pattern) = config::current().date_format() {
+            let mut formatted = String::new();
+            if write!(formatted, "{}", date.format(&pattern)).is_ok() {
+                if let Some(t) = time {
+                    formatted = format!("{formatted} {}", t.format("%H:%M"));
+                }
+                return formatted;
+            }
+        }
+        let day = date.format("%-d");
+        let month = date.format("%b").to_string().to_lowercase();
+        let year = date.format("%y");
+        match time {
+            Some(t) => format!("{day}-{month}-{year} {}", t.format("%H:%M")),
+            None => format!("{day}-{month}-{year}"),
+        }
+    }
+
+    /// Status column glyph for an open, unblocked task.
+    pub(crate) fn priority_glyph(priority: Priority) -> ColoredString {
+        match priority {
+            Priority::High => "!".red().bold(),
+            Priority::Medium => "p".truecolor(255, 165, 0).bold(),
+            Priority::Low => "↓".truecolor(100, 150, 220),
+            Priority::None => "•".normal(),
+        }
+    }
+
+    /// Short age of `ts` relative to `now`: `just now`, `5m ago`, `3h ago`, `2d ago`, `6w ago`,
+    /// `4mo ago`, `2y ago`. Future timestamps (clock skew) are shown as `just now`.
+    #[doc(hidden)]
+    pub fn format_age(ts: DateTime<Utc>, now: DateTime<Utc>) -> String {
+        let secs = (now - ts).num_seconds();
+        if secs < 60 {
+            return "just now".to_string();
+        }
+        let mins = secs / 60;
+        let hours = mins / 60;
+        let days = hours / 24;
+        let amount = if mins < 60 {
+            format!("{mins}m")
+        } else if hours < 24 {
+            format!("{hours}h")
+        } else if days < 14 {
+            format!("{days}d")
+        } else if days < 60 {
+            format!("{}w", days / 7)
+        } else if days < 365 {
+            format!("{}mo", days / 30)
+        } else {
+            format!("{}y", days / 365)
+        };
+        format!("{amount} ago")
+    }
+
+    /// `created 3d ago · updated 2h ago · done 1h ago` for `rusk list --timestamps`; missing
+    /// timestamps (legacy tasks) are omitted. Returns `None` when the task has none.
+    pub(crate) fn format_task_timestamps(task: &Task, now: DateTime<Utc>) -> Option<String> {
+        let parts: Vec<String> = [
+            ("created", task.created_at),
+            ("updated", task.updated_at),
+            ("done", task.completed_at),
+        ]
+        .into_iter()
+        .filter_map(|(label, ts)| ts.map(|ts| format!("{label} {}", Self::format_age(ts, now))))
+        .collect();
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(" · "))
+        }
+    }
+
     pub(crate) fn print_task_text_with_wrapping(prefix: &str, text: &str) {
         let max_line_width = Self::get_max_line_width();
         const LEFT_MARGIN: usize = 4;
@@ -57,12 +156,10 @@ impl HandlerCLI {
         let mut prefix = String::new();
         let mut suffix = String::new();
         let mut chars = s.chars().peekable();
-        let mut in_ansi = false;
         let mut ansi_seq = String::new();
 
         while let Some(&ch) = chars.peek() {
             if ch == '\x1b' {
-                in_ansi = true;
                 ansi_seq.push(ch);
                 chars.next();
                 while let Some(&next) = chars.peek() {
@@ -71,12 +168,9 @@ impl HandlerCLI {
                     if next == 'm' {
                         prefix.push_str(&ansi_seq);
                         ansi_seq.clear();
-                        in_ansi = false;
                         break;
                     }
                 }
-            } else if in_ansi {
-                break;
             } else {
                 break;
             }
@@ -262,7 +356,26 @@ impl HandlerCLI {
         }
     }
 
-    pub(crate) fn print_not_found_ids(not_found: &[u8]) {
+    /// Colors `#tag` tokens in one display line (plain text in, ANSI out).
+    pub(crate) fn highlight_tags(line: &str) -> String {
+        let spans = tag_char_spans(line);
+        if spans.is_empty() {
+            return line.to_string();
+        }
+        let chars: Vec<char> = line.chars().collect();
+        let mut out = String::with_capacity(line.len() + spans.len() * 10);
+        let mut pos = 0;
+        for (start, end) in spans {
+            out.extend(&chars[pos..start]);
+            let tag: String = chars[start..end].iter().collect();
+            out.push_str(&tag.bright_blue().to_string());
+            pos = end;
+        }
+        out.extend(&chars[pos..]);
+        out
+    }
+
+    pub(crate) fn print_not_found_ids(not_found: &[TaskId]) {
         if !not_found.is_empty() {
             let list = not_found
                 .iter()
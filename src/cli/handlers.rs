@@ -1,24 +1,135 @@
-#[cfg(feature = "interactive")]
-use crate::parse_cli_date_for_edit;
+use crate::archive;
+use crate::backup;
+use crate::config::{self, Config, Scope};
+use crate::doctor;
+use crate::journal::{self, EntryKind, JournalEntry};
+use crate::lock::{DbLock, lock_timeout};
+use crate::merge::{self, ConflictKind};
 use crate::parser::date::is_cli_date_clear_value;
-use crate::{Task, TaskManager, validate_cli_date_edit_arg};
-use anyhow::Result;
-use chrono::Datelike;
+#[cfg(feature = "interactive")]
+use crate::parser::date::parse_leading_due;
+use crate::storage::{self, DB_DIR_NAME, RenumberOrder, find_db_dir, list_file_name, list_names};
+use crate::sync::{self, Change, Direction, Side};
+#[cfg(feature = "interactive")]
+use crate::{EditConflict, TaskEdit};
+use crate::{
+    NewTaskOptions, Priority, Recurrence, RecurrenceMode, SubtaskPolicy, Task, TaskId, TaskManager,
+    validate_cli_date_edit_arg,
+};
+use crate::{parse_cli_date_for_edit, parse_cli_due_for_edit};
+use anyhow::{Context, Result};
+use chrono::{Local, TimeZone, Utc};
 use colored::*;
+use std::path::Path;
 
 use super::HandlerCLI;
 #[cfg(feature = "interactive")]
 use super::editor::EditorExtras;
 
+/// Number of UUID characters shown by `rusk list --uuid`.
+const UUID_SHORT_LEN: usize = 8;
+
+/// Display options for [`HandlerCLI::handle_list_tasks`].
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    /// Only the first line of each task, trailing punctuation stripped (`list -c`).
+    pub compact: bool,
+    /// Short UUID column after the ID (`list -u`).
+    pub show_uuid: bool,
+    /// Created / updated / completed ages under each task (`list -t`).
+    pub show_timestamps: bool,
+    /// Only tasks carrying every one of these tags (`list --tag x`), lowercased, no `#`.
+    pub tags: Vec<String>,
+    /// Hide tasks carrying any of these tags (`list --tag -x`).
+    pub exclude_tags: Vec<String>,
+    /// Hide tasks still waiting on an open blocker (`list --hide-blocked`).
+    pub hide_blocked: bool,
+    /// Display order (`list --sort`); storage order when `None`.
+    pub sort: Option<ListSort>,
+    /// Tasks whose start date is still ahead (`list --waiting` / `--all`).
+    pub waiting: WaitingFilter,
+}
+
+/// Which tasks with a future start date (`--wait`) `rusk list` shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WaitingFilter {
+    /// Leave them out (default).
+    #[default]
+    Hide,
+    /// Show them with the rest (`list --all`).
+    Include,
+    /// Show only them (`list --waiting`).
+    Only,
+}
+
+/// A task whose start date passed at most this many days ago is highlighted as newly active.
+const NEWLY_ACTIVE_DAYS: i64 = 3;
+
+/// Sort keys for `rusk list --sort`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ListSort {
+    /// Due date, then time of day; tasks without a time come last on their day, undated tasks
+    /// after all dated ones.
+    Due,
+    /// Highest priority first, then by due date.
+    Priority,
+}
+
+/// Due date, due time and text read back from the editor buffer.
+#[cfg(feature = "interactive")]
+type EditedTask = (Option<chrono::NaiveDate>, Option<chrono::NaiveTime>, String);
+
+/// One line group of `rusk list`: a task and the tree guides drawn before its text.
+struct TreeRow<'a> {
+    task: &'a Task,
+    /// Drawn before the first line, e.g. `│  ├─ `.
+    branch: String,
+    /// Drawn before continuation lines so the guides stay connected.
+    continuation: String,
+    /// Compact mode: `(done, total)` subtasks folded into this top-level task.
+    collapsed: Option<(usize, usize)>,
+}
+
+impl<'a> TreeRow<'a> {
+    fn plain(task: &'a Task, collapsed: Option<(usize, usize)>) -> Self {
+        Self {
+            task,
+            branch: String::new(),
+            continuation: String::new(),
+            collapsed,
+        }
+    }
+}
+
+impl ListOptions {
+    /// Whether `task` passes the list filters.
+    pub fn matches(&self, task: &Task) -> bool {
+        self.tags.iter().all(|t| task.tags.contains(t))
+            && !self.exclude_tags.iter().any(|t| task.tags.contains(t))
+    }
+
+    /// Whether `task` passes the start-date filter on `today`.
+    fn shows_waiting(&self, task: &Task, today: chrono::NaiveDate) -> bool {
+        match self.waiting {
+            WaitingFilter::Hide => !task.is_waiting(today),
+            WaitingFilter::Include => true,
+            WaitingFilter::Only => task.is_waiting(today),
+        }
+    }
+
+    fn is_filtered(&self) -> bool {
+        !self.tags.is_empty()
+            || !self.exclude_tags.is_empty()
+            || self.hide_blocked
+            || self.waiting == WaitingFilter::Only
+    }
+}
+
 impl HandlerCLI {
-    fn print_added_task(task: &Task) {
+    fn print_added_task(tm: &TaskManager, task: &Task) {
         let prefix = if let Some(date) = task.date {
-            let today = chrono::Local::now().date_naive();
-            let day = date.day();
-            let month = date.format("%b").to_string().to_lowercase();
-            let year = date.format("%y").to_string();
-            let date_str = format!("{}-{}-{}", day, month, year);
-            let colored_date = if date < today {
+            let date_str = Self::format_short_due(date, task.time);
+            let colored_date = if task.is_overdue(chrono::Local::now().naive_local()) {
                 date_str.red()
             } else {
                 date_str.cyan()
@@ -28,48 +139,134 @@ impl HandlerCLI {
             format!("{} {}:", "Added task:".green(), task.id)
         };
         Self::print_task_text_with_wrapping(&prefix, &task.text.bold().to_string());
+        if let Some(parent) = task
+            .parent
+            .and_then(|p| tm.tasks().iter().find(|t| t.uuid == p))
+        {
+            println!(
+                " {} {}",
+                "- subtask of:".cyan(),
+                parent.id.to_string().bold()
+            );
+        }
+        if let Some(rule) = &task.recurrence {
+            println!(
+                " {} {}",
+                "- repeats:".cyan(),
+                Self::describe_recurrence(rule).bold()
+            );
+        }
+        if let Some(wait) = task.wait {
+            println!(
+                " {} {}",
+                "- hidden until:".cyan(),
+                Self::format_date_for_display(Some(wait)).bold()
+            );
+        }
+    }
+
+    fn describe_recurrence(rule: &Recurrence) -> String {
+        let from = match rule.mode {
+            RecurrenceMode::FromDue => "due date",
+            RecurrenceMode::FromCompletion => "completion",
+        };
+        format!("every {} (from {from})", rule.every)
+    }
+
+    /// `rusk recur ID INTERVAL|_`.
+    pub fn handle_set_recurrence(
+        tm: &mut TaskManager,
+        id: TaskId,
+        rule: Option<Recurrence>,
+    ) -> Result<()> {
+        let changed = tm.set_recurrence(id, rule.clone())?;
+        match (&rule, changed) {
+            (Some(r), true) => println!(
+                "{} {} {}",
+                "Task".green(),
+                id.to_string().bold(),
+                format!("repeats {}", Self::describe_recurrence(r)).green()
+            ),
+            (None, true) => println!("{} {}", "Task no longer repeats:".green(), id),
+            (_, false) => println!("{} {}", "Task unchanged:".magenta(), id),
+        }
+        Ok(())
+    }
+
+    /// `rusk wait ID DATE|_`. A leading `+` counts from the current start date.
+    pub fn handle_set_wait(tm: &mut TaskManager, id: TaskId, value: &str) -> Result<()> {
+        let idx = tm
+            .find_task_by_id(id)
+            .with_context(|| format!("Task {id} not found"))?;
+        let wait = if is_cli_date_clear_value(value) {
+            None
+        } else {
+            Some(parse_cli_date_for_edit(value, tm.tasks()[idx].wait)?)
+        };
+        match (wait, tm.set_wait(id, wait)?) {
+            (Some(w), true) => println!(
+                "{} {} {}",
+                "Task".green(),
+                id.to_string().bold(),
+                format!("hidden until {}", Self::format_date_for_display(Some(w))).green()
+            ),
+            (None, true) => println!("{} {}", "Task no longer waits:".green(), id),
+            (_, false) => println!("{} {}", "Task unchanged:".magenta(), id),
+        }
+        Ok(())
     }
 
     pub fn handle_add_task(
         tm: &mut TaskManager,
         text: Vec<String>,
         date: Option<String>,
+        mut options: NewTaskOptions,
     ) -> Result<()> {
-        tm.add_task(text, date)?;
+        let date = match date {
+            None => None,
+            Some(d) => {
+                let (date, time) = parse_cli_due_for_edit(&d, None, None)?;
+                options.time = time;
+                Some(date)
+            }
+        };
+        tm.add_task_with_options(text.join(" "), date, &options)?;
         let task = tm.tasks().last().unwrap();
-        Self::print_added_task(task);
+        Self::print_added_task(tm, task);
         Ok(())
     }
 
     /// Interactive TUI: no inline task text; optional `-d` pre-seeds the first line with that due date.
     #[cfg(feature = "interactive")]
-    pub fn handle_add_task_interactive(tm: &mut TaskManager, date: Option<String>) -> Result<()> {
-        let draft_dir = TaskManager::get_db_dir();
-        let draft_path = Self::draft_path_for(&draft_dir);
+    pub fn handle_add_task_interactive(
+        tm: &mut TaskManager,
+        date: Option<String>,
+        mut options: NewTaskOptions,
+    ) -> Result<()> {
+        let draft_path = Self::draft_path_for(&TaskManager::resolve_db_path());
         let draft_key = "new-task".to_string();
 
         let base_prefill = if let Some(ref d) = date {
-            let seed = parse_cli_date_for_edit(d, None)?;
-            format!("{} ", seed.format("%d-%m-%Y"))
+            let (seed, time) = parse_cli_due_for_edit(d, None, None)?;
+            format!("{} ", Self::format_due_for_display(Some(seed), time))
         } else {
             String::new()
         };
 
         let mut prefill_owned = base_prefill.clone();
-        if draft_path.exists() {
-            if let Some(text) = Self::read_draft_for(&draft_path, &draft_key) {
-                if text != base_prefill {
-                    let prompt = format!(
-                        "{}{}",
-                        "Restore unsaved draft for new task ".truecolor(255, 165, 0),
-                        "? [y/N]: ".truecolor(255, 165, 0)
-                    );
-                    if Self::read_confirmation(&prompt)? {
-                        prefill_owned = text;
-                    } else {
-                        let _ = std::fs::remove_file(&draft_path);
-                    }
-                }
+        if draft_path.exists()
+            && let Some(text) = Self::read_draft_for(&draft_path, &draft_key)
+            && text != base_prefill
+        {
+            let prompt = format!(
+                "{}{}",
+                "Restore unsaved draft for new task ".truecolor(255, 165, 0),
+                "? [y/N]: ".truecolor(255, 165, 0)
+            );
+            if Self::read_confirmation(&prompt)? {
+                prefill_owned = text;
+            } else {
+                let _ = std::fs::remove_file(&draft_path);
             }
         }
 
@@ -80,74 +277,155 @@ impl HandlerCLI {
             ..Default::default()
         };
 
-        let edited = Self::run_multi_line_editor(
-            "    ",
-            &prefill_owned,
-            false,
-            None,
-            false,
-            extras,
-        )?;
+        tm.release_lock();
+        let edited =
+            Self::run_multi_line_editor("    ", &prefill_owned, false, None, false, extras)?;
+        tm.reacquire_lock()?;
+        if tm.changed_on_disk()? {
+            // Another process saved while the editor was open; add on top of its version.
+            tm.reload()?;
+        }
 
         if edited.trim().is_empty() {
             return Ok(());
         }
 
-        let (parsed_date, stripped) = Self::extract_leading_date(&edited, None);
+        let (parsed_date, parsed_time, stripped) = Self::extract_leading_date(&edited, None, None);
         if stripped.trim().is_empty() {
             anyhow::bail!("Task text cannot be empty");
         }
 
-        tm.add_task_with_parsed_date(stripped, parsed_date)?;
+        options.time = parsed_time;
+        tm.add_task_with_options(stripped, parsed_date, &options)?;
         let task = tm.tasks().last().unwrap();
-        Self::print_added_task(task);
+        Self::print_added_task(tm, task);
         Ok(())
     }
 
-    pub fn handle_delete_tasks(tm: &mut TaskManager, ids: Vec<u8>, done: bool) -> Result<()> {
+    /// `policy` decides what happens to subtasks of deleted tasks (`del --cascade` / `--reparent`).
+    pub fn handle_delete_tasks(
+        tm: &mut TaskManager,
+        ids: Vec<TaskId>,
+        done: bool,
+        policy: Option<SubtaskPolicy>,
+    ) -> Result<()> {
         if done && ids.is_empty() {
             Self::delete_all_done(tm)
         } else if !ids.is_empty() {
-            Self::delete_by_ids(tm, ids)
+            Self::check_subtask_policy(tm, &ids, policy)?;
+            Self::delete_by_ids(tm, ids, policy)
         } else {
             println!("{}", "Please specify id(s) or --done.".yellow());
             Ok(())
         }
     }
 
+    /// `rusk archive`: moves tasks (or all done ones) to the archive.
+    pub fn handle_archive(tm: &mut TaskManager, ids: Vec<TaskId>, done: bool) -> Result<()> {
+        if done && ids.is_empty() {
+            let moved = tm.archive_done()?;
+            if moved == 0 {
+                println!("{}", "No done tasks to archive.".yellow());
+            } else {
+                println!(
+                    "{} {} {}",
+                    "Archived".green(),
+                    moved.to_string().white(),
+                    "done task(s). See them with `rusk list --archived`.".green()
+                );
+            }
+        } else if !ids.is_empty() {
+            let (moved, not_found) = tm.archive_tasks(ids)?;
+            if !moved.is_empty() {
+                let list: Vec<String> = moved.iter().map(|id| id.to_string()).collect();
+                println!(
+                    "{} {}{}",
+                    "Archived task(s)".green(),
+                    list.join(", ").white(),
+                    ". See them with `rusk list --archived`.".green()
+                );
+            }
+            Self::print_not_found_ids(&not_found);
+        } else {
+            println!("{}", "Please specify id(s) or --done.".yellow());
+        }
+        Ok(())
+    }
+
+    /// `rusk unarchive`: brings archived tasks back, reporting IDs that changed on the way.
+    pub fn handle_unarchive(tm: &mut TaskManager, ids: Vec<TaskId>) -> Result<()> {
+        let (restored, not_found) = tm.unarchive(ids)?;
+        for (old, new) in &restored {
+            if old == new {
+                println!("{} {}", "Restored task".green(), new.to_string().white());
+            } else {
+                println!(
+                    "{} {} {} {}",
+                    "Restored archived task".green(),
+                    old.to_string().white(),
+                    "as task".green(),
+                    new.to_string().white()
+                );
+            }
+        }
+        if !not_found.is_empty() {
+            let list: Vec<String> = not_found.iter().map(|id| id.to_string()).collect();
+            println!("{} {}", "Not in the archive:".yellow(), list.join(" "));
+        }
+        Ok(())
+    }
+
+    /// `rusk list --archived`: the archive, shown like the task list.
+    pub fn handle_list_archived(tm: &TaskManager, mut options: ListOptions) -> Result<()> {
+        let archived = tm.archived_tasks()?;
+        if archived.is_empty() {
+            println!("{}", "The archive is empty.".yellow());
+            return Ok(());
+        }
+        // Start dates no longer matter for archived tasks.
+        if options.waiting == WaitingFilter::Hide {
+            options.waiting = WaitingFilter::Include;
+        }
+        Self::handle_list_tasks(&archived, &options);
+        Ok(())
+    }
+
     #[cfg(feature = "interactive")]
     fn interactive_edit_text(
         current: &str,
-        task_id: u8,
+        task_id: TaskId,
         task_date: Option<chrono::NaiveDate>,
+        task_time: Option<chrono::NaiveTime>,
         allow_skip: bool,
-    ) -> Result<Option<(Option<chrono::NaiveDate>, String)>> {
-        let draft_dir = crate::TaskManager::get_db_dir();
-        let draft_path = Self::draft_path_for(&draft_dir);
+    ) -> Result<Option<EditedTask>> {
+        let draft_path = Self::draft_path_for(&crate::TaskManager::resolve_db_path());
         let draft_key = format!("task-{}", task_id);
 
-        // Prefill embeds the task date as an editable prefix on the first line.
+        // Prefill embeds the task date (and time) as an editable prefix on the first line.
         let base_prefill = if let Some(date) = task_date {
-            format!("{} {}", date.format("%d-%m-%Y"), current)
+            format!(
+                "{} {}",
+                Self::format_due_for_display(Some(date), task_time),
+                current
+            )
         } else {
             current.to_string()
         };
         let mut prefill_owned = base_prefill.clone();
-        if draft_path.exists() {
-            if let Some(text) = Self::read_draft_for(&draft_path, &draft_key) {
-                if text != base_prefill {
-                    let prompt = format!(
-                        "{} {} {} ",
-                        "Restore unsaved draft for task".truecolor(255, 165, 0),
-                        task_id.to_string().white(),
-                        "? [y/N]:".truecolor(255, 165, 0)
-                    );
-                    if Self::read_confirmation(&prompt)? {
-                        prefill_owned = text;
-                    } else {
-                        let _ = std::fs::remove_file(&draft_path);
-                    }
-                }
+        if draft_path.exists()
+            && let Some(text) = Self::read_draft_for(&draft_path, &draft_key)
+            && text != base_prefill
+        {
+            let prompt = format!(
+                "{} {} {} ",
+                "Restore unsaved draft for task".truecolor(255, 165, 0),
+                task_id.to_string().white(),
+                "? [y/N]:".truecolor(255, 165, 0)
+            );
+            if Self::read_confirmation(&prompt)? {
+                prefill_owned = text;
+            } else {
+                let _ = std::fs::remove_file(&draft_path);
             }
         }
 
@@ -163,7 +441,8 @@ impl HandlerCLI {
         if edited.trim().is_empty() {
             return Ok(None);
         }
-        let (parsed_date, stripped) = Self::extract_leading_date(&edited, task_date);
+        let (parsed_date, parsed_time, stripped) =
+            Self::extract_leading_date(&edited, task_date, task_time);
         // If user removed all body text but kept the date, fall back to the
         // original text so the task never becomes empty on a date-only edit.
         let new_text = if stripped.trim().is_empty() {
@@ -171,63 +450,62 @@ impl HandlerCLI {
         } else {
             stripped
         };
-        Ok(Some((parsed_date, new_text)))
+        Ok(Some((parsed_date, parsed_time, new_text)))
     }
 
+    /// Splits a leading due date, with an optional time (`2d@14:00`, `31-12-2026 18:00`), or the
+    /// clear marker `_` off the first line. Returns the due date and time (both `None` when
+    /// there is no date) and the remaining text.
     #[cfg(feature = "interactive")]
     fn extract_leading_date(
         edited: &str,
         task_date: Option<chrono::NaiveDate>,
-    ) -> (Option<chrono::NaiveDate>, String) {
+        task_time: Option<chrono::NaiveTime>,
+    ) -> EditedTask {
         let mut parts = edited.splitn(2, '\n');
         let first = parts.next().unwrap_or("");
         let rest = parts.next();
         let token: String = first.chars().take_while(|c| !c.is_whitespace()).collect();
         if token.is_empty() {
-            return (None, edited.to_string());
-        }
-        if is_cli_date_clear_value(&token) {
-            let token_chars = token.chars().count();
-            let mut tail = first.chars().skip(token_chars);
-            let peek = tail.clone().next();
-            if matches!(peek, Some(c) if c.is_whitespace()) {
-                tail.next();
-            }
-            let first_rest: String = tail.collect();
-            let new_text = match rest {
-                Some(r) => format!("{}\n{}", first_rest, r),
-                None => first_rest,
-            };
-            return (None, new_text);
-        }
-        match crate::parse_cli_date_for_edit(&token, task_date) {
-            Ok(date) => {
-                let token_chars = token.chars().count();
-                let mut tail = first.chars().skip(token_chars);
-                // Drop exactly one separating whitespace if present.
-                let peek = tail.clone().next();
-                if matches!(peek, Some(c) if c.is_whitespace()) {
-                    tail.next();
-                }
-                let first_rest: String = tail.collect();
-                let new_text = match rest {
-                    Some(r) => format!("{}\n{}", first_rest, r),
-                    None => first_rest,
-                };
-                (Some(date), new_text)
+            return (None, None, edited.to_string());
+        }
+        let (date, time, prefix_chars) = if is_cli_date_clear_value(&token) {
+            (None, None, token.chars().count())
+        } else {
+            match parse_leading_due(first, task_date, task_time) {
+                Some((date, time, len)) => (Some(date), time, len),
+                None => return (None, None, edited.to_string()),
             }
-            Err(_) => (None, edited.to_string()),
+        };
+        let mut tail = first.chars().skip(prefix_chars);
+        // Drop exactly one separating whitespace if present.
+        let peek = tail.clone().next();
+        if matches!(peek, Some(c) if c.is_whitespace()) {
+            tail.next();
         }
+        let first_rest: String = tail.collect();
+        let new_text = match rest {
+            Some(r) => format!("{}\n{}", first_rest, r),
+            None => first_rest,
+        };
+        (date, time, new_text)
     }
 
     #[cfg(feature = "interactive")]
-    fn handle_edit_tasks_interactive_internal(tm: &mut TaskManager, ids: Vec<u8>) -> Result<()> {
+    fn handle_edit_tasks_interactive_internal(
+        tm: &mut TaskManager,
+        ids: Vec<TaskId>,
+    ) -> Result<()> {
         let mut any_changed = false;
-        let mut edited: Vec<u8> = Vec::new();
-        let mut unchanged: Vec<u8> = Vec::new();
-        let mut not_found: Vec<u8> = Vec::new();
-        let mut edited_info: Vec<(u8, String)> = Vec::new();
-
+        let mut edited: Vec<TaskId> = Vec::new();
+        let mut unchanged: Vec<TaskId> = Vec::new();
+        let mut not_found: Vec<TaskId> = Vec::new();
+        let mut edited_info: Vec<(TaskId, String)> = Vec::new();
+        let mut edits: Vec<TaskEdit> = Vec::new();
+
+        // The editor may stay open for minutes; let other rusk processes write meanwhile and
+        // merge with whatever they saved afterwards.
+        tm.release_lock();
         let total_ids = ids.len();
         for (task_idx, id) in ids.iter().enumerate() {
             let is_last = task_idx == total_ids - 1;
@@ -236,15 +514,26 @@ impl HandlerCLI {
             if let Some(idx) = tm.find_task_by_id(*id) {
                 let current_text = tm.tasks()[idx].text.clone();
                 let current_date = tm.tasks()[idx].date;
-
-                match Self::interactive_edit_text(&current_text, *id, current_date, allow_skip) {
-                    Ok(Some((new_date, new_text))) => {
+                let current_time = tm.tasks()[idx].time;
+
+                match Self::interactive_edit_text(
+                    &current_text,
+                    *id,
+                    current_date,
+                    current_time,
+                    allow_skip,
+                ) {
+                    Ok(Some((new_date, new_time, new_text))) => {
                         let text_changed = new_text != current_text;
-                        let date_changed = new_date != current_date;
+                        let date_changed = new_date != current_date || new_time != current_time;
                         if text_changed || date_changed {
+                            let before = tm.tasks()[idx].clone();
                             let task = &mut tm.tasks_mut()[idx];
-                            task.text = new_text.clone();
+                            task.set_text(new_text.clone());
                             task.date = new_date;
+                            task.time = new_time;
+                            task.touch();
+                            edits.push((before, task.clone()));
                             edited.push(*id);
                             edited_info.push((*id, new_text.clone()));
                             any_changed = true;
@@ -270,16 +559,51 @@ impl HandlerCLI {
             }
         }
 
+        tm.reacquire_lock()?;
         if any_changed {
-            tm.save()?;
+            let conflicts = tm.save_edits(&edits)?;
+            Self::print_edit_conflicts(&conflicts);
         }
 
         Self::print_not_found_ids(&not_found);
         Ok(())
     }
 
+    /// Reports edits that were not saved because another process changed the same task.
+    #[cfg(feature = "interactive")]
+    fn print_edit_conflicts(conflicts: &[EditConflict]) {
+        let describe = |task: &Task| match task.date {
+            Some(date) => format!("{} {}", Self::format_short_due(date, task.time), task.text),
+            None => task.text.clone(),
+        };
+        for conflict in conflicts {
+            let id = conflict.mine.id;
+            match &conflict.theirs {
+                Some(theirs) => {
+                    println!(
+                        "{} task {} was changed by another rusk process while you were editing; \
+                         kept that version.",
+                        "Conflict:".red().bold(),
+                        id
+                    );
+                    println!("  {} {}", "saved:".dimmed(), describe(theirs));
+                }
+                None => println!(
+                    "{} task {} was deleted by another rusk process while you were editing.",
+                    "Conflict:".red().bold(),
+                    id
+                ),
+            }
+            println!(
+                "  {} {}",
+                "yours (not saved):".yellow(),
+                describe(&conflict.mine)
+            );
+        }
+    }
+
     #[cfg(feature = "interactive")]
-    pub fn handle_edit_tasks_interactive(tm: &mut TaskManager, ids: Vec<u8>) -> Result<()> {
+    pub fn handle_edit_tasks_interactive(tm: &mut TaskManager, ids: Vec<TaskId>) -> Result<()> {
         Self::handle_edit_tasks_interactive_internal(tm, ids)
     }
 
@@ -291,12 +615,13 @@ impl HandlerCLI {
             return Ok(());
         }
 
-        let confirmed = Self::read_confirmation(&format!(
-            "{}{}{}",
-            "Delete all done tasks (".truecolor(255, 165, 0),
-            done_count.to_string().white(),
-            ")? [y/N]: ".truecolor(255, 165, 0)
-        ))?;
+        let confirmed = !config::current().confirm_delete()
+            || Self::read_confirmation(&format!(
+                "{}{}{}",
+                "Delete all done tasks (".truecolor(255, 165, 0),
+                done_count.to_string().white(),
+                ")? [y/N]: ".truecolor(255, 165, 0)
+            ))?;
 
         if confirmed {
             let deleted = tm.delete_all_done()?;
@@ -331,16 +656,65 @@ impl HandlerCLI {
         Ok(())
     }
 
+    /// Fails before any prompt when a task with subtasks is deleted without `--cascade` or
+    /// `--reparent`.
+    fn check_subtask_policy(
+        tm: &TaskManager,
+        ids: &[TaskId],
+        policy: Option<SubtaskPolicy>,
+    ) -> Result<()> {
+        if policy.is_some() {
+            return Ok(());
+        }
+        for &id in ids {
+            if let Some(idx) = tm.find_task_by_id(id)
+                && tm
+                    .subtask_indices(idx)
+                    .iter()
+                    .any(|&c| !ids.contains(&tm.tasks()[c].id))
+            {
+                anyhow::bail!(
+                    "Task {id} has subtasks. Use `rusk del {id} --cascade` to delete them too, or `--reparent` to move them up a level"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn print_subtask_policy_note(tm: &TaskManager, idx: usize, policy: Option<SubtaskPolicy>) {
+        let (count, what) = match policy {
+            Some(SubtaskPolicy::Cascade) => {
+                (tm.descendant_indices(idx).len(), "will be deleted too")
+            }
+            _ => (tm.subtask_indices(idx).len(), "will move up a level"),
+        };
+        if count == 0 {
+            return;
+        }
+        println!(
+            "{}",
+            format!("  {count} subtask(s) {what}").truecolor(255, 165, 0)
+        );
+    }
+
     #[cfg(feature = "interactive")]
-    fn delete_by_ids(tm: &mut TaskManager, ids: Vec<u8>) -> Result<()> {
+    fn delete_by_ids(
+        tm: &mut TaskManager,
+        ids: Vec<TaskId>,
+        policy: Option<SubtaskPolicy>,
+    ) -> Result<()> {
+        let ask = config::current().confirm_delete();
         let mut confirmed_ids = Vec::new();
-        let mut not_found: Vec<u8> = Vec::new();
+        let mut not_found: Vec<TaskId> = Vec::new();
 
         for &id in &ids {
             if let Some(idx) = tm.find_task_by_id(id) {
+                Self::print_subtask_policy_note(tm, idx, policy);
                 let task = &tm.tasks()[idx];
-                let prompt = Self::print_delete_confirmation_dialog(&task.text, task.id);
-                let confirmed = Self::read_confirmation(&prompt)?;
+                let confirmed = !ask || {
+                    let prompt = Self::print_delete_confirmation_dialog(&task.text, task.id);
+                    Self::read_confirmation(&prompt)?
+                };
                 if confirmed {
                     confirmed_ids.push(id);
                 } else {
@@ -354,8 +728,9 @@ impl HandlerCLI {
         }
 
         if !confirmed_ids.is_empty() {
-            let deleted_count = confirmed_ids.len();
-            let _ = tm.delete_tasks(confirmed_ids)?;
+            let before = tm.tasks().len();
+            let _ = tm.delete_tasks_with(confirmed_ids, policy)?;
+            let deleted_count = before - tm.tasks().len();
             println!(
                 "{}{}{}",
                 "Deleted ".truecolor(255, 165, 0),
@@ -369,12 +744,17 @@ impl HandlerCLI {
     }
 
     #[cfg(not(feature = "interactive"))]
-    fn delete_by_ids(tm: &mut TaskManager, ids: Vec<u8>) -> Result<()> {
-        let mut not_found: Vec<u8> = Vec::new();
+    fn delete_by_ids(
+        tm: &mut TaskManager,
+        ids: Vec<TaskId>,
+        policy: Option<SubtaskPolicy>,
+    ) -> Result<()> {
+        let mut not_found: Vec<TaskId> = Vec::new();
         let mut to_delete = Vec::new();
 
         for &id in &ids {
-            if tm.find_task_by_id(id).is_some() {
+            if let Some(idx) = tm.find_task_by_id(id) {
+                Self::print_subtask_policy_note(tm, idx, policy);
                 to_delete.push(id);
             } else {
                 not_found.push(id);
@@ -382,8 +762,9 @@ impl HandlerCLI {
         }
 
         if !to_delete.is_empty() {
-            let deleted_count = to_delete.len();
-            let _ = tm.delete_tasks(to_delete)?;
+            let before = tm.tasks().len();
+            let _ = tm.delete_tasks_with(to_delete, policy)?;
+            let deleted_count = before - tm.tasks().len();
             println!(
                 "{}{}{}",
                 "Deleted ".truecolor(255, 165, 0),
@@ -396,43 +777,185 @@ impl HandlerCLI {
         Ok(())
     }
 
-    pub fn handle_mark_tasks(tm: &mut TaskManager, ids: Vec<u8>, priority: bool) -> Result<()> {
-        let (marked, not_found) = if priority {
-            tm.mark_priority_tasks(ids)?
+    /// `priority` is `None` to toggle done, `Some(None)` to toggle priority (`mark -p`) and
+    /// `Some(Some(level))` to set a level (`mark -p high`).
+    pub fn handle_mark_tasks(
+        tm: &mut TaskManager,
+        ids: Vec<TaskId>,
+        priority: Option<Option<Priority>>,
+    ) -> Result<()> {
+        let mut blocked_before = Vec::new();
+        let mut tasks_before = tm.tasks().len();
+        let (marked, not_found) = if let Some(level) = priority {
+            match level {
+                None => tm.mark_priority_tasks(ids)?,
+                Some(level) => {
+                    let (ids, not_found) = tm.set_priority_tasks(ids, level)?;
+                    (ids.into_iter().map(|id| (id, true)).collect(), not_found)
+                }
+            }
         } else {
-            tm.mark_tasks(ids)?
+            let finish_subtasks_of = Self::ask_finish_subtasks(tm, &ids)?;
+            blocked_before = tm.blocked_task_ids();
+            tasks_before = tm.tasks().len();
+            tm.mark_tasks_with_subtasks(ids, &finish_subtasks_of)?
         };
 
         for (id, _) in marked {
             if let Some(idx) = tm.find_task_by_id(id) {
                 let task = &tm.tasks()[idx];
-                let status = if task.done {
-                    "done"
-                } else if task.priority {
-                    "priority"
+                let label = if task.done {
+                    "Marked task as done:".to_string()
+                } else if task.priority != Priority::None {
+                    format!("Marked task as {} priority:", task.priority)
+                } else if priority.is_some() {
+                    "Priority removed:".to_string()
                 } else {
-                    "undone"
+                    "Marked task as undone:".to_string()
                 };
-                let prefix = format!("{} {}: ", format!("Marked task as {status}:").green(), id);
+                let prefix = format!("{} {}: ", label.green(), id);
                 Self::print_task_text_with_wrapping(&prefix, &task.text.bold().to_string());
             }
         }
 
+        // Recurring tasks append their next occurrence when completed.
+        for task in &tm.tasks()[tasks_before..] {
+            let date = Self::format_due_for_display(task.date, task.time);
+            println!(
+                "{} {} {}",
+                "Next occurrence:".cyan(),
+                task.id.to_string().bold(),
+                format!("(due {date})").cyan()
+            );
+        }
+
+        let still_blocked = tm.blocked_task_ids();
+        for id in blocked_before
+            .into_iter()
+            .filter(|id| !still_blocked.contains(id))
+        {
+            if let Some(idx) = tm.find_task_by_id(id) {
+                let prefix = format!("{} {}: ", "Unblocked task:".cyan(), id);
+                Self::print_task_text_with_wrapping(
+                    &prefix,
+                    &tm.tasks()[idx].text.bold().to_string(),
+                );
+            }
+        }
+
         Self::print_not_found_ids(&not_found);
         Ok(())
     }
 
+    /// For each task about to be marked done that still has open subtasks, asks whether to finish
+    /// them too. Returns the IDs whose subtasks should be completed.
+    #[cfg(feature = "interactive")]
+    fn ask_finish_subtasks(tm: &TaskManager, ids: &[TaskId]) -> Result<Vec<TaskId>> {
+        let mut finish = Vec::new();
+        for &id in ids {
+            let Some(idx) = tm.find_task_by_id(id) else {
+                continue;
+            };
+            if tm.tasks()[idx].done {
+                continue;
+            }
+            let open: Vec<TaskId> = tm
+                .open_subtask_ids(id)
+                .into_iter()
+                .filter(|c| !ids.contains(c))
+                .collect();
+            if open.is_empty() {
+                continue;
+            }
+            let prompt = format!(
+                "{}{}{}{}{}",
+                "Task ".truecolor(255, 165, 0),
+                id.to_string().white(),
+                " has ".truecolor(255, 165, 0),
+                open.len().to_string().white(),
+                " open subtask(s). Mark them done too? [y/N]: ".truecolor(255, 165, 0)
+            );
+            if Self::read_confirmation(&prompt)? {
+                finish.push(id);
+            }
+        }
+        Ok(finish)
+    }
+
+    /// Without a terminal dialog, open subtasks are left alone.
+    #[cfg(not(feature = "interactive"))]
+    fn ask_finish_subtasks(_tm: &TaskManager, _ids: &[TaskId]) -> Result<Vec<TaskId>> {
+        Ok(Vec::new())
+    }
+
+    /// `rusk block ID --on IDS`.
+    pub fn handle_block_task(tm: &mut TaskManager, id: TaskId, on: Vec<TaskId>) -> Result<()> {
+        if on.is_empty() {
+            anyhow::bail!("No blocking task IDs provided");
+        }
+        if !tm.block_task(id, &on)? {
+            println!(
+                "{} {}",
+                "Task already blocked by these tasks:".magenta(),
+                id
+            );
+        }
+        let blockers = tm.tasks()[tm.find_task_by_id(id).unwrap()]
+            .blocked_by
+            .clone();
+        let ids: Vec<String> = blockers
+            .iter()
+            .filter_map(|b| tm.tasks().iter().find(|t| t.uuid == *b))
+            .map(|t| t.id.to_string())
+            .collect();
+        println!(
+            "{} {} {} {}",
+            "Task".green(),
+            id.to_string().bold(),
+            "is blocked by:".green(),
+            ids.join(", ")
+        );
+        Ok(())
+    }
+
+    /// `rusk unblock ID [--from IDS]`.
+    pub fn handle_unblock_task(
+        tm: &mut TaskManager,
+        id: TaskId,
+        from: Option<Vec<TaskId>>,
+    ) -> Result<()> {
+        if tm.unblock_task(id, from.as_deref())? {
+            let remaining = tm.open_blocker_ids(id);
+            if remaining.is_empty() {
+                println!("{} {}", "Unblocked task:".green(), id);
+            } else {
+                let list: Vec<String> = remaining.iter().map(|b| b.to_string()).collect();
+                println!(
+                    "{} {} {} {}",
+                    "Task".green(),
+                    id.to_string().bold(),
+                    "is still blocked by:".green(),
+                    list.join(", ")
+                );
+            }
+        } else {
+            println!("{} {}", "Task has no such blockers:".magenta(), id);
+        }
+        Ok(())
+    }
+
     pub fn handle_edit_tasks(
         tm: &mut TaskManager,
-        ids: Vec<u8>,
+        ids: Vec<TaskId>,
         text: Option<Vec<String>>,
         date: Option<String>,
     ) -> Result<()> {
         let ids_copy = ids.clone();
-        let mut old_dates: Vec<(u8, Option<chrono::NaiveDate>)> = Vec::new();
+        let mut old_dates: Vec<(TaskId, String)> = Vec::new();
         for &id in &ids_copy {
             if let Some(idx) = tm.find_task_by_id(id) {
-                old_dates.push((id, tm.tasks()[idx].date));
+                let task = &tm.tasks()[idx];
+                old_dates.push((id, Self::format_due_for_display(task.date, task.time)));
             }
         }
 
@@ -449,18 +972,18 @@ impl HandlerCLI {
         for id in edited {
             if let Some(idx) = tm.find_task_by_id(id) {
                 let task = &tm.tasks()[idx];
-                let old_date = old_dates
+                let old_date_str = old_dates
                     .iter()
                     .find(|(i, _)| *i == id)
-                    .and_then(|(_, d)| *d);
-                let new_date = task.date;
+                    .map(|(_, d)| d.clone())
+                    .unwrap_or_else(|| Self::format_date_for_display(None));
+                let new_date_str = Self::format_due_for_display(task.date, task.time);
 
                 let prefix = format!("{} {}: ", "Edited task:".green(), id);
                 Self::print_task_text_with_wrapping(&prefix, &task.text.bold().to_string());
 
                 if date_change_requested {
                     if is_clearing_date {
-                        let old_date_str = Self::format_date_for_display(old_date);
                         println!(
                             " {} {} {} {} {}",
                             "- date:".cyan(),
@@ -470,9 +993,7 @@ impl HandlerCLI {
                             ")".normal()
                         );
                     } else {
-                        if new_date != old_date {
-                            let old_date_str = Self::format_date_for_display(old_date);
-                            let new_date_str = Self::format_date_for_display(new_date);
+                        if new_date_str != old_date_str {
                             if old_date_str == "empty" {
                                 println!(
                                     " {} {} {} {} {} {}",
@@ -494,8 +1015,7 @@ impl HandlerCLI {
                                 );
                             }
                         } else {
-                            let date_str = Self::format_date_for_display(new_date);
-                            println!(" {} {}", "- date:".cyan(), date_str.bold());
+                            println!(" {} {}", "- date:".cyan(), new_date_str.bold());
                         }
                     }
                 }
@@ -505,17 +1025,11 @@ impl HandlerCLI {
         for id in unchanged {
             if let Some(idx) = tm.find_task_by_id(id) {
                 let task = &tm.tasks()[idx];
-                let _old_date = old_dates
-                    .iter()
-                    .find(|(i, _)| *i == id)
-                    .and_then(|(_, d)| *d);
-                let current_date = task.date;
-
                 let prefix = format!("{} ", "Task already has this content:".magenta());
                 Self::print_task_text_with_wrapping(&prefix, &task.text.bold().to_string());
 
                 if date_change_requested {
-                    let date_str = Self::format_date_for_display(current_date);
+                    let date_str = Self::format_due_for_display(task.date, task.time);
                     println!(" {} {}", "- date:".cyan(), date_str.bold());
                 }
             }
@@ -525,54 +1039,123 @@ impl HandlerCLI {
         Ok(())
     }
 
-    pub fn handle_list_tasks(tasks: &[Task], compact: bool) {
+    pub fn handle_list_tasks(tasks: &[Task], options: &ListOptions) {
+        let compact = options.compact;
         if tasks.is_empty() {
             println!("{}", "No tasks".yellow());
             return;
         }
+        let all_tasks = tasks;
+        let today = chrono::Local::now().date_naive();
+        let (mut tasks, hidden): (Vec<&Task>, Vec<&Task>) = tasks
+            .iter()
+            .filter(|t| options.matches(t) && !(options.hide_blocked && t.is_blocked_in(all_tasks)))
+            .partition(|t| options.shows_waiting(t, today));
+        let waiting_note =
+            (options.waiting == WaitingFilter::Hide && !hidden.is_empty()).then(|| {
+                format!(
+                    "{} waiting task(s) hidden until their start date (rusk list --waiting)",
+                    hidden.len()
+                )
+                .truecolor(128, 128, 128)
+            });
+        match options.sort {
+            Some(ListSort::Due) => tasks.sort_by_key(|t| (t.date.is_none(), t.due_datetime())),
+            Some(ListSort::Priority) => tasks.sort_by_key(|t| {
+                (
+                    std::cmp::Reverse(t.priority),
+                    t.date.is_none(),
+                    t.due_datetime(),
+                )
+            }),
+            None => {}
+        }
+        if tasks.is_empty() {
+            let msg = if options.is_filtered() {
+                "No matching tasks"
+            } else {
+                "No tasks"
+            };
+            println!("{}", msg.yellow());
+            if let Some(note) = waiting_note {
+                println!("{note}");
+            }
+            return;
+        }
+
+        // IDs are right-aligned in a column at least two digits wide; wider IDs widen the prefix.
+        let id_width = tasks
+            .iter()
+            .map(|t| t.id.to_string().len())
+            .max()
+            .unwrap_or(0)
+            .max(2);
+        let id_pad = " ".repeat(id_width - 2);
+        let uuid_width = if options.show_uuid {
+            UUID_SHORT_LEN + 2
+        } else {
+            0
+        };
+        let uuid_header = if options.show_uuid {
+            format!(" {}{}", "uuid".blue(), " ".repeat(uuid_width - 5))
+        } else {
+            String::new()
+        };
+
+        // The date column widens from `7-jan-26` to `7-jan-26 09:30` when a task has a due time,
+        // and further for longer `display.date_format` patterns.
+        let date_width = if tasks.iter().any(|t| t.date.is_some() && t.time.is_some()) {
+            15
+        } else {
+            9
+        }
+        .max(
+            tasks
+                .iter()
+                .filter_map(|t| t.date.map(|d| Self::format_short_due(d, t.time)))
+                .map(|s| s.chars().count())
+                .max()
+                .unwrap_or(0),
+        );
+        let date_pad = " ".repeat(date_width - 9);
 
         println!(
-            "\n  #  {}    {}       {}",
+            "\n  #  {}{}{}    {}{}       {}",
+            id_pad,
             "id".blue(),
+            uuid_header,
+            date_pad,
             "date".blue(),
             "task".blue()
         );
-        println!("  ──────────────────────────────────────────────");
+        println!(
+            "  ──────────────────────────────────────────────{}",
+            "─".repeat(id_width - 2 + uuid_width + date_width - 9)
+        );
 
         let max_line_width = Self::get_max_line_width();
+        let now = chrono::Utc::now();
+        let local_now = chrono::Local::now().naive_local();
 
-        let prefix_width = 19;
+        let prefix_width = 8 + id_width + uuid_width + date_width;
         let available_width = max_line_width
             .saturating_sub(prefix_width)
             .saturating_sub(4);
 
-        for task in tasks {
+        for row in Self::tree_rows(&tasks, compact) {
+            let task = row.task;
             let status = if task.done {
                 "✔".green()
-            } else if task.priority {
-                "p".truecolor(255, 165, 0).bold()
+            } else if task.is_blocked_in(all_tasks) {
+                "⊘".truecolor(200, 80, 80)
             } else {
-                "•".normal()
+                Self::priority_glyph(task.priority)
             };
 
-            let date_str = task
-                .date
-                .map(|d| {
-                    let day = d.day();
-                    let month = d.format("%b").to_string().to_lowercase();
-                    let year = d.format("%y").to_string();
-                    format!("{}-{}-{}", day, month, year)
-                })
-                .unwrap_or_default();
-
-            let date_colored = if let Some(d) = task.date {
-                if d < chrono::Local::now().date_naive() && !task.done {
-                    date_str.red()
-                } else {
-                    date_str.cyan()
-                }
-            } else {
-                "".normal()
+            let date_colored = match task.date {
+                Some(d) if task.is_overdue(local_now) => Self::format_short_due(d, task.time).red(),
+                Some(d) => Self::format_short_due(d, task.time).cyan(),
+                None => "".normal(),
             };
 
             let text_for_list = if compact {
@@ -580,7 +1163,11 @@ impl HandlerCLI {
             } else {
                 task.text.as_str()
             };
-            let wrapped_lines = Self::wrap_text_by_words(text_for_list, available_width);
+            let branch_width = row.branch.chars().count();
+            let wrapped_lines = Self::wrap_text_by_words(
+                text_for_list,
+                available_width.saturating_sub(branch_width).max(1),
+            );
 
             let first_line: &str = if compact {
                 wrapped_lines
@@ -591,26 +1178,179 @@ impl HandlerCLI {
                 wrapped_lines.first().map(|s| s.as_str()).unwrap_or("")
             };
 
+            let uuid_col = if options.show_uuid {
+                let simple = task.uuid.simple().to_string();
+                format!("  {}", simple[..UUID_SHORT_LEN].dimmed())
+            } else {
+                String::new()
+            };
+
+            let repeat = match &task.recurrence {
+                Some(rule) => format!(" ↻{}", rule.every)
+                    .truecolor(128, 128, 128)
+                    .to_string(),
+                None => String::new(),
+            };
+            let start = match task.wait {
+                Some(w) if w > today => format!(" ⧗{}", Self::format_short_due(w, None))
+                    .truecolor(128, 128, 128)
+                    .to_string(),
+                Some(w) if !task.done && (today - w).num_days() < NEWLY_ACTIVE_DAYS => {
+                    " ✦new".yellow().bold().to_string()
+                }
+                _ => String::new(),
+            };
+            let collapsed = match row.collapsed {
+                Some((done, total)) => format!(" [{done}/{total}]")
+                    .truecolor(128, 128, 128)
+                    .to_string(),
+                None => String::new(),
+            };
+
             if !first_line.is_empty() || !wrapped_lines.is_empty() {
                 println!(
-                    "  {} {:>2}  {:>9}  {}",
+                    "  {} {:>id_width$}{}  {:>date_width$}  {}{}{}{}{}",
                     status,
                     task.id.to_string().bold(),
+                    uuid_col,
                     date_colored,
-                    first_line
+                    row.branch.truecolor(128, 128, 128),
+                    Self::highlight_tags(first_line),
+                    repeat,
+                    start,
+                    collapsed
                 );
             }
 
             if !compact {
                 for line in wrapped_lines.iter().skip(1) {
-                    println!("  {} {:>3} {:>10} {}", " ", " ", " ", line);
+                    println!(
+                        "{:prefix_width$}{}{}",
+                        "",
+                        row.continuation.truecolor(128, 128, 128),
+                        Self::highlight_tags(line)
+                    );
                 }
             }
+
+            if options.show_timestamps
+                && let Some(stamps) = Self::format_task_timestamps(task, now)
+            {
+                println!(
+                    "{:prefix_width$}{}{}",
+                    "",
+                    row.continuation.truecolor(128, 128, 128),
+                    stamps.truecolor(128, 128, 128)
+                );
+            }
         }
 
+        if let Some(note) = waiting_note {
+            println!("\n  {note}");
+        }
         println!("\n");
     }
 
+    /// Orders `tasks` as a forest: each task is followed by its subtasks (depth first, storage
+    /// order among siblings). A task whose parent is not in `tasks` is shown at the top level.
+    /// With `collapse`, only top-level tasks are returned, each with a done/total subtask count.
+    fn tree_rows<'a>(tasks: &[&'a Task], collapse: bool) -> Vec<TreeRow<'a>> {
+        let is_root = |t: &Task| t.parent.is_none_or(|p| !tasks.iter().any(|o| o.uuid == p));
+        let children = |t: &Task| -> Vec<&'a Task> {
+            tasks
+                .iter()
+                .copied()
+                .filter(|c| c.parent == Some(t.uuid) && c.uuid != t.uuid)
+                .collect()
+        };
+
+        let mut rows = Vec::new();
+        let mut shown: Vec<uuid::Uuid> = Vec::new();
+        for root in tasks.iter().copied().filter(|t| is_root(t)) {
+            if collapse {
+                shown.push(root.uuid);
+                let mut stack = children(root);
+                let (mut done, mut total) = (0, 0);
+                while let Some(t) = stack.pop() {
+                    if shown.contains(&t.uuid) {
+                        continue;
+                    }
+                    shown.push(t.uuid);
+                    total += 1;
+                    done += usize::from(t.done);
+                    stack.extend(children(t));
+                }
+                rows.push(TreeRow::plain(root, (total > 0).then_some((done, total))));
+                continue;
+            }
+
+            // (task, ancestor guide columns, is_last_sibling, depth)
+            let mut stack: Vec<(&Task, String, bool, usize)> = vec![(root, String::new(), true, 0)];
+            while let Some((t, guides, last, depth)) = stack.pop() {
+                if shown.contains(&t.uuid) {
+                    continue;
+                }
+                shown.push(t.uuid);
+                if depth == 0 {
+                    rows.push(TreeRow::plain(t, None));
+                } else {
+                    let (joint, rail) = if last {
+                        ("└─ ", "   ")
+                    } else {
+                        ("├─ ", "│  ")
+                    };
+                    rows.push(TreeRow {
+                        task: t,
+                        branch: format!("{guides}{joint}"),
+                        continuation: format!("{guides}{rail}"),
+                        collapsed: None,
+                    });
+                }
+                let child_guides = if depth == 0 {
+                    String::new()
+                } else {
+                    format!("{guides}{}", if last { "   " } else { "│  " })
+                };
+                let kids = children(t);
+                let n = kids.len();
+                for (i, kid) in kids.into_iter().enumerate().rev() {
+                    stack.push((kid, child_guides.clone(), i + 1 == n, depth + 1));
+                }
+            }
+        }
+        // Parent cycles in a hand-edited database have no root; show those tasks flat.
+        for t in tasks.iter().copied() {
+            if !shown.contains(&t.uuid) {
+                rows.push(TreeRow::plain(t, None));
+            }
+        }
+        rows
+    }
+
+    /// `rusk tags`: every tag with the number of tasks carrying it, most used first.
+    pub fn handle_list_tags(tm: &TaskManager) {
+        let counts = tm.tag_counts();
+        if counts.is_empty() {
+            println!("{}", "No tags".yellow());
+            return;
+        }
+        let width = counts
+            .iter()
+            .map(|(t, _)| t.chars().count())
+            .max()
+            .unwrap_or(0)
+            + 1;
+        println!();
+        for (tag, count) in counts {
+            println!(
+                "  {}  {}",
+                format!("{:<width$}", format!("#{tag}")).bright_blue(),
+                count
+            );
+        }
+        println!();
+    }
+
     pub fn handle_list_tasks_for_completion(tasks: &[Task]) {
         for task in tasks {
             let lines: Vec<&str> = task.text.lines().collect();
@@ -625,12 +1365,751 @@ impl HandlerCLI {
         }
     }
 
-    pub fn handle_restore(tm: &mut TaskManager) -> Result<()> {
-        tm.restore_from_backup()
+    pub fn handle_undo(tm: &mut TaskManager, n: usize) -> Result<()> {
+        for step in tm.undo(n)? {
+            println!(
+                "{} {} {}",
+                "Undid:".green(),
+                step.command,
+                Self::format_journal_counts(&step).dimmed()
+            );
+        }
+        Ok(())
+    }
+
+    pub fn handle_redo(tm: &mut TaskManager, n: usize) -> Result<()> {
+        for step in tm.redo(n)? {
+            println!(
+                "{} {} {}",
+                "Redid:".green(),
+                step.command,
+                Self::format_journal_counts(&step).dimmed()
+            );
+        }
+        Ok(())
+    }
+
+    /// `(1 added, 2 deleted)` for an operation's journal entry.
+    fn format_journal_counts(entry: &JournalEntry) -> String {
+        let (added, removed, changed) = entry.counts();
+        let parts: Vec<String> = [(added, "added"), (removed, "deleted"), (changed, "changed")]
+            .iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, what)| format!("{count} {what}"))
+            .collect();
+        format!("({})", parts.join(", "))
+    }
+
+    /// `rusk log`: the newest `limit` journal entries.
+    pub fn handle_log(tm: &TaskManager, limit: usize) -> Result<()> {
+        let entries = journal::read_journal(&tm.db_path)?;
+        if entries.is_empty() {
+            println!("{}", "No changes recorded yet.".yellow());
+            return Ok(());
+        }
+        let (_, undone) = journal::undo_redo_stacks(&entries);
+        let now = Utc::now();
+        let shown: Vec<&JournalEntry> = entries.iter().rev().take(limit).collect();
+        let seq_width = shown.first().map_or(1, |e| e.seq.to_string().len());
+        let command_width = shown
+            .iter()
+            .map(|e| e.command.chars().count())
+            .max()
+            .unwrap_or(0)
+            .min(40);
+        for entry in shown {
+            let when = entry.at.with_timezone(&Local).format("%d-%m-%Y %H:%M");
+            let age = Self::format_age(entry.at, now);
+            let command: String = entry.command.chars().take(40).collect();
+            let summary = match entry.kind {
+                EntryKind::Op if undone.contains(&entry.seq) => format!(
+                    "{} {}",
+                    Self::format_journal_counts(entry),
+                    "undone".yellow()
+                ),
+                EntryKind::Op => Self::format_journal_counts(entry),
+                EntryKind::Undo(target) => format!("undid #{target}").dimmed().to_string(),
+                EntryKind::Redo(target) => format!("redid #{target}").dimmed().to_string(),
+            };
+            println!(
+                "{}  {when}  {:<9}  {command:<command_width$}  {summary}",
+                format!("#{:>seq_width$}", entry.seq).white().bold(),
+                age.dimmed()
+            );
+        }
+        Ok(())
+    }
+
+    /// `rusk renumber`: reassigns IDs 1..n in `order` and prints the IDs that changed.
+    pub fn handle_renumber(tm: &mut TaskManager, order: RenumberOrder) -> Result<()> {
+        let mapping = tm.renumber(order)?;
+        if mapping.is_empty() {
+            println!("{}", "IDs already run from 1 in this order.".green());
+            return Ok(());
+        }
+        println!(
+            "{}",
+            format!(
+                "Renumbered {} task(s) by {}:",
+                mapping.len(),
+                order.as_str()
+            )
+            .green()
+        );
+        let width = mapping
+            .iter()
+            .map(|(old, new)| old.max(new).to_string().len())
+            .max()
+            .unwrap_or(1);
+        for (old, new) in &mapping {
+            let text = tm
+                .find_task_by_id(*new)
+                .map(|i| {
+                    tm.tasks[i]
+                        .text
+                        .lines()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string()
+                })
+                .unwrap_or_default();
+            println!(
+                "  {:>width$} → {:<width$}  {text}",
+                old.to_string().dimmed(),
+                new.to_string().bold()
+            );
+        }
+        println!(
+            "{}",
+            "Old IDs stay listed in `rusk renumber --history`; `rusk undo` reverts.".dimmed()
+        );
+        Ok(())
+    }
+
+    /// `rusk renumber --history`: the mappings of past renumberings, newest first.
+    pub fn handle_renumber_history(tm: &TaskManager) {
+        let history = tm.renumberings();
+        if history.is_empty() {
+            println!("{}", "No renumberings recorded yet.".yellow());
+            return;
+        }
+        for renumbering in history.iter().rev() {
+            let when = renumbering
+                .at
+                .with_timezone(&Local)
+                .format("%d-%m-%Y %H:%M");
+            println!(
+                "{} {when} {}",
+                "Renumbered".cyan(),
+                format!("(by {})", renumbering.order).dimmed()
+            );
+            let pairs: Vec<String> = renumbering
+                .ids
+                .iter()
+                .map(|(old, new)| format!("{old} → {new}"))
+                .collect();
+            println!("  {}", pairs.join(", "));
+        }
+    }
+
+    /// Restores backup number `n` from `rusk restore --list` (newest when `None`), or the file
+    /// `from`, after printing what it changes.
+    pub fn handle_restore(
+        tm: &mut TaskManager,
+        n: Option<usize>,
+        from: Option<&Path>,
+    ) -> Result<()> {
+        let path = match from {
+            Some(file) => file.to_path_buf(),
+            None => {
+                let backups = backup::list_backups(&tm.db_path)?;
+                let n = n.unwrap_or(1);
+                match backups.get(n.wrapping_sub(1)) {
+                    Some(entry) => entry.path.clone(),
+                    None if backups.is_empty() => {
+                        anyhow::bail!("No backup file found for '{}'", tm.db_path.display())
+                    }
+                    None => anyhow::bail!(
+                        "There is no backup {n}; `rusk restore --list` shows {} backup(s)",
+                        backups.len()
+                    ),
+                }
+            }
+        };
+
+        let target = TaskManager::load_tasks_from_path(&path)?;
+        if let Ok(current) = TaskManager::load_tasks_from_path(&tm.db_path) {
+            Self::print_restore_diff(&backup::diff_tasks(&current, &target));
+        }
+        tm.restore_from_path(&path)
+    }
+
+    /// `rusk restore --list`: backups newest first, with age, task count and what restoring
+    /// each would change.
+    pub fn handle_restore_list(db_path: &Path) -> Result<()> {
+        let backups = backup::list_backups(db_path)?;
+        if backups.is_empty() {
+            println!("{}", "No backups yet.".yellow());
+            return Ok(());
+        }
+        let current = TaskManager::load_tasks_from_path(&db_path.to_path_buf()).ok();
+        let now = Utc::now();
+        let num_width = backups.len().to_string().len();
+        for (i, entry) in backups.iter().enumerate() {
+            let age = Local
+                .from_local_datetime(&entry.taken_at)
+                .earliest()
+                .map(|t| Self::format_age(t.with_timezone(&Utc), now))
+                .unwrap_or_default();
+            let when = entry.taken_at.format("%d-%m-%Y %H:%M:%S").to_string();
+            let details = match TaskManager::load_tasks_from_path(&entry.path) {
+                Ok(tasks) => {
+                    let changes = match &current {
+                        Some(current) => {
+                            Self::format_restore_diff_counts(&backup::diff_tasks(current, &tasks))
+                        }
+                        None => String::new(),
+                    };
+                    format!("{:>4} tasks  {changes}", tasks.len())
+                }
+                Err(_) => "unreadable".red().to_string(),
+            };
+            let legacy = if entry.legacy {
+                "  (.json.backup)".dimmed().to_string()
+            } else {
+                String::new()
+            };
+            println!(
+                "{:>num_width$}  {}  {:<9}  {details}{legacy}",
+                (i + 1).to_string().white().bold(),
+                when,
+                age.dimmed()
+            );
+        }
+        Ok(())
+    }
+
+    /// `+1 -2 ~3`, or `no changes`.
+    fn format_restore_diff_counts(diff: &backup::RestoreDiff) -> String {
+        if diff.is_empty() {
+            return "no changes".dimmed().to_string();
+        }
+        format!(
+            "{} {} {}",
+            format!("+{}", diff.added.len()).green(),
+            format!("-{}", diff.removed.len()).red(),
+            format!("~{}", diff.changed.len()).yellow()
+        )
+    }
+
+    fn print_restore_diff(diff: &backup::RestoreDiff) {
+        if diff.is_empty() {
+            println!("{}", "The backup matches the current tasks.".dimmed());
+            return;
+        }
+        let first_line = |t: &Task| t.text.lines().next().unwrap_or("").to_string();
+        println!("{}", "Restoring changes:".cyan());
+        for task in &diff.added {
+            println!("  {} {} {}", "+".green(), task.id, first_line(task));
+        }
+        for task in &diff.removed {
+            println!("  {} {} {}", "-".red(), task.id, first_line(task));
+        }
+        for (current, restored) in &diff.changed {
+            let mut fields = Vec::new();
+            if current.text != restored.text {
+                fields.push("text");
+            }
+            if current.date != restored.date || current.time != restored.time {
+                fields.push("due");
+            }
+            if current.done != restored.done {
+                fields.push(if restored.done { "done" } else { "undone" });
+            }
+            if current.priority != restored.priority {
+                fields.push("priority");
+            }
+            println!(
+                "  {} {} {} {}",
+                "~".yellow(),
+                restored.id,
+                first_line(restored),
+                format!("({})", fields.join(", ")).dimmed()
+            );
+        }
+    }
+
+    /// `rusk init`: creates `.rusk/tasks.json` in the current directory.
+    pub fn handle_init(git: bool) -> Result<()> {
+        if TaskManager::selected_db().is_some() {
+            if git {
+                anyhow::bail!(
+                    "--git sets up the .rusk directory here and cannot be combined with --db"
+                );
+            }
+            let db_path = TaskManager::locate_db_path()?;
+            TaskManager::create_db(&db_path)?;
+            println!(
+                "{} {}",
+                "Initialized an empty task database in".green(),
+                db_path.display()
+            );
+            return Ok(());
+        }
+        let cwd = std::env::current_dir().context("Failed to read the current directory")?;
+        let existing = cwd
+            .join(DB_DIR_NAME)
+            .join(list_file_name(TaskManager::selected_list()));
+        if git && existing.exists() {
+            println!(
+                "{} {}",
+                "Using the existing task database in".green(),
+                existing.display()
+            );
+        } else {
+            let outer = cwd
+                .parent()
+                .and_then(|p| find_db_dir(p, dirs::home_dir().as_deref()));
+            let db_path = TaskManager::init_db(&cwd)?;
+            println!(
+                "{} {}",
+                "Initialized an empty task database in".green(),
+                db_path.display()
+            );
+            if let Some(outer) = outer {
+                println!(
+                    "{}",
+                    format!(
+                        "It takes over from '{}' in this directory and below.",
+                        outer.display()
+                    )
+                    .dimmed()
+                );
+            }
+            if std::env::var_os("RUSK_DB").is_some() {
+                println!(
+                    "{}",
+                    "Note: RUSK_DB is set, so rusk keeps using that database until it is unset."
+                        .truecolor(255, 165, 0)
+                );
+            }
+        }
+        if git {
+            Self::setup_git_merge(&cwd)?;
+        }
+        Ok(())
+    }
+
+    /// `rusk init --git`: routes `.rusk/*.json` through `rusk merge-driver` and keeps the local
+    /// side files out of git.
+    fn setup_git_merge(dir: &Path) -> Result<()> {
+        let pattern = format!("{DB_DIR_NAME}/*.json");
+        if merge::add_gitattributes(dir, &pattern)? {
+            println!(
+                "{} `{pattern} merge={}` {}",
+                "Added".green(),
+                merge::GIT_DRIVER_NAME,
+                "to .gitattributes".green()
+            );
+        } else {
+            println!(
+                "{}",
+                ".gitattributes already uses the rusk merge driver".dimmed()
+            );
+        }
+        if merge::add_db_gitignore(&dir.join(DB_DIR_NAME))? {
+            println!(
+                "{}",
+                "Wrote .rusk/.gitignore so journals, backups and locks stay out of git".green()
+            );
+        }
+        match merge::configure_git(dir) {
+            Ok(()) => println!(
+                "{}",
+                "Registered the rusk merge driver in the git config".green()
+            ),
+            Err(e) => {
+                println!(
+                    "{}",
+                    format!("Could not update the git config ({e:#}). Run this in the repository:")
+                        .truecolor(255, 165, 0)
+                );
+                for (key, value) in merge::git_config_entries() {
+                    println!("  git config {key} \"{value}\"");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `rusk merge-driver`: merges, reports to stderr (git shows it) and returns whether the
+    /// merge is clean.
+    pub fn handle_merge_driver(base: &Path, ours: &Path, theirs: &Path) -> Result<bool> {
+        let merged = merge::merge_files(base, ours, theirs)?;
+        for (old, new) in &merged.renumbered {
+            eprintln!(
+                "{} task {old} from the other branch is now task {new}",
+                "rusk merge:".cyan()
+            );
+        }
+        for (task, blocker) in &merged.unblocked {
+            eprintln!(
+                "{} task {task} no longer waits for task {blocker}: the branches together made \
+                 their dependencies a cycle",
+                "rusk merge:".truecolor(255, 165, 0)
+            );
+        }
+        for conflict in &merged.conflicts {
+            let what = match &conflict.kind {
+                ConflictKind::Fields(fields) => {
+                    let names: Vec<_> = fields.iter().map(|(f, _, _)| f.as_str()).collect();
+                    format!("{} changed on both branches", names.join(", "))
+                }
+                ConflictKind::DeletedByUs => "deleted here, changed on the other branch".into(),
+                ConflictKind::DeletedByThem => "changed here, deleted on the other branch".into(),
+            };
+            eprintln!(
+                "{} conflict in task {}: {what}",
+                "rusk merge:".red(),
+                conflict.id
+            );
+        }
+        Ok(merged.conflicts.is_empty())
+    }
+
+    /// `rusk config list`: every setting with its effective value and source.
+    pub fn handle_config_list() -> Result<()> {
+        let current = config::current();
+        for setting in config::SETTINGS {
+            let (value, source) = current.get(setting.key)?;
+            let shown = config::display_value(&value);
+            println!(
+                "{:<22} {:<12} {}",
+                setting.key.bold(),
+                if shown.is_empty() {
+                    "-".to_string()
+                } else {
+                    shown
+                },
+                format!("({source})").dimmed()
+            );
+            println!("  {}", setting.about.dimmed());
+        }
+        let files = current.files();
+        if !files.is_empty() {
+            println!();
+            for file in files {
+                println!("{} {}", "Read".cyan(), file.display());
+            }
+        }
+        Ok(())
+    }
+
+    /// `rusk config get KEY`: the effective value alone, for scripts.
+    pub fn handle_config_get(key: &str) -> Result<()> {
+        let (value, _) = config::current().get(key)?;
+        println!("{}", config::display_value(&value));
+        Ok(())
+    }
+
+    /// `rusk config set KEY VALUE`: writes the project file, or the user file with `global`.
+    pub fn handle_config_set(key: &str, value: &str, global: bool) -> Result<()> {
+        let (path, scope) = if global {
+            let path = config::user_config_path()
+                .ok_or_else(|| anyhow::anyhow!("No user config directory; set RUSK_CONFIG"))?;
+            (path, Scope::User)
+        } else {
+            let db_path = TaskManager::locate_db_path()?;
+            (config::project_config_path(&db_path), Scope::Project)
+        };
+        let value = config::set(&path, scope, key, value)?;
+        println!(
+            "{} {} = {} {}",
+            "Set".green(),
+            key.bold(),
+            config::display_value(&value),
+            format!("in {}", path.display()).dimmed()
+        );
+        Ok(())
+    }
+
+    /// `rusk lists`: the lists next to `db_path` with their open and total task counts.
+    pub fn handle_lists(db_path: &Path) -> Result<()> {
+        let dir = match db_path.parent() {
+            Some(p) if !p.as_os_str().is_empty() => p,
+            _ => Path::new("."),
+        };
+        let current = db_path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let mut names = list_names(dir)?;
+        if !names.contains(&current) {
+            names.push(current.clone());
+        }
+        println!("{} {}", "Lists in".cyan(), dir.display());
+        for name in names {
+            let marker = if name == current { "*" } else { " " };
+            let path = dir.join(list_file_name(&name));
+            let counts = if path.exists() {
+                match TaskManager::load_tasks_from_path(&path) {
+                    Ok(tasks) => {
+                        let open = tasks.iter().filter(|t| !t.done).count();
+                        format!("{open} open, {} total", tasks.len()).normal()
+                    }
+                    Err(_) => "unreadable (see `rusk doctor`)".red(),
+                }
+            } else {
+                "empty, created on first change".dimmed()
+            };
+            println!("{marker} {:<16} {counts}", name.bold());
+        }
+        Ok(())
+    }
+
+    /// `rusk sync PATH`: merges the database with the one at `other` in both directions and
+    /// reports each task that moved.
+    pub fn handle_sync(tm: &mut TaskManager, other: &Path) -> Result<()> {
+        let other_path = storage::db_file_at(other);
+        if !other_path.exists() {
+            anyhow::bail!(
+                "No task database at '{}'. Create it with `rusk --db {} init` first.",
+                other_path.display(),
+                other.display()
+            );
+        }
+        if let (Ok(a), Ok(b)) = (
+            std::fs::canonicalize(&tm.db_path),
+            std::fs::canonicalize(&other_path),
+        ) && a == b
+        {
+            anyhow::bail!("'{}' is the database in use", other_path.display());
+        }
+
+        let _lock = DbLock::acquire(&other_path, lock_timeout())?;
+        let mut remote = TaskManager::new_with_path(other_path.clone())?;
+        let (local_deleted, remote_deleted) = (tm.tombstones(), remote.tombstones());
+        // Each side hands out IDs by its own `ids.reuse` setting.
+        let last_id =
+            |tm: &TaskManager, config: &Config| (!config.reuse_ids()).then(|| tm.last_id());
+        let synced = sync::sync(
+            Side {
+                last_id: last_id(tm, config::current()),
+                ..Side::new(&tm.tasks, &local_deleted)
+            },
+            Side {
+                last_id: last_id(&remote, &config::for_db(&other_path)),
+                ..Side::new(&remote.tasks, &remote_deleted)
+            },
+            sync::last_sync(&tm.db_path, &other_path),
+        )?;
+
+        let moved = |direction| synced.events.iter().any(|e| e.direction == direction);
+        // Archive first: a task in both the database and its archive is still listed once.
+        let unblocked = !synced.unblocked.is_empty();
+        if moved(Direction::Pushed) || remote_deleted != synced.deleted || unblocked {
+            if !synced.other_archived.is_empty() {
+                archive::add(&archive::archive_path(&other_path), synced.other_archived)?;
+            }
+            remote.tasks = synced.other;
+            remote.set_tombstones(synced.deleted.clone());
+            if let Some(id) = synced.other_last_id {
+                remote.raise_last_id(id);
+            }
+            remote.save()?;
+        }
+        if moved(Direction::Pulled) || local_deleted != synced.deleted || unblocked {
+            if !synced.local_archived.is_empty() {
+                archive::add(&archive::archive_path(&tm.db_path), synced.local_archived)?;
+            }
+            tm.tasks = synced.local;
+            tm.set_tombstones(synced.deleted);
+            if let Some(id) = synced.local_last_id {
+                tm.raise_last_id(id);
+            }
+            tm.save()?;
+        }
+        sync::record_sync(&tm.db_path, &other_path, Utc::now())?;
+
+        println!("{} {}", "Synced with".cyan(), other_path.display());
+        for event in &synced.events {
+            let label = match (event.conflict, event.direction) {
+                (true, _) => "conflict".red(),
+                (false, Direction::Pulled) => "pulled".green(),
+                (false, Direction::Pushed) => "pushed".blue(),
+            };
+            let id = event
+                .local_id
+                .map_or_else(|| "-".to_string(), |id| id.to_string());
+            let what = match event.change {
+                Change::Added => "new",
+                Change::Changed => "changed",
+                Change::Deleted => "deleted",
+                Change::Archived => "archived",
+            };
+            let note = match (event.conflict, event.direction) {
+                (false, _) => what.to_string(),
+                (true, Direction::Pulled) => format!("{what}, the other copy was newer"),
+                (true, Direction::Pushed) => format!("{what}, this copy was newer"),
+            };
+            println!(
+                "  {label:<8} {id:>4}  {} {}",
+                event.text.lines().next().unwrap_or_default(),
+                format!("({note})").dimmed()
+            );
+        }
+        for (task, blocker) in &synced.unblocked {
+            println!(
+                "  {:<8} {task:>4}  {}",
+                "unblocked".truecolor(255, 165, 0),
+                format!("(no longer waits for task {blocker}: the two made a dependency cycle)")
+                    .dimmed()
+            );
+        }
+        let count = |direction| {
+            synced
+                .events
+                .iter()
+                .filter(|e| e.direction == direction)
+                .count()
+        };
+        let conflicts = synced.events.iter().filter(|e| e.conflict).count();
+        if synced.events.is_empty() {
+            println!("{}", "Already in sync.".green());
+        } else {
+            println!(
+                "{} pulled, {} pushed, {conflicts} conflict(s) resolved by the newer change",
+                count(Direction::Pulled),
+                count(Direction::Pushed)
+            );
+        }
+        Ok(())
+    }
+
+    /// `rusk doctor`: reports what [`doctor::check`] finds and, with `fix`, repairs it. Returns
+    /// whether no problems are left.
+    pub fn handle_doctor(db_path: &Path, fix: bool) -> Result<bool> {
+        let diagnosis = doctor::check(db_path)?;
+        if diagnosis.issues.is_empty() {
+            println!(
+                "{} '{}' ({} tasks)",
+                "No problems found in".green(),
+                db_path.display(),
+                diagnosis.entry_count()
+            );
+            return Ok(true);
+        }
+        println!("{} '{}':", "Checked".cyan(), db_path.display());
+        for issue in &diagnosis.issues {
+            let mark = if issue.is_fixable() {
+                "x".red()
+            } else {
+                "!".yellow()
+            };
+            println!("  {mark} {}", issue.describe());
+        }
+
+        let total = diagnosis.issues.len();
+        let fixable = diagnosis.fixable_count();
+        if !fix || fixable == 0 {
+            let hint = if fixable == 0 {
+                "none of them can be repaired automatically".to_string()
+            } else {
+                format!("`rusk doctor --fix` repairs {fixable}")
+            };
+            println!(
+                "{}",
+                format!("Found {total} problem(s); {hint}.").truecolor(255, 165, 0)
+            );
+            return Ok(false);
+        }
+
+        if let Some(salvaged) = &diagnosis.salvage
+            && !salvaged.tasks.is_empty()
+            && !Self::confirm_salvage(salvaged)?
+        {
+            println!("{}", "Nothing was changed.".yellow());
+            return Ok(false);
+        }
+        let summary = doctor::repair(&diagnosis)?;
+        if let Some(count) = summary.salvaged {
+            println!("{} {count} task(s)", "Salvaged:".green());
+        }
+        for (old, new) in &summary.renumbered {
+            println!("{} {old} -> {new}", "Renumbered:".green());
+        }
+        for (id, blocker) in &summary.unblocked {
+            println!(
+                "{} task {id} no longer waits for task {blocker}",
+                "Unblocked:".green()
+            );
+        }
+        if let Some(path) = &summary.quarantine_path {
+            println!(
+                "{} {} entry(s) moved to '{}'",
+                "Quarantined:".green(),
+                summary.quarantined,
+                path.display()
+            );
+        }
+        for path in &summary.removed_files {
+            println!("{} {}", "Removed:".green(), path.display());
+        }
+        if fixable < total {
+            println!(
+                "{}",
+                format!(
+                    "{} problem(s) need a manual look (marked with !).",
+                    total - fixable
+                )
+                .truecolor(255, 165, 0)
+            );
+        }
+        Ok(fixable == total)
+    }
+
+    /// Shows what salvaging a damaged database keeps and drops, and asks before writing.
+    fn confirm_salvage(salvaged: &doctor::Salvage) -> Result<bool> {
+        for fragment in &salvaged.fragments {
+            let flat = fragment
+                .text
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            let shown: String = flat.chars().take(60).collect();
+            let more = if shown.len() < flat.len() { "..." } else { "" };
+            println!(
+                "  {} line {}: {shown}{more} {}",
+                "-".red(),
+                fragment.line,
+                format!("({})", fragment.reason).dimmed()
+            );
+        }
+        let recovered = format!(
+            "{}{}{}{}{}",
+            "Recovered ".truecolor(255, 165, 0),
+            salvaged.tasks.len().to_string().white(),
+            " task(s); ".truecolor(255, 165, 0),
+            salvaged.fragments.len().to_string().white(),
+            " damaged fragment(s) go to quarantine.".truecolor(255, 165, 0)
+        );
+        #[cfg(feature = "interactive")]
+        {
+            Self::read_confirmation(&format!(
+                "{recovered}{}",
+                " Replace the damaged database? [y/N]: ".truecolor(255, 165, 0)
+            ))
+        }
+        #[cfg(not(feature = "interactive"))]
+        {
+            println!("{recovered}");
+            Ok(true)
+        }
     }
 
     #[cfg(feature = "interactive")]
-    fn handle_skip_task_error(e: &anyhow::Error, id: u8) -> bool {
+    fn handle_skip_task_error(e: &anyhow::Error, id: TaskId) -> bool {
         if e.downcast_ref::<crate::error::AppError>() == Some(&crate::error::AppError::SkipTask) {
             println!("{} {}", "Skipped task:".yellow(), id);
             true
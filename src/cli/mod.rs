@@ -4,4 +4,6 @@ mod editor;
 mod formatter;
 mod handlers;
 
+pub use handlers::{ListOptions, ListSort, WaitingFilter};
+
 pub struct HandlerCLI;
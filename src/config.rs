@@ -0,0 +1,497 @@
+//! Optional settings from `config.toml` files.
+//!
+//! Two files are read: the user-global one (`<config dir>/rusk/config.toml`, or the file named by
+//! `RUSK_CONFIG`) and the project one next to the database in use (`.rusk/config.toml`). For each
+//! setting the first source that has it wins: command-line flag, environment variable, project
+//! file, user file, built-in default. A file that does not parse, an unknown key or a bad value is
+//! reported on stderr and ignored, so a typo never locks you out of your tasks.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use colored::*;
+use toml::{Table, Value};
+
+use crate::atomic_write;
+use crate::storage::TaskManager;
+
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// Which config file a value is written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// `config.toml` next to the database in use.
+    Project,
+    /// The user-global file.
+    User,
+}
+
+/// Where an effective value came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    File(Scope),
+    /// An environment variable that overrides the files.
+    Env(&'static str),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::File(Scope::Project) => write!(f, "project"),
+            Source::File(Scope::User) => write!(f, "user"),
+            Source::Env(var) => write!(f, "{var}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Bool,
+    /// A whole number of this unit (`seconds`, `days`) from 0 up to the maximum.
+    Count(&'static str, i64),
+    Choice(&'static [&'static str]),
+    /// `short` or a strftime pattern.
+    DateFormat,
+    Path,
+}
+
+/// A known configuration key.
+#[derive(Debug)]
+pub struct Setting {
+    /// Dotted key, e.g. `list.mode` (`[list]` table, `mode` entry in the file).
+    pub key: &'static str,
+    pub default: &'static str,
+    pub about: &'static str,
+    kind: Kind,
+    /// Only meaningful in the user file (the project file is found through the database).
+    user_only: bool,
+}
+
+/// Every key rusk reads, in `rusk config list` order.
+pub const SETTINGS: &[Setting] = &[
+    Setting {
+        key: "list.mode",
+        default: "full",
+        about: "How `rusk list` shows tasks without -c / --full: full or compact",
+        kind: Kind::Choice(&["full", "compact"]),
+        user_only: false,
+    },
+    Setting {
+        key: "confirm.delete",
+        default: "true",
+        about: "Ask before `rusk del` deletes tasks",
+        kind: Kind::Bool,
+        user_only: false,
+    },
+    Setting {
+        key: "editor.autosave_secs",
+        default: "3",
+        about: "Seconds between draft autosaves in the interactive editor; 0 turns autosave off",
+        kind: Kind::Count("seconds", 86_400),
+        user_only: false,
+    },
+    Setting {
+        key: "display.date_format",
+        default: "short",
+        about: "Due dates in lists: short (7-jan-26) or a strftime pattern such as %Y-%m-%d",
+        kind: Kind::DateFormat,
+        user_only: false,
+    },
+    Setting {
+        key: "display.color",
+        default: "auto",
+        about: "Colored output: auto, always or never; RUSK_NO_COLOR and NO_COLOR still turn it off",
+        kind: Kind::Choice(&["auto", "always", "never"]),
+        user_only: false,
+    },
+    Setting {
+        key: "ids.reuse",
+        default: "true",
+        about: "Give new tasks the lowest free ID; false: always one above any ID handed out before",
+        kind: Kind::Bool,
+        user_only: false,
+    },
+    Setting {
+        key: "archive.after_days",
+        default: "0",
+        about: "Move done tasks to the archive this many days after completion, after each command that changes the list except undo and redo; 0: never",
+        kind: Kind::Count("days", 36_500),
+        user_only: false,
+    },
+    Setting {
+        key: "database.path",
+        default: "",
+        about: "Database used when no .rusk directory is found (user config only)",
+        kind: Kind::Path,
+        user_only: true,
+    },
+];
+
+/// The known setting called `key`.
+pub fn setting(key: &str) -> Result<&'static Setting> {
+    SETTINGS.iter().find(|s| s.key == key).ok_or_else(|| {
+        let keys: Vec<_> = SETTINGS.iter().map(|s| s.key).collect();
+        anyhow::anyhow!(
+            "Unknown config key '{key}'. Known keys: {}",
+            keys.join(", ")
+        )
+    })
+}
+
+impl Setting {
+    /// Parses a value typed on the command line.
+    pub fn parse(&self, raw: &str) -> Result<Value> {
+        let value = match self.kind {
+            Kind::Bool => match raw.to_lowercase().as_str() {
+                "true" | "yes" | "on" | "1" => Value::Boolean(true),
+                "false" | "no" | "off" | "0" => Value::Boolean(false),
+                _ => anyhow::bail!("'{}' expects true or false, got '{raw}'", self.key),
+            },
+            Kind::Count(unit, _) => match raw.parse::<i64>() {
+                Ok(n) => Value::Integer(n),
+                Err(_) => anyhow::bail!("'{}' expects a number of {unit}, got '{raw}'", self.key),
+            },
+            _ => Value::String(raw.to_string()),
+        };
+        self.check(&value)?;
+        Ok(value)
+    }
+
+    /// Rejects values of the wrong type or outside the allowed set.
+    fn check(&self, value: &Value) -> Result<()> {
+        let ok = match (self.kind, value) {
+            (Kind::Bool, Value::Boolean(_)) => true,
+            (Kind::Count(_, max), Value::Integer(n)) => (0..=max).contains(n),
+            (Kind::Choice(choices), Value::String(s)) => choices.contains(&s.as_str()),
+            (Kind::DateFormat, Value::String(s)) => s == "short" || is_valid_strftime(s),
+            (Kind::Path, Value::String(s)) => !s.is_empty(),
+            _ => false,
+        };
+        if ok {
+            return Ok(());
+        }
+        let expected = match self.kind {
+            Kind::Bool => "true or false".to_string(),
+            Kind::Count(unit, max) => format!("a number of {unit} from 0 to {max}"),
+            Kind::Choice(choices) => choices.join(", "),
+            Kind::DateFormat => "short or a strftime pattern such as %Y-%m-%d".to_string(),
+            Kind::Path => "a file or directory path".to_string(),
+        };
+        anyhow::bail!(
+            "Invalid value {} for '{}': expected {expected}",
+            display_value(value),
+            self.key
+        )
+    }
+}
+
+/// Whether `pattern` formats a date. Time and zone specifiers such as `%H` or `%z` parse, but
+/// fail when given a date alone.
+fn is_valid_strftime(pattern: &str) -> bool {
+    use fmt::Write;
+    let Some(sample) = NaiveDate::from_ymd_opt(2026, 1, 7) else {
+        return false;
+    };
+    let mut formatted = String::new();
+    pattern.contains('%') && write!(formatted, "{}", sample.format(pattern)).is_ok()
+}
+
+/// A value as shown by `rusk config get` (strings without quotes).
+pub fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// One config file and the valid settings in it.
+#[derive(Debug, Clone)]
+struct ConfigFile {
+    path: PathBuf,
+    values: Vec<(&'static str, Value)>,
+}
+
+impl ConfigFile {
+    /// Reads `path`; a missing file is `None`, problems are warned about and skipped.
+    fn load(path: &Path, scope: Scope) -> Option<Self> {
+        let table = match read_table(path) {
+            Ok(Some(table)) => table,
+            Ok(None) => return None,
+            Err(e) => {
+                warn(&format!("ignoring '{}': {e:#}", path.display()));
+                return None;
+            }
+        };
+        let mut values = Vec::new();
+        for (key, value) in flatten(&table) {
+            let checked = setting(&key).and_then(|s| {
+                if s.user_only && scope == Scope::Project {
+                    anyhow::bail!("'{key}' only works in the user config");
+                }
+                s.check(&value).map(|_| (s.key, value))
+            });
+            match checked {
+                Ok(entry) => values.push(entry),
+                Err(e) => warn(&format!("{}: {e}", path.display())),
+            }
+        }
+        Some(Self {
+            path: path.to_path_buf(),
+            values,
+        })
+    }
+
+    fn get(&self, key: &str) -> Option<&Value> {
+        self.values.iter().find(|(k, _)| *k == key).map(|(_, v)| v)
+    }
+}
+
+fn warn(message: &str) {
+    eprintln!(
+        "{}",
+        format!("Warning: config: {message}").truecolor(255, 165, 0)
+    );
+}
+
+fn read_table(path: &Path) -> Result<Option<Table>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read '{}'", path.display())),
+    };
+    text.parse::<Table>().map(Some).context("invalid TOML")
+}
+
+/// `[list] mode = "x"` → `("list.mode", "x")`.
+fn flatten(table: &Table) -> Vec<(String, Value)> {
+    let mut out = Vec::new();
+    for (key, value) in table {
+        match value {
+            Value::Table(inner) => out.extend(
+                flatten(inner)
+                    .into_iter()
+                    .map(|(k, v)| (format!("{key}.{k}"), v)),
+            ),
+            other => out.push((key.clone(), other.clone())),
+        }
+    }
+    out
+}
+
+/// Settings from the project and user files.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    project: Option<ConfigFile>,
+    user: Option<ConfigFile>,
+}
+
+impl Config {
+    /// Reads the given files; either may be missing.
+    pub fn from_files(project: Option<&Path>, user: Option<&Path>) -> Self {
+        Self {
+            project: project.and_then(|p| ConfigFile::load(p, Scope::Project)),
+            user: user.and_then(|p| ConfigFile::load(p, Scope::User)),
+        }
+    }
+
+    /// Effective value of `key` and where it came from. Unknown keys are an error.
+    pub fn get(&self, key: &str) -> Result<(Value, Source)> {
+        let setting = setting(key)?;
+        if let Some(var) = env_override(key) {
+            return Ok((Value::String("never".to_string()), Source::Env(var)));
+        }
+        for (file, scope) in [(&self.project, Scope::Project), (&self.user, Scope::User)] {
+            if let Some(value) = file.as_ref().and_then(|f| f.get(key)) {
+                return Ok((value.clone(), Source::File(scope)));
+            }
+        }
+        Ok((default_value(setting), Source::Default))
+    }
+
+    /// Paths of the files that were read, project first.
+    pub fn files(&self) -> Vec<&Path> {
+        [&self.project, &self.user]
+            .into_iter()
+            .flatten()
+            .map(|f| f.path.as_path())
+            .collect()
+    }
+
+    fn value(&self, key: &str) -> Value {
+        self.get(key)
+            .map(|(value, _)| value)
+            .expect("known config key")
+    }
+
+    fn string(&self, key: &str) -> String {
+        display_value(&self.value(key))
+    }
+
+    /// `list.mode = "compact"`.
+    pub fn list_compact(&self) -> bool {
+        self.string("list.mode") == "compact"
+    }
+
+    /// `confirm.delete`.
+    pub fn confirm_delete(&self) -> bool {
+        self.value("confirm.delete").as_bool().unwrap_or(true)
+    }
+
+    /// `ids.reuse`.
+    pub fn reuse_ids(&self) -> bool {
+        self.value("ids.reuse").as_bool().unwrap_or(true)
+    }
+
+    /// `archive.after_days`; `None` when auto-archiving is off.
+    pub fn archive_after_days(&self) -> Option<i64> {
+        let days = self.value("archive.after_days").as_integer().unwrap_or(0);
+        (days > 0).then_some(days)
+    }
+
+    /// `editor.autosave_secs`; `None` when autosave is off.
+    pub fn autosave_interval(&self) -> Option<Duration> {
+        let secs = self.value("editor.autosave_secs").as_integer().unwrap_or(3);
+        (secs > 0).then(|| Duration::from_secs(secs as u64))
+    }
+
+    /// `display.date_format` as a strftime pattern; `None` for the built-in short format.
+    pub fn date_format(&self) -> Option<String> {
+        Some(self.string("display.date_format")).filter(|f| f != "short")
+    }
+
+    /// `display.color`: `Some(false)` for never, `Some(true)` for always, `None` for auto.
+    pub fn color(&self) -> Option<bool> {
+        match self.string("display.color").as_str() {
+            "never" => Some(false),
+            "always" => Some(true),
+            _ => None,
+        }
+    }
+
+    /// `database.path` from the user file.
+    pub fn database_path(&self) -> Option<PathBuf> {
+        self.user
+            .as_ref()
+            .and_then(|f| f.get("database.path"))
+            .and_then(Value::as_str)
+            .map(|p| match (p.strip_prefix("~/"), dirs::home_dir()) {
+                (Some(rest), Some(home)) => home.join(rest),
+                _ => PathBuf::from(p),
+            })
+    }
+}
+
+fn default_value(setting: &Setting) -> Value {
+    match setting.kind {
+        Kind::Bool => Value::Boolean(setting.default == "true"),
+        Kind::Count(..) => Value::Integer(setting.default.parse().unwrap_or(0)),
+        _ => Value::String(setting.default.to_string()),
+    }
+}
+
+/// Environment variables that beat the config files for `key`.
+fn env_override(key: &str) -> Option<&'static str> {
+    if key != "display.color" {
+        return None;
+    }
+    ["RUSK_NO_COLOR", "NO_COLOR"]
+        .into_iter()
+        .find(|var| std::env::var_os(var).is_some_and(|v| !v.is_empty()))
+}
+
+/// The user-global config file: `RUSK_CONFIG` when set, otherwise `rusk/config.toml` in the
+/// platform config directory. Tests only read one when `RUSK_CONFIG` points at it.
+pub fn user_config_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("RUSK_CONFIG").filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+    if TaskManager::is_test_mode() {
+        return None;
+    }
+    dirs::config_dir().map(|d| d.join("rusk").join(CONFIG_FILE_NAME))
+}
+
+/// The project config file for the database at `db_path`.
+pub fn project_config_path(db_path: &Path) -> PathBuf {
+    match db_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.join(CONFIG_FILE_NAME),
+        _ => PathBuf::from(CONFIG_FILE_NAME),
+    }
+}
+
+/// The user file alone, read once. Finding the database may need it (`database.path`), so it
+/// cannot wait for [`current`].
+fn user_only() -> &'static Config {
+    static USER: OnceLock<Config> = OnceLock::new();
+    USER.get_or_init(|| Config::from_files(None, user_config_path().as_deref()))
+}
+
+/// `database.path` from the user config, for [`TaskManager::locate_db_path`].
+pub fn fallback_db_path() -> Option<PathBuf> {
+    user_only().database_path()
+}
+
+/// Settings for this process, read on first use from the user file and the project file of the
+/// selected database.
+pub fn current() -> &'static Config {
+    static CURRENT: OnceLock<Config> = OnceLock::new();
+    CURRENT.get_or_init(|| match TaskManager::locate_db_path() {
+        Ok(db) => for_db(&db),
+        Err(_) => user_only().clone(),
+    })
+}
+
+/// Settings that apply to the database at `db_path`: the user file and its project file. For
+/// another database than the selected one, such as the other side of `rusk sync`.
+pub fn for_db(db_path: &Path) -> Config {
+    Config {
+        project: ConfigFile::load(&project_config_path(db_path), Scope::Project),
+        user: user_only().user.clone(),
+    }
+}
+
+/// Applies `display.color`. Called once at startup, after the database is selected.
+pub fn apply_color() {
+    if let Some(on) = current().color() {
+        colored::control::set_override(on);
+    }
+}
+
+/// Sets `key` in the config file of `scope` (creating it), keeping its other entries.
+pub fn set(path: &Path, scope: Scope, key: &str, raw: &str) -> Result<Value> {
+    let setting = setting(key)?;
+    if setting.user_only && scope == Scope::Project {
+        anyhow::bail!("'{key}' only works in the user config; use --global");
+    }
+    let value = setting.parse(raw)?;
+    let mut table = read_table(path)
+        .with_context(|| format!("Cannot update '{}'", path.display()))?
+        .unwrap_or_default();
+    let (section, name) = key.split_once('.').expect("config keys are dotted");
+    let inner = table
+        .entry(section)
+        .or_insert_with(|| Value::Table(Table::new()));
+    let Value::Table(inner) = inner else {
+        anyhow::bail!(
+            "'{section}' in '{}' is not a table; fix the file by hand",
+            path.display()
+        );
+    };
+    inner.insert(name.to_string(), value.clone());
+
+    if let Some(dir) = path.parent()
+        && !dir.as_os_str().is_empty()
+    {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create '{}'", dir.display()))?;
+    }
+    let text = toml::to_string(&table).context("Failed to write the config")?;
+    atomic_write::write_atomic(path, text.as_bytes())?;
+    Ok(value)
+}
@@ -0,0 +1,303 @@
+//! On-disk layout of the task database.
+//!
+//! Since format version 2 the file is an envelope object:
+//!
+//! ```json
+//! { "version": 2, "tasks": [ ... ], "deleted": [ ... ] }
+//! ```
+//!
+//! `deleted` holds a [`Tombstone`] for each removed task, so `rusk sync` can tell a deletion
+//! from a task the other database has not seen yet. `renumbered` records the ID mapping of each
+//! `rusk renumber`, and `last_id` the highest ID handed out while `ids.reuse` is off. Each is
+//! left out while empty.
+//!
+//! Version 1 is the original bare JSON array of tasks. Files are upgraded in memory by
+//! [`MIGRATIONS`] on load and written back in the current format on the next save. Fields this
+//! build does not know, both on the envelope and on individual tasks, are kept and written back
+//! unchanged so an older rusk does not drop data added by a newer one.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::model::{Task, TaskId};
+
+/// Format version written by this build.
+pub const DB_FORMAT_VERSION: u32 = 2;
+
+/// Version assumed for files without a `version` field (the bare array layout).
+const LEGACY_VERSION: u32 = 1;
+
+/// Upgrade steps: `MIGRATIONS[i]` turns a version `i + 1` document into version `i + 2`.
+const MIGRATIONS: [fn(Value) -> Result<Value>; (DB_FORMAT_VERSION - LEGACY_VERSION) as usize] =
+    [migrate_v1_to_v2];
+
+/// Envelope fields other than the tasks, carried from load to save.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbMeta {
+    /// Version the file had when it was read. Saving is refused when it is newer than
+    /// [`DB_FORMAT_VERSION`].
+    pub version: u32,
+    /// Tasks deleted from this database.
+    pub deleted: Vec<Tombstone>,
+    /// Highest ID handed out since IDs stopped being reused (0: not tracked).
+    pub last_id: TaskId,
+    /// ID mappings of past renumberings, oldest first.
+    pub renumbered: Vec<Renumbering>,
+    /// Unknown top-level fields, written back as they were.
+    pub extra: Map<String, Value>,
+}
+
+impl Default for DbMeta {
+    fn default() -> Self {
+        Self {
+            version: DB_FORMAT_VERSION,
+            deleted: Vec::new(),
+            last_id: 0,
+            renumbered: Vec::new(),
+            extra: Map::new(),
+        }
+    }
+}
+
+/// Record of a deleted task.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tombstone {
+    pub uuid: Uuid,
+    pub deleted_at: DateTime<Utc>,
+    /// The task was moved to the archive rather than deleted.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+}
+
+/// One `rusk renumber`: when, in which order, and the IDs that changed as `(old, new)`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Renumbering {
+    pub at: DateTime<Utc>,
+    pub order: String,
+    pub ids: Vec<(TaskId, TaskId)>,
+}
+
+impl DbMeta {
+    /// True when the file comes from a newer rusk whose format this build cannot write safely.
+    pub fn is_newer_than_supported(&self) -> bool {
+        self.version > DB_FORMAT_VERSION
+    }
+
+    /// Splits the known fields off the other envelope fields in `obj`. Known fields that do not
+    /// parse are dropped.
+    pub fn from_fields(version: u32, mut obj: Map<String, Value>) -> Self {
+        fn take<T: serde::de::DeserializeOwned + Default>(
+            obj: &mut Map<String, Value>,
+            key: &str,
+        ) -> T {
+            obj.remove(key)
+                .and_then(|v| serde_json::from_value(v).ok())
+                .unwrap_or_default()
+        }
+        Self {
+            version,
+            deleted: take(&mut obj, "deleted"),
+            last_id: take(&mut obj, "last_id"),
+            renumbered: take(&mut obj, "renumbered"),
+            extra: obj,
+        }
+    }
+}
+
+fn is_zero(id: &TaskId) -> bool {
+    *id == 0
+}
+
+#[derive(Serialize)]
+struct EnvelopeRef<'a> {
+    version: u32,
+    tasks: &'a [Task],
+    #[serde(skip_serializing_if = "<[Tombstone]>::is_empty")]
+    deleted: &'a [Tombstone],
+    #[serde(skip_serializing_if = "is_zero")]
+    last_id: TaskId,
+    #[serde(skip_serializing_if = "<[Renumbering]>::is_empty")]
+    renumbered: &'a [Renumbering],
+    #[serde(flatten)]
+    extra: &'a Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct Envelope {
+    version: u32,
+    tasks: Vec<Task>,
+    #[serde(default)]
+    deleted: Vec<Tombstone>,
+    #[serde(default)]
+    last_id: TaskId,
+    #[serde(default)]
+    renumbered: Vec<Renumbering>,
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+/// Version of a parsed document: bare arrays are version 1.
+fn document_version(doc: &Value) -> Result<u32> {
+    match doc {
+        Value::Array(_) => Ok(LEGACY_VERSION),
+        Value::Object(obj) => match obj.get("version") {
+            Some(v) => v
+                .as_u64()
+                .and_then(|v| u32::try_from(v).ok())
+                .filter(|v| *v >= LEGACY_VERSION)
+                .ok_or_else(|| anyhow::anyhow!("Invalid database format version: {v}")),
+            None => anyhow::bail!("Database object has no \"version\" field"),
+        },
+        _ => anyhow::bail!("Expected a task list or a versioned database object"),
+    }
+}
+
+/// 1 → 2: wrap the bare task array in an envelope and turn the old boolean `priority` into a
+/// level name (`true` → `high`).
+fn migrate_v1_to_v2(doc: Value) -> Result<Value> {
+    let Value::Array(mut tasks) = doc else {
+        anyhow::bail!("Version 1 database must be a task list");
+    };
+    for task in &mut tasks {
+        if let Some(p) = task.get_mut("priority")
+            && let Some(flag) = p.as_bool()
+        {
+            *p = Value::from(if flag { "high" } else { "none" });
+        }
+    }
+    let mut obj = Map::new();
+    obj.insert("version".to_string(), Value::from(2));
+    obj.insert("tasks".to_string(), Value::Array(tasks));
+    Ok(Value::Object(obj))
+}
+
+/// Runs the migrations that bring `doc` up to [`DB_FORMAT_VERSION`]. Documents from a newer
+/// version are returned as they are.
+pub fn upgrade(doc: Value) -> Result<Value> {
+    let mut version = document_version(&doc)?;
+    let mut doc = doc;
+    while version < DB_FORMAT_VERSION {
+        doc = MIGRATIONS[(version - LEGACY_VERSION) as usize](doc)?;
+        version += 1;
+    }
+    Ok(doc)
+}
+
+/// Upgrades `doc` to [`DB_FORMAT_VERSION`] and splits it into tasks and envelope metadata.
+/// Documents from a newer version are read as they are, without migrating.
+pub fn decode(doc: Value) -> Result<(Vec<Task>, DbMeta)> {
+    let doc = upgrade(doc)?;
+    let read_version = document_version(&doc)?;
+    let envelope: Envelope = serde_json::from_value(doc).map_err(|e| {
+        if read_version > DB_FORMAT_VERSION {
+            anyhow::anyhow!(
+                "Database format version {read_version} is newer than this rusk supports \
+                 (up to {DB_FORMAT_VERSION}) and could not be read: {e}. Upgrade rusk."
+            )
+        } else {
+            anyhow::anyhow!("{e}")
+        }
+    })?;
+    Ok((
+        envelope.tasks,
+        DbMeta {
+            version: envelope.version,
+            deleted: envelope.deleted,
+            last_id: envelope.last_id,
+            renumbered: envelope.renumbered,
+            extra: envelope.extra,
+        },
+    ))
+}
+
+/// Reads and decodes the task file at `path` for rewriting it, as the archive and the merge driver
+/// do. A missing or blank file reads as empty; a file from a newer rusk is refused.
+pub fn read_file(path: &Path) -> Result<(Vec<Task>, DbMeta)> {
+    let data = match fs::read_to_string(path) {
+        Ok(data) if data.trim().is_empty() => return Ok((Vec::new(), DbMeta::default())),
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok((Vec::new(), DbMeta::default()));
+        }
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read '{}'", path.display()));
+        }
+    };
+    let doc = serde_json::from_str(&data)
+        .with_context(|| format!("'{}' is not a valid task file", path.display()))?;
+    let (tasks, meta) =
+        decode(doc).with_context(|| format!("'{}' is not a valid task file", path.display()))?;
+    if meta.is_newer_than_supported() {
+        anyhow::bail!(
+            "'{}' was written by a newer rusk (format version {}); upgrade rusk to change it",
+            path.display(),
+            meta.version
+        );
+    }
+    Ok((tasks, meta))
+}
+
+/// File next to the database at `db_path` named after it plus `suffix`, such as
+/// `tasks.json.lock` for `.lock`.
+pub fn sibling_path(db_path: &Path, suffix: &str) -> PathBuf {
+    let mut name = db_path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    db_path.with_file_name(name)
+}
+
+/// Serializes `tasks` in the current format, keeping the unknown envelope fields of `meta`.
+pub fn encode(tasks: &[Task], meta: &DbMeta) -> Result<String> {
+    let envelope = EnvelopeRef {
+        version: DB_FORMAT_VERSION,
+        tasks,
+        deleted: &meta.deleted,
+        last_id: meta.last_id,
+        renumbered: &meta.renumbered,
+        extra: &meta.extra,
+    };
+    Ok(serde_json::to_string_pretty(&envelope)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DB_FORMAT_VERSION, decode, encode};
+    use serde_json::json;
+
+    #[test]
+    fn bare_array_is_migrated() {
+        let (tasks, meta) = decode(json!([
+            {"id": 1, "text": "a", "date": null, "done": false, "priority": true}
+        ]))
+        .unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].priority, crate::Priority::High);
+        assert_eq!(meta.version, DB_FORMAT_VERSION);
+    }
+
+    #[test]
+    fn unknown_fields_round_trip() {
+        let doc = json!({
+            "version": DB_FORMAT_VERSION,
+            "tasks": [{"id": 1, "text": "a", "date": null, "done": false, "estimate": "2h"}],
+            "views": {"work": "--tag work"}
+        });
+        let (tasks, meta) = decode(doc).unwrap();
+        let written: serde_json::Value =
+            serde_json::from_str(&encode(&tasks, &meta).unwrap()).unwrap();
+        assert_eq!(written["views"]["work"], "--tag work");
+        assert_eq!(written["tasks"][0]["estimate"], "2h");
+    }
+
+    #[test]
+    fn newer_version_is_flagged() {
+        let (_, meta) = decode(json!({"version": DB_FORMAT_VERSION + 1, "tasks": []})).unwrap();
+        assert!(meta.is_newer_than_supported());
+        assert!(decode(json!({"tasks": []})).is_err());
+    }
+}
@@ -0,0 +1,751 @@
+//! `rusk doctor`: integrity checks for the database and the files kept next to it.
+//!
+//! The database is read entry by entry rather than in one go, so a single bad task (an
+//! impossible date, a missing field) is reported on its own instead of making the whole file
+//! unreadable. [`repair`] renumbers duplicate and zero IDs, breaks dependency cycles, moves
+//! entries that cannot be kept into a quarantine file next to the database and removes leftover
+//! temporary and draft files.
+//! A file that is not valid JSON at all is salvaged object by object (see [`salvage`]), keeping
+//! whatever envelope fields still parse.
+
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use colored::*;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::atomic_write;
+use crate::backup;
+use crate::db_format::{self, DbMeta};
+use crate::journal::{self, EntryKind};
+use crate::model::{self, Task, TaskId};
+use crate::parser::tags::extract_tags;
+
+/// The newest backup is only flagged when restoring it would touch at least this many tasks
+/// (and more than half of them).
+const BACKUP_DIFF_MIN: usize = 5;
+
+/// One problem found by [`check`]. Entry numbers are 1-based positions in the task list.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Issue {
+    /// The file is not a task database this rusk can read; nothing else in it can be checked.
+    Unreadable(String),
+    /// The file is not valid JSON. [`salvage`] found `recovered` intact tasks and `fragments`
+    /// damaged pieces around them.
+    Corrupt {
+        reason: String,
+        recovered: usize,
+        fragments: usize,
+    },
+    /// Several tasks share a short ID.
+    DuplicateId { id: TaskId, count: usize },
+    /// A task has ID 0, which no command can address.
+    ZeroId { entry: usize },
+    /// A task has no text.
+    EmptyText { entry: usize, id: TaskId },
+    /// Task `id` waits for `blocker`, which in turn waits (maybe through others) for `id`.
+    DependencyCycle { id: TaskId, blocker: TaskId },
+    /// An entry that does not load as a task (impossible date, wrong field type, ...).
+    InvalidEntry {
+        entry: usize,
+        id: Option<u64>,
+        reason: String,
+    },
+    /// Restoring the newest backup would change more than half of the tasks.
+    BackupDiffers {
+        backup: PathBuf,
+        backup_tasks: usize,
+        live_tasks: usize,
+        changed: usize,
+    },
+    /// A temporary file left by a save that did not finish. It is only `removable` when the
+    /// database can be read (or salvaged): otherwise it may hold the only good copy.
+    StaleTempFile { path: PathBuf, removable: bool },
+    /// Autosaved text of an editor session that did not finish.
+    EditorDraft(PathBuf),
+}
+
+impl Issue {
+    /// Whether [`repair`] deals with this issue.
+    pub fn is_fixable(&self) -> bool {
+        match self {
+            Self::Unreadable(_) | Self::BackupDiffers { .. } => false,
+            Self::Corrupt { recovered, .. } => *recovered > 0,
+            Self::StaleTempFile { removable, .. } => *removable,
+            _ => true,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Unreadable(reason) => format!(
+                "The database cannot be read: {reason}; `rusk restore --list` shows backups"
+            ),
+            Self::Corrupt {
+                reason,
+                recovered,
+                fragments,
+            } => format!(
+                "The database is not valid JSON ({reason}); {recovered} task(s) can be salvaged \
+                 and {fragments} damaged fragment(s) would be moved to quarantine"
+            ),
+            Self::DuplicateId { id, count } => format!("ID {id} is used by {count} tasks"),
+            Self::ZeroId { entry } => format!("Entry {entry} has ID 0"),
+            Self::EmptyText { entry, id } => format!("Entry {entry} (ID {id}) has no text"),
+            Self::DependencyCycle { id, blocker } => format!(
+                "Task {id} waits for task {blocker}, which waits for task {id} again (directly or \
+                 through other tasks): a dependency cycle"
+            ),
+            Self::InvalidEntry { entry, id, reason } => match id {
+                Some(id) => format!("Entry {entry} (ID {id}) is invalid: {reason}"),
+                None => format!("Entry {entry} is invalid: {reason}"),
+            },
+            Self::BackupDiffers {
+                backup,
+                backup_tasks,
+                live_tasks,
+                changed,
+            } => format!(
+                "The newest backup '{}' differs from the database in {changed} tasks \
+                 ({backup_tasks} there, {live_tasks} here); check `rusk restore --list` if that \
+                 was not a bulk change of yours",
+                backup.display()
+            ),
+            Self::StaleTempFile {
+                path,
+                removable: true,
+            } => format!("Leftover temporary file '{}'", path.display()),
+            Self::StaleTempFile {
+                path,
+                removable: false,
+            } => format!(
+                "Leftover temporary file '{}'; kept while the database cannot be read, as it may \
+                 hold the latest tasks",
+                path.display()
+            ),
+            Self::EditorDraft(path) => {
+                format!("Leftover editor draft '{}'", path.display())
+            }
+        }
+    }
+}
+
+/// Result of [`check`]: the issues plus what [`repair`] needs to act on them.
+#[derive(Debug, Clone)]
+pub struct Diagnosis {
+    pub db_path: PathBuf,
+    pub issues: Vec<Issue>,
+    /// Task list entries in file order.
+    entries: Vec<Entry>,
+    /// `None` when the database could not be read at all.
+    meta: Option<DbMeta>,
+    /// What can be recovered when the file is not valid JSON.
+    pub salvage: Option<Salvage>,
+}
+
+impl Diagnosis {
+    /// Number of entries in the task list (loadable or not).
+    pub fn entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn fixable_count(&self) -> usize {
+        self.issues.iter().filter(|i| i.is_fixable()).count()
+    }
+}
+
+/// One task list entry as written, and the task it loads as (or why it does not).
+#[derive(Debug, Clone)]
+struct Entry {
+    raw: Value,
+    task: Result<Task, String>,
+}
+
+/// Tasks recovered from a file that is not valid JSON (see [`salvage`]).
+#[derive(Debug, Clone, Default)]
+pub struct Salvage {
+    pub tasks: Vec<Task>,
+    pub fragments: Vec<Fragment>,
+    /// Envelope fields that still parse; the defaults for a bare-array file.
+    pub meta: DbMeta,
+}
+
+/// A piece of a damaged file that is not a loadable task.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fragment {
+    /// 1-based line the fragment starts on.
+    pub line: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+/// What [`repair`] did.
+#[derive(Debug, Clone, Default)]
+pub struct RepairSummary {
+    /// Number of tasks written back when a damaged file was salvaged.
+    pub salvaged: Option<usize>,
+    /// `(old, new)` short IDs of renumbered tasks.
+    pub renumbered: Vec<(TaskId, TaskId)>,
+    /// `(task, blocker)` links dropped to break dependency cycles.
+    pub unblocked: Vec<(TaskId, TaskId)>,
+    /// Entries, fragments and drafts moved into the quarantine file.
+    pub quarantined: usize,
+    pub quarantine_path: Option<PathBuf>,
+    pub removed_files: Vec<PathBuf>,
+}
+
+/// Quarantine file for the database at `db_path` (`tasks.json` → `tasks.json.quarantine`). One
+/// JSON object per line with the time, the reason and the removed entry.
+pub fn quarantine_path(db_path: &Path) -> PathBuf {
+    db_format::sibling_path(db_path, ".quarantine")
+}
+
+fn dir_of(path: &Path) -> &Path {
+    match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    }
+}
+
+/// Where the interactive editor autosaves (see `cli::editor::draft::path_for`), plus the shared
+/// `editor.draft` older versions wrote for the default list.
+fn draft_paths(db_path: &Path) -> Vec<PathBuf> {
+    let legacy = (db_path.file_name().unwrap_or_default() == "tasks.json")
+        .then(|| dir_of(db_path).join("editor.draft"));
+    std::iter::once(db_format::sibling_path(db_path, ".draft"))
+        .chain(legacy)
+        .filter(|p| p.exists())
+        .collect()
+}
+
+/// Temporary files from unfinished saves: the unique names used now and the fixed
+/// `tasks.json.tmp` of older versions.
+fn temp_files(db_path: &Path) -> Vec<PathBuf> {
+    let name = db_path.file_name().unwrap_or_default().to_string_lossy();
+    let current_prefix = format!(".{name}.");
+    let legacy = db_path.with_extension("json.tmp");
+    let Ok(read_dir) = fs::read_dir(dir_of(db_path)) else {
+        return Vec::new();
+    };
+    let mut found: Vec<PathBuf> = read_dir
+        .flatten()
+        .filter(|e| {
+            let file = e.file_name().to_string_lossy().to_string();
+            (file.starts_with(&current_prefix) && file.ends_with(".tmp")) || e.path() == legacy
+        })
+        .map(|e| e.path())
+        .collect();
+    found.sort();
+    found
+}
+
+/// Why the date-like field `field` of `entry` cannot be read as a `T`, if it cannot.
+fn bad_field<T: DeserializeOwned>(entry: &Value, field: &str) -> Option<String> {
+    let value = entry.get(field).filter(|v| !v.is_null())?;
+    serde_json::from_value::<T>(value.clone())
+        .err()
+        .map(|_| format!("impossible date or time {value} in \"{field}\""))
+}
+
+/// Loads one raw entry the way the database loader does, explaining the first problem when it
+/// cannot be a task.
+fn load_entry(entry: &Value) -> Result<Task, String> {
+    let bad_date = bad_field::<NaiveDate>(entry, "date")
+        .or_else(|| bad_field::<NaiveDate>(entry, "wait"))
+        .or_else(|| bad_field::<NaiveTime>(entry, "time"))
+        .or_else(|| bad_field::<DateTime<Utc>>(entry, "created_at"))
+        .or_else(|| bad_field::<DateTime<Utc>>(entry, "updated_at"))
+        .or_else(|| bad_field::<DateTime<Utc>>(entry, "completed_at"));
+    if let Some(reason) = bad_date {
+        return Err(reason);
+    }
+    let mut task = serde_json::from_value::<Task>(entry.clone()).map_err(|e| e.to_string())?;
+    task.tags = extract_tags(&task.text);
+    if task.uuid.is_nil() {
+        task.uuid = Uuid::new_v4();
+    }
+    Ok(task)
+}
+
+fn loaded(entries: &[Entry]) -> Vec<Task> {
+    entries.iter().filter_map(|e| e.task.clone().ok()).collect()
+}
+
+/// Reads the database at `db_path` entry by entry and checks it and the files around it.
+pub fn check(db_path: &Path) -> Result<Diagnosis> {
+    let mut diagnosis = Diagnosis {
+        db_path: db_path.to_path_buf(),
+        issues: Vec::new(),
+        entries: Vec::new(),
+        meta: None,
+        salvage: None,
+    };
+    match read_entries(db_path) {
+        Ok((_, meta)) if meta.is_newer_than_supported() => {
+            diagnosis.issues.push(Issue::Unreadable(format!(
+                "it uses database format version {}, which is newer than this rusk understands",
+                meta.version
+            )));
+        }
+        Ok((entries, meta)) => {
+            diagnosis.entries = entries
+                .into_iter()
+                .map(|raw| Entry {
+                    task: load_entry(&raw),
+                    raw,
+                })
+                .collect();
+            diagnosis.meta = Some(meta);
+        }
+        Err(e) => match fs::read_to_string(db_path).map(|data| salvage_text(&data)) {
+            Ok(Some((_, salvaged))) if salvaged.meta.is_newer_than_supported() => {
+                diagnosis.issues.push(Issue::Unreadable(format!(
+                    "it is damaged and uses database format version {}, which is newer than this \
+                     rusk understands",
+                    salvaged.meta.version
+                )));
+            }
+            Ok(Some((reason, salvaged))) => {
+                diagnosis.issues.push(Issue::Corrupt {
+                    reason,
+                    recovered: salvaged.tasks.len(),
+                    fragments: salvaged.fragments.len(),
+                });
+                diagnosis.salvage = Some(salvaged);
+            }
+            _ => diagnosis.issues.push(Issue::Unreadable(format!("{e:#}"))),
+        },
+    }
+
+    let mut ids: Vec<(TaskId, usize)> = Vec::new();
+    for (i, entry) in diagnosis.entries.iter().enumerate() {
+        let entry_no = i + 1;
+        match &entry.task {
+            Ok(task) => {
+                if task.id == 0 {
+                    diagnosis.issues.push(Issue::ZeroId { entry: entry_no });
+                } else {
+                    match ids.iter_mut().find(|(id, _)| *id == task.id) {
+                        Some((_, count)) => *count += 1,
+                        None => ids.push((task.id, 1)),
+                    }
+                }
+                if task.text.trim().is_empty() {
+                    diagnosis.issues.push(Issue::EmptyText {
+                        entry: entry_no,
+                        id: task.id,
+                    });
+                }
+            }
+            Err(reason) => diagnosis.issues.push(Issue::InvalidEntry {
+                entry: entry_no,
+                id: entry.raw.get("id").and_then(Value::as_u64),
+                reason: reason.clone(),
+            }),
+        }
+    }
+    for (id, count) in ids.into_iter().filter(|(_, count)| *count > 1) {
+        diagnosis.issues.push(Issue::DuplicateId { id, count });
+    }
+    let tasks = loaded(&diagnosis.entries);
+    for (i, blocker) in model::cycle_links(&tasks) {
+        if let Some(b) = tasks.iter().find(|t| t.uuid == blocker) {
+            diagnosis.issues.push(Issue::DependencyCycle {
+                id: tasks[i].id,
+                blocker: b.id,
+            });
+        }
+    }
+
+    if diagnosis.meta.is_some() {
+        check_backup(&mut diagnosis)?;
+    }
+    // `repair` removes them once the database is readable, after a salvage if need be.
+    let removable = diagnosis.meta.is_some()
+        || diagnosis
+            .salvage
+            .as_ref()
+            .is_some_and(|salvaged| !salvaged.tasks.is_empty());
+    for path in temp_files(db_path) {
+        diagnosis
+            .issues
+            .push(Issue::StaleTempFile { path, removable });
+    }
+    for draft in draft_paths(db_path) {
+        diagnosis.issues.push(Issue::EditorDraft(draft));
+    }
+    Ok(diagnosis)
+}
+
+/// The raw task entries and envelope fields of the database, upgraded to the current format.
+fn read_entries(db_path: &Path) -> Result<(Vec<Value>, DbMeta)> {
+    if !db_path.exists() {
+        return Ok((Vec::new(), DbMeta::default()));
+    }
+    let data = fs::read_to_string(db_path).context("Failed to read the database file")?;
+    let doc: Value = serde_json::from_str(&data).context("Invalid JSON")?;
+    let Value::Object(mut obj) = db_format::upgrade(doc)? else {
+        anyhow::bail!("Expected a versioned database object");
+    };
+    let version = obj
+        .remove("version")
+        .and_then(|v| v.as_u64())
+        .and_then(|v| u32::try_from(v).ok())
+        .unwrap_or(db_format::DB_FORMAT_VERSION);
+    let entries = match obj.remove("tasks") {
+        Some(Value::Array(entries)) => entries,
+        Some(_) => anyhow::bail!("\"tasks\" is not a list"),
+        None => anyhow::bail!("Database object has no \"tasks\" field"),
+    };
+    Ok((entries, DbMeta::from_fields(version, obj)))
+}
+
+/// The JSON error and what [`salvage`] recovers, when `data` is not valid JSON.
+fn salvage_text(data: &str) -> Option<(String, Salvage)> {
+    let err = serde_json::from_str::<Value>(data).err()?;
+    Some((err.to_string(), salvage(data)))
+}
+
+/// Byte index just past the object that starts with the `{` at `start`, or `None` when the text
+/// ends first.
+fn object_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, &b) in bytes.iter().enumerate().skip(start) {
+        if in_string {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match b {
+            b'"' => in_string = true,
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// First position at or after `from` where a list entry may start: a `{` that follows a `,` or
+/// the opening `[` (nested objects follow a `:`).
+fn next_entry_start(bytes: &[u8], from: usize) -> Option<usize> {
+    (from..bytes.len()).find(|&i| {
+        bytes[i] == b'{'
+            && matches!(
+                bytes[..i].iter().rev().find(|c| !c.is_ascii_whitespace()),
+                Some(b',' | b'[')
+            )
+    })
+}
+
+/// The first JSON value at the start of `text` and the byte length it takes up.
+fn leading_value(text: &str) -> Option<(Value, usize)> {
+    let mut values = serde_json::Deserializer::from_str(text).into_iter::<Value>();
+    let value = values.next()?.ok()?;
+    Some((value, values.byte_offset()))
+}
+
+/// Envelope fields of a damaged database: the top-level fields that parse, up to the first
+/// that does not. The task list is skipped up to `list_end`, just past its closing `]`; when it
+/// is not closed, only the fields before it are kept.
+fn salvage_meta(data: &str, list_end: Option<usize>) -> DbMeta {
+    let mut fields = Map::new();
+    let mut pos = data.find('{').map_or(data.len(), |i| i + 1);
+    loop {
+        let rest = data[pos..].trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        let Some((Value::String(key), key_len)) = leading_value(rest) else {
+            break;
+        };
+        let Some(value) = rest[key_len..].trim_start().strip_prefix(':') else {
+            break;
+        };
+        if key == "tasks" {
+            match list_end {
+                Some(end) => {
+                    pos = end;
+                    continue;
+                }
+                None => break,
+            }
+        }
+        let Some((value_json, value_len)) = leading_value(value) else {
+            break;
+        };
+        fields.insert(key, value_json);
+        pos = data.len() - value.len() + value_len;
+    }
+    let version = fields
+        .remove("version")
+        .and_then(|v| v.as_u64())
+        .and_then(|v| u32::try_from(v).ok())
+        .unwrap_or(db_format::DB_FORMAT_VERSION);
+    DbMeta::from_fields(version, fields)
+}
+
+/// Recovers the tasks of a damaged database by parsing its task list one object at a time.
+/// Objects that do not parse or load are returned as fragments, along with any other text
+/// between them; after a broken object, parsing resumes at the next entry start. The envelope
+/// fields around the list are kept when they still parse.
+pub fn salvage(data: &str) -> Salvage {
+    let mut out = Salvage::default();
+    let bytes = data.as_bytes();
+    let legacy = data.trim_start().starts_with('[');
+    let list_start = if legacy {
+        data.find('[')
+    } else {
+        data.find("\"tasks\"")
+            .and_then(|key| data[key..].find('[').map(|i| key + i))
+    };
+    let Some(list_start) = list_start else {
+        return out;
+    };
+    let line_of = |at: usize| data[..at].matches('\n').count() + 1;
+    let mut fragment = |from: usize, to: usize, reason: &str| {
+        let text = data[from..to].trim_matches(|c: char| c.is_whitespace() || c == ',');
+        if !text.is_empty() {
+            let skipped = data[from..].len() - data[from..].trim_start().len();
+            out.fragments.push(Fragment {
+                line: line_of(from + skipped),
+                text: text.to_string(),
+                reason: reason.to_string(),
+            });
+        }
+    };
+
+    let mut tasks = Vec::new();
+    let mut pos = list_start + 1;
+    // The list ends at a `]` between entries; objects after it belong to the envelope.
+    while !data[pos..].trim_start().starts_with(']')
+        && let Some(start) = next_entry_start(bytes, pos)
+    {
+        fragment(pos, start, "text between tasks");
+        let parsed = object_end(bytes, start).and_then(|end| {
+            serde_json::from_str::<Value>(&data[start..end])
+                .ok()
+                .map(|raw| (end, raw))
+        });
+        let Some((end, raw)) = parsed else {
+            let end = next_entry_start(bytes, start + 1).unwrap_or(bytes.len());
+            fragment(start, end, "not valid JSON");
+            pos = end;
+            continue;
+        };
+        // Entries of a bare-array file still need the version 1 migration.
+        let entry = if legacy {
+            db_format::upgrade(Value::Array(vec![raw]))
+                .ok()
+                .and_then(|mut doc| doc["tasks"].get_mut(0).map(Value::take))
+        } else {
+            Some(raw)
+        };
+        match entry
+            .ok_or_else(|| "cannot be upgraded".to_string())
+            .and_then(|e| load_entry(&e))
+        {
+            Ok(task) => tasks.push(task),
+            Err(reason) => fragment(start, end, &reason),
+        }
+        pos = end;
+    }
+    let rest = data[pos..].trim_start();
+    let list_end = rest.starts_with(']').then(|| data.len() - rest.len() + 1);
+    if list_end.is_none() {
+        fragment(pos, bytes.len(), "text after the last task");
+    }
+    out.tasks = tasks;
+    if !legacy {
+        out.meta = salvage_meta(data, list_end);
+    }
+    out
+}
+
+/// Replaces the damaged database with the salvaged tasks and envelope fields, after backing up
+/// the original and moving the fragments into `quarantine_file`. Not journaled: there is no
+/// readable state for an undo to return to (the backup keeps the damaged file).
+fn write_salvage(db_path: &Path, salvaged: &Salvage, quarantine_file: &Path) -> Result<()> {
+    if salvaged.meta.is_newer_than_supported() {
+        anyhow::bail!(
+            "Not salvaging: the database uses format version {}, which is newer than this rusk \
+             understands",
+            salvaged.meta.version
+        );
+    }
+    if let Err(e) = backup::create_backup(db_path, backup::backup_retention().max(1)) {
+        anyhow::bail!("Not salvaging without a backup of the damaged file: {e:#}");
+    }
+    for fragment in &salvaged.fragments {
+        quarantine(
+            quarantine_file,
+            &format!("line {}: {}", fragment.line, fragment.reason),
+            Value::String(fragment.text.clone()),
+        )?;
+    }
+    let data =
+        db_format::encode(&salvaged.tasks, &salvaged.meta).context("Failed to serialize tasks")?;
+    atomic_write::write_atomic(db_path, data.as_bytes())
+        .context("Failed to save the salvaged database")
+}
+
+fn check_backup(diagnosis: &mut Diagnosis) -> Result<()> {
+    let Some(newest) = backup::list_backups(&diagnosis.db_path)?.into_iter().next() else {
+        return Ok(());
+    };
+    // An unreadable backup is usually the copy of a broken file taken by a repair.
+    let Ok(backup_tasks) = crate::TaskManager::load_tasks_from_path(&newest.path) else {
+        return Ok(());
+    };
+    let live = loaded(&diagnosis.entries);
+    let diff = backup::diff_tasks(&live, &backup_tasks);
+    let changed = diff.added.len() + diff.removed.len() + diff.changed.len();
+    if changed >= BACKUP_DIFF_MIN && changed * 2 > live.len().max(backup_tasks.len()) {
+        diagnosis.issues.push(Issue::BackupDiffers {
+            backup: newest.path,
+            backup_tasks: backup_tasks.len(),
+            live_tasks: live.len(),
+            changed,
+        });
+    }
+    Ok(())
+}
+
+fn quarantine(path: &Path, reason: &str, entry: Value) -> Result<()> {
+    let line = serde_json::json!({
+        "at": chrono::Local::now().to_rfc3339(),
+        "reason": reason,
+        "entry": entry,
+    });
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open '{}'", path.display()))?;
+    writeln!(file, "{line}").with_context(|| format!("Failed to write '{}'", path.display()))?;
+    file.sync_all()
+        .with_context(|| format!("Failed to write '{}'", path.display()))
+}
+
+/// Fixes the fixable issues of `diagnosis`. The caller must hold the database lock. Removed
+/// entries and drafts are written to the quarantine file before the database is replaced, and
+/// the repair is journaled like any other change so `rusk undo` can revert it.
+pub fn repair(diagnosis: &Diagnosis) -> Result<RepairSummary> {
+    let mut summary = RepairSummary::default();
+    let db_path = &diagnosis.db_path;
+    let quarantine_file = quarantine_path(db_path);
+
+    if let Some(salvaged) = &diagnosis.salvage
+        && diagnosis
+            .issues
+            .iter()
+            .any(|i| matches!(i, Issue::Corrupt { .. }) && i.is_fixable())
+    {
+        write_salvage(db_path, salvaged, &quarantine_file)?;
+        // The salvaged tasks may still need the regular repairs.
+        let mut summary = repair(&check(db_path)?)?;
+        summary.salvaged = Some(salvaged.tasks.len());
+        summary.quarantined += salvaged.fragments.len();
+        if summary.quarantined > 0 {
+            summary.quarantine_path = Some(quarantine_file);
+        }
+        return Ok(summary);
+    }
+
+    if let Some(meta) = &diagnosis.meta {
+        let before = loaded(&diagnosis.entries);
+        let mut kept = Vec::new();
+        for (i, entry) in diagnosis.entries.iter().enumerate() {
+            let reason = match &entry.task {
+                Ok(task) if !task.text.trim().is_empty() => {
+                    kept.push(task.clone());
+                    continue;
+                }
+                Ok(_) => format!("entry {}: empty text", i + 1),
+                Err(reason) => format!("entry {}: {reason}", i + 1),
+            };
+            quarantine(&quarantine_file, &reason, entry.raw.clone())?;
+            summary.quarantined += 1;
+        }
+
+        // Later duplicates and ID 0 get fresh IDs above the highest one in use.
+        let mut seen = HashSet::new();
+        let mut next = kept.iter().map(|t| t.id).max().unwrap_or(0);
+        for task in &mut kept {
+            if task.id != 0 && seen.insert(task.id) {
+                continue;
+            }
+            next += 1;
+            summary.renumbered.push((task.id, next));
+            task.id = next;
+            task.touch();
+        }
+
+        summary.unblocked = model::break_cycles(&mut kept);
+        for (id, _) in &summary.unblocked {
+            if let Some(task) = kept.iter_mut().find(|t| t.id == *id) {
+                task.touch();
+            }
+        }
+
+        if summary.quarantined > 0
+            || !summary.renumbered.is_empty()
+            || !summary.unblocked.is_empty()
+        {
+            if let Err(e) = backup::create_backup(db_path, backup::backup_retention().max(1)) {
+                anyhow::bail!("Not repairing without a backup of the current file: {e:#}");
+            }
+            let data = db_format::encode(&kept, meta).context("Failed to serialize tasks")?;
+            atomic_write::write_atomic(db_path, data.as_bytes())
+                .context("Failed to save the repaired database")?;
+            let changes = journal::diff(&before, &kept);
+            if !changes.is_empty()
+                && let Err(e) =
+                    journal::append(db_path, EntryKind::Op, journal::current_command(), changes)
+            {
+                eprintln!(
+                    "{}",
+                    format!("Warning: Failed to write the undo journal: {e}").yellow()
+                );
+            }
+        }
+
+        // Temporary files may hold the only copy of data when the database itself is unreadable.
+        for path in temp_files(db_path) {
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove '{}'", path.display()))?;
+            summary.removed_files.push(path);
+        }
+    }
+
+    for draft in draft_paths(db_path) {
+        let Ok(text) = fs::read_to_string(&draft) else {
+            continue;
+        };
+        let entry = serde_json::from_str(&text).unwrap_or(Value::String(text));
+        quarantine(&quarantine_file, "editor draft", entry)?;
+        summary.quarantined += 1;
+        fs::remove_file(&draft)
+            .with_context(|| format!("Failed to remove '{}'", draft.display()))?;
+        summary.removed_files.push(draft);
+    }
+
+    if summary.quarantined > 0 {
+        summary.quarantine_path = Some(quarantine_file);
+    }
+    Ok(summary)
+}
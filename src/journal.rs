@@ -0,0 +1,299 @@
+//! Append-only operation journal behind `rusk undo`, `rusk redo` and `rusk log`.
+//!
+//! Every save that changes tasks appends one JSON line to `<db file>.journal` with the before
+//! and after state of each task it touched (matched by UUID) and its position in the list.
+//! Undo and redo are journaled too, as entries pointing at the operation they reverse or
+//! reapply, so the undo/redo stacks can be rebuilt from the file alone. Only the newest
+//! [`JOURNAL_RETENTION`] entries are kept.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::atomic_write;
+use crate::db_format;
+use crate::model::Task;
+
+/// Entries kept in the journal; older ones are dropped, taking their undo history with them.
+pub const JOURNAL_RETENTION: u64 = 1000;
+
+/// The journal is trimmed back to [`JOURNAL_RETENTION`] entries every this many appends.
+const TRIM_EVERY: u64 = 100;
+
+/// One task touched by an operation. `before` is `None` for added tasks, `after` for deleted
+/// ones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskChange {
+    pub before: Option<Task>,
+    pub after: Option<Task>,
+    /// Position in the task list before the operation (for putting deleted tasks back).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before_index: Option<usize>,
+    /// Position after the operation (for redoing an add in place).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after_index: Option<usize>,
+}
+
+/// What produced an entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind", content = "target")]
+pub enum EntryKind {
+    /// A regular command.
+    Op,
+    /// `rusk undo` of the entry with this sequence number.
+    Undo(u64),
+    /// `rusk redo` of the entry with this sequence number.
+    Redo(u64),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub seq: u64,
+    pub at: DateTime<Utc>,
+    /// Command line that made the change, e.g. `del --done`.
+    pub command: String,
+    #[serde(flatten)]
+    pub kind: EntryKind,
+    pub changes: Vec<TaskChange>,
+}
+
+impl JournalEntry {
+    /// Counts of `(added, removed, changed)` tasks.
+    pub fn counts(&self) -> (usize, usize, usize) {
+        let mut counts = (0, 0, 0);
+        for change in &self.changes {
+            match (&change.before, &change.after) {
+                (None, Some(_)) => counts.0 += 1,
+                (Some(_), None) => counts.1 += 1,
+                _ => counts.2 += 1,
+            }
+        }
+        counts
+    }
+}
+
+/// Journal path for the database at `db_path` (`tasks.json` → `tasks.json.journal`).
+pub fn journal_path(db_path: &Path) -> PathBuf {
+    db_format::sibling_path(db_path, ".journal")
+}
+
+/// All entries, oldest first. Lines that do not parse (e.g. a torn final write) are skipped.
+pub fn read_journal(db_path: &Path) -> Result<Vec<JournalEntry>> {
+    let path = journal_path(db_path);
+    let data = match fs::read_to_string(&path) {
+        Ok(d) => d,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read '{}'", path.display()));
+        }
+    };
+    Ok(data
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect())
+}
+
+/// Appends an entry with the next sequence number and returns it.
+pub fn append(
+    db_path: &Path,
+    kind: EntryKind,
+    command: String,
+    changes: Vec<TaskChange>,
+) -> Result<JournalEntry> {
+    let path = journal_path(db_path);
+    let seq = match last_entry(&path)? {
+        Some(last) => last.seq + 1,
+        // Empty, missing, or ending in a torn line: fall back to the last entry that parses.
+        None => read_journal(db_path)?.last().map_or(1, |e| e.seq + 1),
+    };
+    let entry = JournalEntry {
+        seq,
+        at: Utc::now(),
+        command,
+        kind,
+        changes,
+    };
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open '{}'", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(&entry)?)
+        .and_then(|_| file.sync_all())
+        .with_context(|| format!("Failed to append to '{}'", path.display()))?;
+    if seq % TRIM_EVERY == 0 && seq > JOURNAL_RETENTION {
+        trim(db_path, seq - JOURNAL_RETENTION + 1)?;
+    }
+    Ok(entry)
+}
+
+/// The entry on the last line of the journal at `path`; `None` when there is none or it does
+/// not parse.
+fn last_entry(path: &Path) -> Result<Option<JournalEntry>> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read '{}'", path.display()));
+        }
+    };
+    let line =
+        last_line(&mut file).with_context(|| format!("Failed to read '{}'", path.display()))?;
+    Ok(serde_json::from_slice(&line).ok())
+}
+
+/// Last non-empty line of `file`, read backwards from the end so a long journal is not read
+/// whole.
+fn last_line(file: &mut File) -> std::io::Result<Vec<u8>> {
+    const CHUNK: u64 = 8192;
+    let mut tail: Vec<u8> = Vec::new();
+    let mut start = file.seek(SeekFrom::End(0))?;
+    while start > 0 && !tail.trim_ascii_end().contains(&b'\n') {
+        let from = start.saturating_sub(CHUNK);
+        let mut chunk = vec![0; (start - from) as usize];
+        file.seek(SeekFrom::Start(from))?;
+        file.read_exact(&mut chunk)?;
+        chunk.extend_from_slice(&tail);
+        tail = chunk;
+        start = from;
+    }
+    let body = tail.trim_ascii_end();
+    let line = body.rsplit(|&b| b == b'\n').next().unwrap_or_default();
+    Ok(line.to_vec())
+}
+
+/// Rewrites the journal without the entries numbered below `first_kept`.
+fn trim(db_path: &Path, first_kept: u64) -> Result<()> {
+    let mut data = String::new();
+    for entry in read_journal(db_path)?
+        .into_iter()
+        .filter(|e| e.seq >= first_kept)
+    {
+        data.push_str(&serde_json::to_string(&entry)?);
+        data.push('\n');
+    }
+    atomic_write::write_atomic(&journal_path(db_path), data.as_bytes())
+        .context("Failed to trim the undo journal")
+}
+
+/// The command line of this process without the program name, for [`JournalEntry::command`].
+pub fn current_command() -> String {
+    std::env::args().skip(1).collect::<Vec<_>>().join(" ")
+}
+
+/// Tasks that differ between `before` and `after`, matched by UUID.
+pub fn diff(before: &[Task], after: &[Task]) -> Vec<TaskChange> {
+    let mut changes = Vec::new();
+    for (i, old) in before.iter().enumerate() {
+        match after.iter().position(|t| t.uuid == old.uuid) {
+            Some(j) if after[j] == *old => {}
+            Some(j) => changes.push(TaskChange {
+                before: Some(old.clone()),
+                after: Some(after[j].clone()),
+                before_index: Some(i),
+                after_index: Some(j),
+            }),
+            None => changes.push(TaskChange {
+                before: Some(old.clone()),
+                after: None,
+                before_index: Some(i),
+                after_index: None,
+            }),
+        }
+    }
+    for (j, new) in after.iter().enumerate() {
+        if !before.iter().any(|t| t.uuid == new.uuid) {
+            changes.push(TaskChange {
+                before: None,
+                after: Some(new.clone()),
+                before_index: None,
+                after_index: Some(j),
+            });
+        }
+    }
+    changes
+}
+
+/// Sequence numbers of operations that can be undone (oldest first) and of undone operations
+/// that can be redone (most recently undone last). A new operation clears the redo stack.
+pub fn undo_redo_stacks(entries: &[JournalEntry]) -> (Vec<u64>, Vec<u64>) {
+    let mut done = Vec::new();
+    let mut undone = Vec::new();
+    for entry in entries {
+        match entry.kind {
+            EntryKind::Op => {
+                done.push(entry.seq);
+                undone.clear();
+            }
+            EntryKind::Undo(target) => {
+                done.retain(|s| *s != target);
+                undone.push(target);
+            }
+            EntryKind::Redo(target) => {
+                undone.retain(|s| *s != target);
+                done.push(target);
+            }
+        }
+    }
+    (done, undone)
+}
+
+/// Tasks whose current state is not what `changes` expects to find (`expect_after`: the
+/// state after the operation, for undo; otherwise the state before it, for redo).
+pub fn diverged(tasks: &[Task], changes: &[TaskChange], expect_after: bool) -> Vec<Task> {
+    let mut out = Vec::new();
+    for change in changes {
+        let (expected, other) = if expect_after {
+            (&change.after, &change.before)
+        } else {
+            (&change.before, &change.after)
+        };
+        let uuid = expected.as_ref().or(other.as_ref()).map(|t| t.uuid);
+        let current = tasks.iter().find(|t| Some(t.uuid) == uuid);
+        match (expected, current) {
+            (Some(exp), Some(cur)) if exp != cur => out.push(cur.clone()),
+            (Some(exp), None) => out.push(exp.clone()),
+            (None, Some(cur)) => out.push(cur.clone()),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Moves `tasks` from the after state of `changes` to the before state (`reverse`) or the
+/// other way round.
+pub fn apply(tasks: &mut Vec<Task>, changes: &[TaskChange], reverse: bool) {
+    let target = |c: &TaskChange| {
+        if reverse {
+            (c.before.clone(), c.before_index)
+        } else {
+            (c.after.clone(), c.after_index)
+        }
+    };
+    // Remove every touched task first, then put the target states back in list order.
+    for change in changes {
+        let uuid = change
+            .before
+            .as_ref()
+            .or(change.after.as_ref())
+            .map(|t| t.uuid);
+        tasks.retain(|t| Some(t.uuid) != uuid);
+    }
+    let mut inserts: Vec<(usize, Task)> = changes
+        .iter()
+        .filter_map(|c| match target(c) {
+            (Some(task), index) => Some((index.unwrap_or(usize::MAX), task)),
+            (None, _) => None,
+        })
+        .collect();
+    inserts.sort_by_key(|(index, _)| *index);
+    for (index, task) in inserts {
+        let at = index.min(tasks.len());
+        tasks.insert(at, task);
+    }
+}
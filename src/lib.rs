@@ -1,17 +1,32 @@
+pub mod archive;
 pub mod args;
+pub mod atomic_write;
+pub mod backup;
 pub mod cli;
 #[cfg(feature = "completions")]
 pub mod completions;
+pub mod config;
+pub mod db_format;
+pub mod doctor;
 pub mod error;
+pub mod journal;
+pub mod lock;
+pub mod merge;
 pub mod model;
 pub mod parser;
 pub mod storage;
+pub mod sync;
 pub mod windows_console;
 
-pub use model::Task;
+pub use model::{Priority, Recurrence, RecurrenceMode, Task, TaskId};
 pub use parser::{
-    BareEditDateFlag, EditArgs, is_cli_date_help_value, normalize_date_string, parse_cli_date,
-    parse_cli_date_for_edit, parse_cli_date_optional_empty, parse_cli_date_with_base,
-    parse_edit_args, parse_flexible_ids, strip_edit_date_flag, validate_cli_date_edit_arg,
+    BareEditDateFlag, EditArgs, extract_tags, find_out_of_range_id, is_cli_date_help_value,
+    is_uuid_prefix_token, normalize_date_string, parse_cli_date, parse_cli_date_for_edit,
+    parse_cli_date_optional_empty, parse_cli_date_with_base, parse_cli_due_for_edit,
+    parse_cli_time, parse_edit_args, parse_flexible_ids, parse_leading_due, parse_tag_filter,
+    strip_edit_date_flag, tag_char_spans, validate_cli_date_edit_arg,
+};
+pub use storage::{
+    DbSelection, EditConflict, MarkResult, NewTaskOptions, RenumberOrder, SubtaskPolicy, TaskEdit,
+    TaskManager, UnarchiveResult,
 };
-pub use storage::{MarkResult, TaskManager};
@@ -0,0 +1,240 @@
+//! Advisory lock around a database's read-modify-write cycle.
+//!
+//! The lock is a `<db>.lock` file next to the database, created exclusively. It records who holds
+//! it so a waiting process can say what it is waiting for. The file is removed when the
+//! [`DbLock`] is dropped; a lock left behind by a process that no longer runs is taken over.
+//!
+//! The holder also keeps an OS lock on the file. A takeover rewrites the stale file in place
+//! under that OS lock, so when several processes find the same stale lock only one of them gets
+//! it.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+use crate::db_format;
+
+/// How long to wait for another process when `RUSK_LOCK_TIMEOUT` is not set.
+pub const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Who holds a lock, as written into the lock file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockHolder {
+    pub pid: u32,
+    /// Command line of the holder, e.g. `rusk edit 3`.
+    pub command: String,
+    /// Local time the lock was taken (RFC 3339).
+    pub since: String,
+}
+
+impl LockHolder {
+    fn current() -> Self {
+        let mut args = std::env::args();
+        let program = args
+            .next()
+            .map(|p| {
+                Path::new(&p)
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or(p)
+            })
+            .unwrap_or_else(|| "rusk".to_string());
+        let command = std::iter::once(program)
+            .chain(args)
+            .collect::<Vec<_>>()
+            .join(" ");
+        Self {
+            pid: std::process::id(),
+            command,
+            since: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+        }
+    }
+
+    /// `` `rusk edit 3` (pid 1234, since 14:02:11) ``
+    pub fn describe(&self) -> String {
+        let since = chrono::DateTime::parse_from_rfc3339(&self.since)
+            .map(|t| t.format("%H:%M:%S").to_string())
+            .unwrap_or_else(|_| self.since.clone());
+        format!("`{}` (pid {}, since {since})", self.command, self.pid)
+    }
+}
+
+/// An acquired lock; released when dropped.
+#[derive(Debug)]
+pub struct DbLock {
+    path: PathBuf,
+    /// Kept open, with the OS lock on it, until the lock is released.
+    _file: File,
+}
+
+/// Lock file path for the database at `db_path` (`tasks.json` → `tasks.json.lock`).
+pub fn lock_path(db_path: &Path) -> PathBuf {
+    db_format::sibling_path(db_path, ".lock")
+}
+
+/// Wait limit from `RUSK_LOCK_TIMEOUT` (seconds), or [`DEFAULT_LOCK_TIMEOUT`].
+pub fn lock_timeout() -> Duration {
+    std::env::var("RUSK_LOCK_TIMEOUT")
+        .ok()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|s| s.is_finite() && *s >= 0.0)
+        .map(Duration::from_secs_f64)
+        .unwrap_or(DEFAULT_LOCK_TIMEOUT)
+}
+
+/// Holder recorded in the lock file, if it exists and is readable.
+pub fn read_holder(lock_path: &Path) -> Option<LockHolder> {
+    let data = fs::read_to_string(lock_path).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+/// Whether the lock file was left behind by a process that no longer runs.
+fn is_stale(holder: Option<&LockHolder>) -> bool {
+    holder.is_some_and(|h| process_is_running(h.pid) == Some(false))
+}
+
+/// Whether `pid` is a running process; `None` when this platform cannot tell.
+#[cfg(target_os = "linux")]
+fn process_is_running(pid: u32) -> Option<bool> {
+    Some(Path::new("/proc").join(pid.to_string()).exists())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_is_running(_pid: u32) -> Option<bool> {
+    None
+}
+
+impl DbLock {
+    /// Takes the lock for `db_path`, waiting up to `timeout` for another process to release it.
+    pub fn acquire(db_path: &Path, timeout: Duration) -> Result<Self> {
+        let path = lock_path(db_path);
+        let start = Instant::now();
+        let mut announced = false;
+        loop {
+            if let Some(lock) = Self::try_acquire(db_path)? {
+                return Ok(lock);
+            }
+            let holder = read_holder(&path);
+            if start.elapsed() >= timeout {
+                let who = holder
+                    .map(|h| format!("held by {}", h.describe()))
+                    .unwrap_or_else(|| "held by another process".to_string());
+                anyhow::bail!(
+                    "The database is locked: {who}.\n\
+                     Gave up after {:.0}s (set RUSK_LOCK_TIMEOUT to wait longer). If that process \
+                     is no longer running, delete '{}'.",
+                    timeout.as_secs_f64(),
+                    path.display()
+                );
+            }
+            if !announced {
+                let who = holder
+                    .map(|h| h.describe())
+                    .unwrap_or_else(|| "another process".to_string());
+                eprintln!(
+                    "{}",
+                    format!("Waiting for the database lock held by {who}...").yellow()
+                );
+                announced = true;
+            }
+            std::thread::sleep(POLL_INTERVAL.min(timeout.saturating_sub(start.elapsed())));
+        }
+    }
+
+    /// Takes the lock for `db_path` if it is free (or stale); `None` when another process holds
+    /// it.
+    pub fn try_acquire(db_path: &Path) -> Result<Option<Self>> {
+        let path = lock_path(db_path);
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent).context("Failed to create the database directory")?;
+        }
+        if let Some(lock) = Self::create(&path)? {
+            return Ok(Some(lock));
+        }
+        if !is_stale(read_holder(&path).as_ref()) {
+            return Ok(None);
+        }
+        // The holder exited without cleaning up; take the lock over.
+        Self::take_over(&path)
+    }
+
+    /// Takes over a stale lock file by rewriting it in place. Other waiters that found it stale
+    /// too either fail to get the OS lock or, once they have it, find a live holder. `None` when
+    /// another process got there first.
+    fn take_over(path: &Path) -> Result<Option<Self>> {
+        let mut file = match OpenOptions::new().read(true).write(true).open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to open the lock file '{}'", path.display()));
+            }
+        };
+        if file.try_lock().is_err() {
+            return Ok(None);
+        }
+        let mut data = String::new();
+        file.read_to_string(&mut data)
+            .context("Failed to read the lock file")?;
+        if !is_stale(serde_json::from_str(&data).ok().as_ref()) {
+            return Ok(None);
+        }
+        let holder = LockHolder::current();
+        let data = serde_json::to_string(&holder)?;
+        file.set_len(0)
+            .and_then(|()| file.rewind())
+            .and_then(|()| file.write_all(data.as_bytes()))
+            .context("Failed to write the lock file")?;
+        // The file we rewrote may have been released (removed) after we opened it; then it is
+        // no longer the lock.
+        if read_holder(path).as_ref() != Some(&holder) {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            path: path.to_path_buf(),
+            _file: file,
+        }))
+    }
+
+    /// One exclusive-create attempt; `None` when the lock file already exists.
+    fn create(path: &Path) -> Result<Option<Self>> {
+        match OpenOptions::new().write(true).create_new(true).open(path) {
+            Ok(mut file) => {
+                // Held until release, like the OS lock on a taken-over file.
+                let _ = file.try_lock();
+                let holder = serde_json::to_string(&LockHolder::current())?;
+                file.write_all(holder.as_bytes())
+                    .context("Failed to write the lock file")?;
+                Ok(Some(Self {
+                    path: path.to_path_buf(),
+                    _file: file,
+                }))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(None),
+            Err(e) => Err(e)
+                .with_context(|| format!("Failed to create the lock file '{}'", path.display())),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for DbLock {
+    fn drop(&mut self) {
+        // Only remove the file if it is still ours (it may have been taken over as stale).
+        if read_holder(&self.path).is_none_or(|h| h.pid == std::process::id()) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
@@ -2,11 +2,15 @@ use anyhow::{Context, Result};
 use clap::{CommandFactory, Parser};
 use colored::*;
 use rusk::{
-    BareEditDateFlag, TaskManager,
-    args::{Cli, Command},
-    cli::HandlerCLI,
+    BareEditDateFlag, DbSelection, NewTaskOptions, Priority, Recurrence, RecurrenceMode,
+    SubtaskPolicy, TaskId, TaskManager,
+    args::{Cli, Command, ConfigAction},
+    cli::{HandlerCLI, ListOptions, WaitingFilter},
+    config,
     error::AppError,
-    is_cli_date_help_value, parse_edit_args, parse_flexible_ids,
+    find_out_of_range_id, is_cli_date_help_value, is_uuid_prefix_token,
+    lock::{DbLock, lock_timeout},
+    parse_cli_date_for_edit, parse_edit_args, parse_flexible_ids, parse_tag_filter,
     parser::date::is_cli_date_clear_value,
     strip_edit_date_flag, windows_console,
 };
@@ -25,10 +29,11 @@ fn print_subcommand_help(name: &str) -> anyhow::Result<()> {
 fn args_have_date_then_help(args: &[String]) -> bool {
     let mut i = 0usize;
     while i < args.len() {
-        if args[i] == "-d" || args[i] == "--date" {
-            if i + 1 < args.len() && is_cli_date_help_value(&args[i + 1]) {
-                return true;
-            }
+        if (args[i] == "-d" || args[i] == "--date")
+            && i + 1 < args.len()
+            && is_cli_date_help_value(&args[i + 1])
+        {
+            return true;
         }
         i += 1;
     }
@@ -40,6 +45,41 @@ fn eprint_cli_error(msg: impl std::fmt::Display) {
     eprintln!("\n{}\n", msg);
 }
 
+/// Fails when an ID argument is numeric but does not fit in a [`TaskId`].
+fn check_id_range(args: &[String]) -> Result<()> {
+    if let Some(token) = find_out_of_range_id(args) {
+        anyhow::bail!(
+            "Task ID '{token}' is out of range (maximum is {})",
+            TaskId::MAX
+        );
+    }
+    Ok(())
+}
+
+/// Resolves one task reference (short ID or UUID prefix) given as an option value.
+fn parse_single_id(tm: &TaskManager, arg: &str) -> Result<TaskId> {
+    let resolved = tm.resolve_uuid_prefix_args(&[arg.trim().to_string()])?;
+    check_id_range(&resolved)?;
+    resolved[0]
+        .parse::<TaskId>()
+        .map_err(|_| anyhow::anyhow!("Invalid task ID '{arg}'"))
+}
+
+/// Resolves a comma-separated list of task references given as an option value.
+fn parse_id_list(tm: &TaskManager, arg: &str) -> Result<Vec<TaskId>> {
+    let resolved = tm.resolve_uuid_prefix_args(&[format!("{},", arg.trim())])?;
+    check_id_range(&resolved)?;
+    Ok(parse_flexible_ids(&resolved))
+}
+
+fn recurrence_mode(from_completion: bool) -> RecurrenceMode {
+    if from_completion {
+        RecurrenceMode::FromCompletion
+    } else {
+        RecurrenceMode::FromDue
+    }
+}
+
 fn main() {
     match run() {
         Ok(()) => {}
@@ -64,6 +104,11 @@ fn run() -> Result<()> {
     }
 
     let cli = Cli::parse();
+    TaskManager::select_db(DbSelection {
+        db: cli.db.clone(),
+        list: cli.list.clone(),
+    })?;
+    config::apply_color();
 
     #[cfg(feature = "completions")]
     if let Some(Command::Completions { action }) = &cli.command {
@@ -85,6 +130,7 @@ fn run() -> Result<()> {
         Some(Command::Add {
             text,
             date: Some(d),
+            ..
         }) if text.is_empty() && is_cli_date_clear_value(d) => {
             eprint_cli_error(
                 "Error: `-d _` cannot be used when adding a task with no text: there is no date to clear. \
@@ -111,53 +157,176 @@ fn run() -> Result<()> {
                 std::process::exit(1);
             }
         }
+        Some(Command::Init { git }) => {
+            HandlerCLI::handle_init(*git)?;
+            return Ok(());
+        }
+        Some(Command::MergeDriver { base, ours, theirs }) => {
+            if !HandlerCLI::handle_merge_driver(base, ours, theirs)? {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(Command::Config { action }) => {
+            match action {
+                ConfigAction::List => HandlerCLI::handle_config_list()?,
+                ConfigAction::Get { key } => HandlerCLI::handle_config_get(key)?,
+                ConfigAction::Set { key, value, global } => {
+                    HandlerCLI::handle_config_set(key, value, *global)?
+                }
+            }
+            return Ok(());
+        }
+        Some(Command::Lists) => {
+            HandlerCLI::handle_lists(&TaskManager::locate_db_path()?)?;
+            return Ok(());
+        }
+        Some(Command::Doctor { fix }) => {
+            // The doctor reads the file itself: the database may not load as a whole.
+            let db_path = TaskManager::locate_db_path()?;
+            let healthy = {
+                let _lock = if *fix {
+                    Some(DbLock::acquire(&db_path, lock_timeout())?)
+                } else {
+                    None
+                };
+                HandlerCLI::handle_doctor(&db_path, *fix)?
+            };
+            if !healthy {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(Command::Restore { n, list, from }) => {
+            // Restore must not load the (possibly corrupted) database first.
+            if *list {
+                HandlerCLI::handle_restore_list(&TaskManager::locate_db_path()?)?;
+            } else {
+                let mut restore_tm = TaskManager::new_for_restore()?;
+                HandlerCLI::handle_restore(&mut restore_tm, *n, from.as_deref())?;
+            }
+            return Ok(());
+        }
         _ => {}
     }
 
-    let mut tm = TaskManager::new()?;
+    // Commands that only read the database do not wait for (or hold) the lock.
+    let read_only = matches!(
+        cli.command,
+        None | Some(Command::List { .. })
+            | Some(Command::Tags)
+            | Some(Command::Log { .. })
+            | Some(Command::Renumber { history: true, .. })
+    );
+    // Old done tasks go to the archive after a command that changes the list, but not right
+    // after an undo or redo, which would archive again the tasks it just brought back.
+    let auto_archive = !read_only
+        && !matches!(
+            cli.command,
+            Some(Command::Undo { .. }) | Some(Command::Redo { .. })
+        );
+    let mut tm = if read_only {
+        TaskManager::new_read_only()?
+    } else {
+        TaskManager::new()?
+    };
 
     match cli.command {
-        Some(Command::Add { text, date }) => {
+        Some(Command::Add {
+            text,
+            date,
+            wait,
+            parent,
+            every,
+            from_completion,
+        }) => {
+            let options = NewTaskOptions {
+                parent: parent.map(|p| parse_single_id(&tm, &p)).transpose()?,
+                recurrence: every
+                    .map(|e| Recurrence::new(&e, recurrence_mode(from_completion)))
+                    .transpose()?,
+                wait: wait
+                    .map(|w| parse_cli_date_for_edit(&w, None))
+                    .transpose()?,
+                ..Default::default()
+            };
             if text.is_empty() {
                 #[cfg(feature = "interactive")]
                 {
                     use std::io::IsTerminal;
                     if !std::io::stdout().is_terminal() {
-                        eprint_cli_error(
-                            "Error: interactive `rusk add` requires a terminal. \
+                        anyhow::bail!(
+                            "interactive `rusk add` requires a terminal. \
                              Pass the task on the command line, e.g. `rusk add buy milk`."
-                                .red(),
                         );
-                        std::process::exit(1);
-                    }
-                    if let Err(e) = HandlerCLI::handle_add_task_interactive(&mut tm, date) {
-                        eprint_cli_error(format!("Error: {e}").red());
-                        std::process::exit(1);
                     }
+                    HandlerCLI::handle_add_task_interactive(&mut tm, date, options)?;
                 }
                 #[cfg(not(feature = "interactive"))]
                 {
-                    if let Err(e) = HandlerCLI::handle_add_task(&mut tm, text, date) {
-                        eprint_cli_error(format!("Error: {e}").red());
-                        std::process::exit(1);
-                    }
+                    HandlerCLI::handle_add_task(&mut tm, text, date, options)?;
                 }
-            } else if let Err(e) = HandlerCLI::handle_add_task(&mut tm, text, date) {
-                eprint_cli_error(format!("Error: {e}").red());
-                std::process::exit(1);
+            } else {
+                HandlerCLI::handle_add_task(&mut tm, text, date, options)?;
             }
         }
-        Some(Command::Del { ids, done }) => {
+        Some(Command::Del {
+            ids,
+            done,
+            cascade,
+            reparent,
+        }) => {
             let filtered_ids: Vec<String> = ids
                 .iter()
                 .filter(|arg| !arg.trim_start().starts_with('-'))
                 .cloned()
                 .collect();
 
+            let filtered_ids = tm.resolve_uuid_prefix_args(&filtered_ids)?;
+            check_id_range(&filtered_ids)?;
             let parsed_ids = parse_flexible_ids(&filtered_ids);
-            HandlerCLI::handle_delete_tasks(&mut tm, parsed_ids, done)?;
+            // IDS is a trailing var-arg, so flags after the IDs land in `ids`.
+            let cascade = cascade || ids.iter().any(|a| a == "--cascade");
+            let reparent = reparent || ids.iter().any(|a| a == "--reparent");
+            if cascade && reparent {
+                anyhow::bail!("--cascade and --reparent cannot be used together");
+            }
+            let policy = if cascade {
+                Some(SubtaskPolicy::Cascade)
+            } else if reparent {
+                Some(SubtaskPolicy::Reparent)
+            } else {
+                None
+            };
+            HandlerCLI::handle_delete_tasks(&mut tm, parsed_ids, done, policy)?;
         }
-        Some(Command::Mark { ids, priority }) => {
+        Some(Command::Archive { ids, done }) => {
+            let filtered_ids: Vec<String> = ids
+                .iter()
+                .filter(|arg| !arg.trim_start().starts_with('-'))
+                .cloned()
+                .collect();
+            let filtered_ids = tm.resolve_uuid_prefix_args(&filtered_ids)?;
+            check_id_range(&filtered_ids)?;
+            HandlerCLI::handle_archive(&mut tm, parse_flexible_ids(&filtered_ids), done)?;
+        }
+        Some(Command::Unarchive { ids }) => {
+            check_id_range(&ids)?;
+            HandlerCLI::handle_unarchive(&mut tm, parse_flexible_ids(&ids))?;
+        }
+        Some(Command::Mark { mut ids, priority }) => {
+            // `-p` takes an optional level, so in `rusk mark -p 3` the ID lands in its value.
+            let priority = match priority {
+                Some(Some(value))
+                    if !value.starts_with(|c: char| c.is_ascii_alphabetic())
+                        || is_uuid_prefix_token(&value) =>
+                {
+                    ids.insert(0, value);
+                    Some(None)
+                }
+                Some(Some(value)) => Some(Some(value.parse::<Priority>()?)),
+                other => other.map(|_| None),
+            };
             let filtered_ids: Vec<String> = ids
                 .iter()
                 .filter(|arg| {
@@ -168,14 +337,14 @@ fn run() -> Result<()> {
                 .collect();
 
             if filtered_ids.is_empty() {
-                eprint_cli_error("Error: No valid task IDs provided".red());
-                std::process::exit(1);
+                anyhow::bail!("No valid task IDs provided");
             }
 
+            let filtered_ids = tm.resolve_uuid_prefix_args(&filtered_ids)?;
+            check_id_range(&filtered_ids)?;
             let parsed_ids = parse_flexible_ids(&filtered_ids);
             if parsed_ids.is_empty() {
-                eprint_cli_error("Error: No valid task IDs provided".red());
-                std::process::exit(1);
+                anyhow::bail!("No valid task IDs provided");
             }
             HandlerCLI::handle_mark_tasks(&mut tm, parsed_ids, priority)?;
         }
@@ -183,21 +352,20 @@ fn run() -> Result<()> {
             let (args, opt_date) = match strip_edit_date_flag(args) {
                 Ok(p) => p,
                 Err(BareEditDateFlag) => {
-                    eprint_cli_error(
-                        "Error: `rusk edit` does not support `-d` / `--date` without a value. \
+                    anyhow::bail!(
+                        "`rusk edit` does not support `-d` / `--date` without a value. \
                          Use `rusk edit <id>` to set the due date on the first line of the task text in the editor, \
                          or pass a date: `rusk edit <id> -d 31-12-2025` or `rusk edit <id> -d 2w` (see `rusk add --help` for syntax)."
-                            .red(),
                     );
-                    std::process::exit(1);
                 }
             };
 
+            let args = tm.resolve_uuid_prefix_args(&args)?;
+            check_id_range(&args)?;
             let (ids, text_option) = parse_edit_args(args);
 
             if ids.is_empty() {
-                eprint_cli_error("Error: No valid task IDs provided".red());
-                std::process::exit(1);
+                anyhow::bail!("No valid task IDs provided");
             }
 
             match (text_option, opt_date) {
@@ -212,10 +380,7 @@ fn run() -> Result<()> {
                     }
                     #[cfg(not(feature = "interactive"))]
                     {
-                        eprint_cli_error(
-                            "Interactive editing requires the 'interactive' feature".red(),
-                        );
-                        std::process::exit(1);
+                        anyhow::bail!("Interactive editing requires the 'interactive' feature");
                     }
                 }
                 (Some(text), None) => {
@@ -226,35 +391,134 @@ fn run() -> Result<()> {
         Some(Command::List {
             for_completion,
             compact,
+            full,
+            uuid,
+            timestamps,
+            tag,
+            hide_blocked,
+            sort,
+            waiting,
+            all,
+            archived,
         }) => {
             if for_completion {
                 HandlerCLI::handle_list_tasks_for_completion(tm.tasks());
             } else {
-                HandlerCLI::handle_list_tasks(tm.tasks(), compact);
+                let mut options = ListOptions {
+                    compact: compact || (!full && config::current().list_compact()),
+                    show_uuid: uuid,
+                    show_timestamps: timestamps,
+                    hide_blocked,
+                    sort,
+                    waiting: if waiting {
+                        WaitingFilter::Only
+                    } else if all {
+                        WaitingFilter::Include
+                    } else {
+                        WaitingFilter::Hide
+                    },
+                    ..Default::default()
+                };
+                for value in &tag {
+                    let (name, negated) = parse_tag_filter(value);
+                    if name.is_empty() {
+                        continue;
+                    }
+                    if negated {
+                        options.exclude_tags.push(name);
+                    } else {
+                        options.tags.push(name);
+                    }
+                }
+                if archived {
+                    HandlerCLI::handle_list_archived(&tm, options)?;
+                } else {
+                    HandlerCLI::handle_list_tasks(tm.tasks(), &options);
+                }
             }
         }
         None => {
-            HandlerCLI::handle_list_tasks(tm.tasks(), false);
-        }
-        Some(Command::Restore) => {
-            let mut restore_tm = match TaskManager::new_for_restore() {
-                Ok(tm) => tm,
-                Err(e) => {
-                    eprint_cli_error(format!("Error: {e}").red());
-                    std::process::exit(1);
-                }
+            let options = ListOptions {
+                compact: config::current().list_compact(),
+                ..Default::default()
             };
-
-            if let Err(e) = HandlerCLI::handle_restore(&mut restore_tm) {
-                eprint_cli_error(format!("Error: {e}").red());
-                std::process::exit(1);
+            HandlerCLI::handle_list_tasks(tm.tasks(), &options);
+        }
+        Some(Command::Tags) => {
+            HandlerCLI::handle_list_tags(&tm);
+        }
+        Some(Command::Recur {
+            id,
+            every,
+            from_completion,
+        }) => {
+            let id = parse_single_id(&tm, &id)?;
+            let rule = if is_cli_date_clear_value(&every) {
+                None
+            } else {
+                Some(Recurrence::new(&every, recurrence_mode(from_completion))?)
+            };
+            HandlerCLI::handle_set_recurrence(&mut tm, id, rule)?;
+        }
+        Some(Command::Wait { id, date }) => {
+            let id = parse_single_id(&tm, &id)?;
+            HandlerCLI::handle_set_wait(&mut tm, id, &date)?;
+        }
+        Some(Command::Block { id, on }) => {
+            let id = parse_single_id(&tm, &id)?;
+            let on = parse_id_list(&tm, &on)?;
+            HandlerCLI::handle_block_task(&mut tm, id, on)?;
+        }
+        Some(Command::Unblock { id, from }) => {
+            let id = parse_single_id(&tm, &id)?;
+            let from = from.map(|f| parse_id_list(&tm, &f)).transpose()?;
+            HandlerCLI::handle_unblock_task(&mut tm, id, from)?;
+        }
+        Some(Command::Undo { n }) => {
+            HandlerCLI::handle_undo(&mut tm, n)?;
+        }
+        Some(Command::Redo { n }) => {
+            HandlerCLI::handle_redo(&mut tm, n)?;
+        }
+        Some(Command::Log { limit }) => {
+            HandlerCLI::handle_log(&tm, limit)?;
+        }
+        Some(Command::Renumber { by, history }) => {
+            if history {
+                HandlerCLI::handle_renumber_history(&tm);
+            } else {
+                HandlerCLI::handle_renumber(&mut tm, by)?;
             }
         }
+        Some(Command::Sync { other }) => {
+            HandlerCLI::handle_sync(&mut tm, &other)?;
+        }
+        Some(Command::Init { .. }) => {
+            unreachable!("init is handled before TaskManager::new()");
+        }
+        Some(Command::MergeDriver { .. }) => {
+            unreachable!("merge-driver is handled before TaskManager::new()");
+        }
+        Some(Command::Config { .. }) => {
+            unreachable!("config is handled before TaskManager::new()");
+        }
+        Some(Command::Lists) => {
+            unreachable!("lists is handled before TaskManager::new()");
+        }
+        Some(Command::Doctor { .. }) => {
+            unreachable!("doctor is handled before TaskManager::new()");
+        }
+        Some(Command::Restore { .. }) => {
+            unreachable!("restore is handled before TaskManager::new()");
+        }
         #[cfg(feature = "completions")]
         Some(Command::Completions { .. }) => {
             unreachable!("completions are handled before TaskManager::new()");
         }
     }
+    if auto_archive {
+        tm.auto_archive()?;
+    }
 
     Ok(())
 }
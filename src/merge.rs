@@ -0,0 +1,539 @@
+//! Three-way merge of task databases, for use as a git merge driver (`rusk merge-driver`).
+//!
+//! Tasks are matched across the three versions by UUID; tasks from databases that predate UUIDs
+//! are matched by text, then by ID. Each field is merged on its own: a field changed on one side
+//! takes that side's value, and only a field changed differently on both sides is a conflict.
+//! Timestamps never conflict (the later one wins) and tags follow the merged text. Tasks added on
+//! both sides keep our IDs; theirs move above the highest ID when they collide. Blocker links
+//! that only form a cycle once both sides are combined are dropped and reported.
+//!
+//! Conflicts are written git-style into the pretty-printed file, around just the conflicting
+//! field (or the whole task when one side deleted what the other changed), so the result can be
+//! resolved in an editor like any other conflict.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::atomic_write;
+use crate::db_format::{self, DbMeta};
+use crate::model::{self, Task, TaskId};
+
+/// Name of the driver in git config and `.gitattributes`.
+pub const GIT_DRIVER_NAME: &str = "rusk";
+
+/// Fields that are resolved by taking the later time instead of conflicting.
+const TIMESTAMP_FIELDS: &[&str] = &["created_at", "updated_at", "completed_at"];
+
+/// Fields that are not merged value by value: identity and derived data.
+const SPECIAL_FIELDS: &[&str] = &["id", "uuid", "tags"];
+
+/// A field both sides changed differently: `(field, ours, theirs)`, `None` where a side has no
+/// such field.
+pub type FieldConflict = (String, Option<Value>, Option<Value>);
+
+/// A change that could not be merged.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConflictKind {
+    /// Both sides changed these fields to different values.
+    Fields(Vec<FieldConflict>),
+    /// We deleted the task, they changed it. The result keeps their version.
+    DeletedByUs,
+    /// They deleted the task, we changed it. The result keeps our version.
+    DeletedByThem,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    /// UUID of the task in the merged list.
+    pub uuid: Uuid,
+    /// ID of the task in the merged list.
+    pub id: TaskId,
+    pub kind: ConflictKind,
+}
+
+/// Result of [`merge`].
+#[derive(Debug, Clone)]
+pub struct Merged {
+    /// Merged tasks; conflicting fields hold our value (theirs if we have none).
+    pub tasks: Vec<Task>,
+    /// Their tasks that got a new ID because ours already used it: `(old, new)`.
+    pub renumbered: Vec<(TaskId, TaskId)>,
+    pub conflicts: Vec<Conflict>,
+    /// Blocker links dropped because the branches together made them a cycle: `(task, blocker)`.
+    pub unblocked: Vec<(TaskId, TaskId)>,
+}
+
+/// One task across the three versions.
+struct Entity<'a> {
+    base: Option<&'a Task>,
+    ours: Option<&'a Task>,
+    theirs: Option<&'a Task>,
+}
+
+/// For each task in `side`, the index of its counterpart in `base`.
+fn match_to_base(base: &[Task], side: &[Task]) -> Vec<Option<usize>> {
+    let mut taken = vec![false; base.len()];
+    let mut matched = vec![None; side.len()];
+    for (i, task) in side.iter().enumerate().filter(|(_, t)| !t.uuid.is_nil()) {
+        if let Some(j) = base.iter().position(|b| b.uuid == task.uuid) {
+            matched[i] = Some(j);
+            taken[j] = true;
+        }
+    }
+    // Tasks written before UUIDs existed: same text first, then same ID.
+    let same_text = |b: &Task, t: &Task| b.text == t.text;
+    let same_id = |b: &Task, t: &Task| b.id == t.id;
+    for same in [&same_text as &dyn Fn(&Task, &Task) -> bool, &same_id] {
+        for (i, task) in side.iter().enumerate() {
+            if matched[i].is_some() || !task.uuid.is_nil() {
+                continue;
+            }
+            if let Some(j) = (0..base.len())
+                .find(|&j| !taken[j] && base[j].uuid.is_nil() && same(&base[j], task))
+            {
+                matched[i] = Some(j);
+                taken[j] = true;
+            }
+        }
+    }
+    matched
+}
+
+fn group<'a>(base: &'a [Task], ours: &'a [Task], theirs: &'a [Task]) -> Vec<Entity<'a>> {
+    let ours_base = match_to_base(base, ours);
+    let theirs_base = match_to_base(base, theirs);
+    let mut entities: Vec<Entity> = ours
+        .iter()
+        .zip(&ours_base)
+        .map(|(task, b)| Entity {
+            base: b.map(|j| &base[j]),
+            ours: Some(task),
+            theirs: None,
+        })
+        .collect();
+    for (task, b) in theirs.iter().zip(&theirs_base) {
+        let existing = match b {
+            Some(j) => entities
+                .iter()
+                .position(|e| e.base.is_some_and(|bt| std::ptr::eq(bt, &base[*j]))),
+            // Added on both sides: the same task if it has the same UUID or the same text.
+            None => entities.iter().position(|e| {
+                e.base.is_none()
+                    && e.theirs.is_none()
+                    && e.ours.is_some_and(|o| {
+                        (!o.uuid.is_nil() && o.uuid == task.uuid) || o.text == task.text
+                    })
+            }),
+        };
+        match existing {
+            Some(k) if entities[k].theirs.is_none() => entities[k].theirs = Some(task),
+            _ => entities.push(Entity {
+                base: b.map(|j| &base[j]),
+                ours: None,
+                theirs: Some(task),
+            }),
+        }
+    }
+    entities
+}
+
+fn to_map(task: &Task) -> Map<String, Value> {
+    match serde_json::to_value(task) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+/// Whether `side` differs from `base` in anything but its ID and derived fields.
+fn changed(base: &Task, side: &Task) -> bool {
+    let (mut b, mut s) = (to_map(base), to_map(side));
+    for field in SPECIAL_FIELDS {
+        b.remove(*field);
+        s.remove(*field);
+    }
+    b != s
+}
+
+fn later(a: &Option<Value>, b: &Option<Value>) -> Option<Value> {
+    let time = |v: &Option<Value>| {
+        v.clone()
+            .and_then(|v| serde_json::from_value::<Option<DateTime<Utc>>>(v).ok())
+            .flatten()
+    };
+    if time(a) >= time(b) {
+        a.clone()
+    } else {
+        b.clone()
+    }
+}
+
+/// Field-by-field merge of `ours` and `theirs` against `base` (empty when both added the task).
+/// Returns the merged task, whether its ID is ours, and the conflicting fields.
+fn merge_fields(
+    base: Option<&Task>,
+    ours: &Task,
+    theirs: &Task,
+) -> Result<(Task, bool, Vec<FieldConflict>)> {
+    let b = base.map(to_map).unwrap_or_default();
+    let (o, t) = (to_map(ours), to_map(theirs));
+    let keys: Vec<&String> = o.keys().chain(t.keys()).chain(b.keys()).collect();
+    let mut merged = Map::new();
+    let mut conflicts = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    for key in keys {
+        if !seen.insert(key.clone()) || SPECIAL_FIELDS.contains(&key.as_str()) {
+            continue;
+        }
+        let (bv, ov, tv) = (
+            b.get(key).cloned(),
+            o.get(key).cloned(),
+            t.get(key).cloned(),
+        );
+        let value = if ov == tv || tv == bv {
+            ov
+        } else if ov == bv {
+            tv
+        } else if TIMESTAMP_FIELDS.contains(&key.as_str()) {
+            later(&ov, &tv)
+        } else {
+            let kept = ov.clone().or_else(|| tv.clone());
+            conflicts.push((key.clone(), ov, tv));
+            kept
+        };
+        if let Some(value) = value {
+            merged.insert(key.clone(), value);
+        }
+    }
+
+    let base_id = base.map(|b| b.id);
+    let ours_id = Some(ours.id) != base_id || Some(theirs.id) == base_id;
+    let id = if ours_id { ours.id } else { theirs.id };
+    let uuid = [ours.uuid, theirs.uuid]
+        .into_iter()
+        .find(|u| !u.is_nil())
+        .or(base.map(|b| b.uuid))
+        .unwrap_or_default();
+    merged.insert("id".to_string(), Value::from(id));
+    merged.insert("uuid".to_string(), serde_json::to_value(uuid)?);
+    let mut task: Task = serde_json::from_value(Value::Object(merged))
+        .context("Merged task has an invalid field")?;
+    let text = std::mem::take(&mut task.text);
+    task.set_text(text);
+    Ok((task, ours_id, conflicts))
+}
+
+/// Merges `ours` and `theirs`, both descended from `base`.
+pub fn merge(base: &[Task], ours: &[Task], theirs: &[Task]) -> Result<Merged> {
+    let mut tasks = Vec::new();
+    let mut keeps_our_id = Vec::new();
+    let mut conflict_kinds = Vec::new();
+    for entity in group(base, ours, theirs) {
+        let (task, from_ours, conflict) = match (entity.base, entity.ours, entity.theirs) {
+            (b, Some(o), Some(t)) => {
+                let (task, ours_id, fields) = merge_fields(b, o, t)?;
+                let conflict = (!fields.is_empty()).then_some(ConflictKind::Fields(fields));
+                (task, ours_id, conflict)
+            }
+            (Some(b), Some(o), None) if changed(b, o) => {
+                (o.clone(), true, Some(ConflictKind::DeletedByThem))
+            }
+            (Some(b), None, Some(t)) if changed(b, t) => {
+                (t.clone(), false, Some(ConflictKind::DeletedByUs))
+            }
+            (None, Some(o), None) => (o.clone(), true, None),
+            (None, None, Some(t)) => (t.clone(), false, None),
+            // Deleted on one side and untouched on the other, or deleted on both.
+            _ => continue,
+        };
+        tasks.push(task);
+        keeps_our_id.push(from_ours);
+        conflict_kinds.push(conflict);
+    }
+
+    // Give tasks without a UUID (old databases) one, so conflicts can be located.
+    for task in tasks.iter_mut().filter(|t| t.uuid.is_nil()) {
+        task.uuid = Uuid::new_v4();
+    }
+
+    let mut used: Vec<TaskId> = tasks
+        .iter()
+        .zip(&keeps_our_id)
+        .filter(|(_, ours)| **ours)
+        .map(|(t, _)| t.id)
+        .collect();
+    let mut next = tasks.iter().map(|t| t.id).max().unwrap_or(0);
+    let mut renumbered = Vec::new();
+    for (task, _) in tasks
+        .iter_mut()
+        .zip(&keeps_our_id)
+        .filter(|(_, ours)| !**ours)
+    {
+        if task.id == 0 || used.contains(&task.id) {
+            next = next
+                .checked_add(1)
+                .with_context(|| format!("Maximum task ID ({}) reached", TaskId::MAX))?;
+            renumbered.push((task.id, next));
+            task.id = next;
+        }
+        used.push(task.id);
+    }
+    let unblocked = model::break_cycles(&mut tasks);
+
+    let conflicts = tasks
+        .iter()
+        .zip(conflict_kinds)
+        .filter_map(|(task, kind)| {
+            kind.map(|kind| Conflict {
+                uuid: task.uuid,
+                id: task.id,
+                kind,
+            })
+        })
+        .collect();
+    Ok(Merged {
+        tasks,
+        renumbered,
+        conflicts,
+        unblocked,
+    })
+}
+
+/// Merges the files git passes as `%O %A %B` and writes the result over `ours` (`%A`). Conflicts
+/// are left in the file as markers.
+pub fn merge_files(base: &Path, ours: &Path, theirs: &Path) -> Result<Merged> {
+    let (base_tasks, _) = db_format::read_file(base)?;
+    let (our_tasks, mut meta) = db_format::read_file(ours)?;
+    let (their_tasks, their_meta) = db_format::read_file(theirs)?;
+    let merged = merge(&base_tasks, &our_tasks, &their_tasks)?;
+    // Keep the tombstones of both branches, for `rusk sync`.
+    for tombstone in their_meta.deleted {
+        if !meta.deleted.iter().any(|d| d.uuid == tombstone.uuid) {
+            meta.deleted.push(tombstone);
+        }
+    }
+    meta.deleted
+        .retain(|d| !merged.tasks.iter().any(|t| t.uuid == d.uuid));
+    let text = render(&merged, &meta)?;
+    atomic_write::write_atomic(ours, text.as_bytes())?;
+    Ok(merged)
+}
+
+/// The merged database as it is written to disk: the normal format, plus conflict markers. Fails
+/// when a marker cannot be placed, rather than leave a conflict unmarked.
+pub fn render(merged: &Merged, meta: &DbMeta) -> Result<String> {
+    let text = db_format::encode(&merged.tasks, meta)?;
+    if merged.conflicts.is_empty() {
+        return Ok(text);
+    }
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+    for conflict in &merged.conflicts {
+        let uuid_line = format!("{TASK_FIELD_INDENT}\"uuid\": \"{}\",", conflict.uuid);
+        let find_task = |lines: &[String]| {
+            let at = lines.iter().position(|l| *l == uuid_line)?;
+            Some(object_bounds(lines, at))
+        };
+        match &conflict.kind {
+            ConflictKind::Fields(fields) => {
+                for (field, ours, theirs) in fields {
+                    let Some((mut from, to)) = find_task(&lines)
+                        .and_then(|(start, end)| field_bounds(&lines, start, end, field))
+                    else {
+                        anyhow::bail!(
+                            "Cannot place the conflict markers for \"{field}\" of task {}",
+                            conflict.id
+                        );
+                    };
+                    let comma = lines[to].ends_with(',');
+                    let mut ours_lines = field_lines(field, ours.as_ref(), comma)?;
+                    let mut theirs_lines = field_lines(field, theirs.as_ref(), comma)?;
+                    // Without the last field, the comma before it has to go too.
+                    if !comma && (ours.is_none() || theirs.is_none()) {
+                        from -= 1;
+                        let before = lines[from].clone();
+                        let trimmed = before.strip_suffix(',').unwrap_or(&before).to_string();
+                        for side in [&mut ours_lines, &mut theirs_lines] {
+                            let prev = if side.is_empty() { &trimmed } else { &before };
+                            side.insert(0, prev.clone());
+                        }
+                    }
+                    lines.splice(from..=to, markers(ours_lines, theirs_lines));
+                }
+            }
+            ConflictKind::DeletedByUs | ConflictKind::DeletedByThem => {
+                let Some((start, end)) = find_task(&lines) else {
+                    anyhow::bail!("Cannot place the conflict markers for task {}", conflict.id);
+                };
+                let (mut from, mut kept, mut gone) = (start, lines[start..=end].to_vec(), vec![]);
+                // Without the last task, the comma after the one before it has to go too.
+                if !lines[end].ends_with(',') && start > 0 && !lines[start - 1].ends_with('[') {
+                    from -= 1;
+                    let before = lines[from].clone();
+                    if before != format!("{TASK_INDENT}}},") {
+                        anyhow::bail!("Cannot place the conflict markers for task {}", conflict.id);
+                    }
+                    gone.push(before.trim_end_matches(',').to_string());
+                    kept.insert(0, before);
+                }
+                let block = if conflict.kind == ConflictKind::DeletedByUs {
+                    markers(gone, kept)
+                } else {
+                    markers(kept, gone)
+                };
+                lines.splice(from..=end, block);
+            }
+        }
+    }
+    let mut out = lines.join("\n");
+    out.push('\n');
+    Ok(out)
+}
+
+const TASK_INDENT: &str = "    ";
+const TASK_FIELD_INDENT: &str = "      ";
+
+/// First and last line of the task object containing line `at`.
+fn object_bounds(lines: &[String], at: usize) -> (usize, usize) {
+    let open = format!("{TASK_INDENT}{{");
+    let start = (0..=at).rev().find(|&i| lines[i] == open).unwrap_or(at);
+    let end = (at..lines.len())
+        .find(|&i| lines[i].starts_with(&format!("{TASK_INDENT}}}")))
+        .unwrap_or(at);
+    (start, end)
+}
+
+/// Lines holding `field` within the task object spanning `start..=end`.
+fn field_bounds(lines: &[String], start: usize, end: usize, field: &str) -> Option<(usize, usize)> {
+    let key = format!("{TASK_FIELD_INDENT}\"{field}\": ");
+    let from = (start..end).find(|&i| lines[i].starts_with(&key))?;
+    let is_next_field = |l: &String| {
+        l.starts_with(&format!("{TASK_FIELD_INDENT}\""))
+            || l.starts_with(&format!("{TASK_INDENT}}}"))
+    };
+    let to = (from + 1..=end)
+        .find(|&i| is_next_field(&lines[i]))
+        .unwrap_or(end + 1)
+        - 1;
+    Some((from, to))
+}
+
+/// `"field": value` as pretty-printed inside a task object; nothing for a missing field.
+fn field_lines(field: &str, value: Option<&Value>, comma: bool) -> Result<Vec<String>> {
+    let Some(value) = value else {
+        return Ok(Vec::new());
+    };
+    let pretty = serde_json::to_string_pretty(value)?;
+    let mut lines: Vec<String> = pretty
+        .lines()
+        .enumerate()
+        .map(|(i, l)| {
+            if i == 0 {
+                format!(
+                    "{TASK_FIELD_INDENT}{}: {l}",
+                    serde_json::to_string(field).unwrap_or_default()
+                )
+            } else {
+                format!("{TASK_FIELD_INDENT}{l}")
+            }
+        })
+        .collect();
+    if comma && let Some(last) = lines.last_mut() {
+        last.push(',');
+    }
+    Ok(lines)
+}
+
+fn markers(ours: Vec<String>, theirs: Vec<String>) -> Vec<String> {
+    let mut block = vec!["<<<<<<< ours".to_string()];
+    block.extend(ours);
+    block.push("=======".to_string());
+    block.extend(theirs);
+    block.push(">>>>>>> theirs".to_string());
+    block
+}
+
+/// Adds `pattern merge=rusk` to `dir/.gitattributes` unless a rusk merge rule is already there.
+/// Returns whether the file changed.
+pub fn add_gitattributes(dir: &Path, pattern: &str) -> Result<bool> {
+    let path = dir.join(".gitattributes");
+    let existing = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read '{}'", path.display())),
+    };
+    let rule = format!("merge={GIT_DRIVER_NAME}");
+    if existing
+        .lines()
+        .any(|l| l.split_whitespace().next() == Some(pattern) && l.contains(&rule))
+    {
+        return Ok(false);
+    }
+    let mut text = existing;
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text.push_str(&format!("{pattern} {rule}\n"));
+    atomic_write::write_atomic(&path, text.as_bytes())?;
+    Ok(true)
+}
+
+/// Local files kept next to each list; committing them would only produce conflicts.
+const DB_GITIGNORE: &str = "\
+# Local state of each list: only the *.json lists and config.toml are shared.
+*.lock
+*.journal
+*.backup-*
+*.draft
+*.quarantine
+*.sync
+.*.tmp
+editor.draft
+";
+
+/// Writes `db_dir/.gitignore` for the per-list side files unless it exists. Returns whether it
+/// was written.
+pub fn add_db_gitignore(db_dir: &Path) -> Result<bool> {
+    let path = db_dir.join(".gitignore");
+    if path.exists() {
+        return Ok(false);
+    }
+    atomic_write::write_atomic(&path, DB_GITIGNORE.as_bytes())?;
+    Ok(true)
+}
+
+/// `git config` settings that define the driver, as `(key, value)`.
+pub fn git_config_entries() -> [(String, String); 2] {
+    [
+        (
+            format!("merge.{GIT_DRIVER_NAME}.name"),
+            "rusk task database merge".to_string(),
+        ),
+        (
+            format!("merge.{GIT_DRIVER_NAME}.driver"),
+            "rusk merge-driver %O %A %B".to_string(),
+        ),
+    ]
+}
+
+/// Defines the driver in the git config of the repository containing `dir`.
+pub fn configure_git(dir: &Path) -> Result<()> {
+    for (key, value) in git_config_entries() {
+        let output = Command::new("git")
+            .current_dir(dir)
+            .args(["config", &key, &value])
+            .output()
+            .context("Failed to run git")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "git config {key} failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+    }
+    Ok(())
+}
@@ -1,12 +1,251 @@
-use chrono::NaiveDate;
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::parser::date::{add_relative_interval, validate_relative_interval};
+use crate::parser::tags::extract_tags;
+
+/// Short numeric task identifier shown in `rusk list` and typed on the command line.
+pub type TaskId = u32;
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// Priority level, ordered from `None` (lowest) to `High`.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+}
+
+impl Priority {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Priority {
+    type Err = anyhow::Error;
+
+    /// Level names or their first letter, case-insensitive (`high`, `H`, `med`, `none`, …).
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "none" | "n" => Ok(Self::None),
+            "low" | "l" => Ok(Self::Low),
+            "medium" | "med" | "m" => Ok(Self::Medium),
+            "high" | "h" => Ok(Self::High),
+            _ => anyhow::bail!(
+                "Invalid priority '{}': use high, medium, low or none",
+                s.trim()
+            ),
+        }
+    }
+}
+
+/// What the next occurrence of a recurring task is counted from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RecurrenceMode {
+    /// From the due date of the completed occurrence (keeps a fixed schedule).
+    #[default]
+    FromDue,
+    /// From the day the occurrence was marked done.
+    FromCompletion,
+}
+
+/// Repeat rule: `every` is a relative offset in the `--date` grammar (e.g. `1w`, `1m`, `2w3d`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    pub every: String,
+    #[serde(default)]
+    pub mode: RecurrenceMode,
+}
+
+impl Recurrence {
+    /// Validates `every` against the relative-date grammar.
+    pub fn new(every: &str, mode: RecurrenceMode) -> anyhow::Result<Self> {
+        validate_relative_interval(every)
+            .map_err(|e| anyhow::anyhow!("Invalid repeat interval '{}': {e}", every.trim()))?;
+        Ok(Self {
+            every: every.trim().to_string(),
+            mode,
+        })
+    }
+
+    /// Due date of the occurrence after one due on `due` and completed on `completed`.
+    /// From-due rules without a due date count from the completion day.
+    pub fn next_due(
+        &self,
+        due: Option<NaiveDate>,
+        completed: NaiveDate,
+    ) -> anyhow::Result<NaiveDate> {
+        let base = match self.mode {
+            RecurrenceMode::FromDue => due.unwrap_or(completed),
+            RecurrenceMode::FromCompletion => completed,
+        };
+        add_relative_interval(base, &self.every)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct Task {
-    pub id: u8,
+    pub id: TaskId,
+    /// Stable identity that survives deletions and ID reuse. Nil in databases written before
+    /// UUIDs existed; [`TaskManager`](crate::TaskManager) backfills it on load.
+    #[serde(default)]
+    pub uuid: Uuid,
     pub text: String,
     pub date: Option<NaiveDate>,
+    /// Time of day the task is due on `date`; `None` means any time that day.
+    #[serde(default)]
+    pub time: Option<NaiveTime>,
+    /// Start date: the default list hides the task until this day (`--wait`).
+    #[serde(default)]
+    pub wait: Option<NaiveDate>,
     pub done: bool,
     #[serde(default)]
-    pub priority: bool,
+    pub priority: Priority,
+    /// When the task was added. `None` for tasks from databases that predate timestamps.
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Last change to text, date, done or priority.
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// When the task was last marked done; cleared when it is marked undone.
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Utc>>,
+    /// `#tag` names found in `text`, lowercased and without `#`. Kept in sync by
+    /// [`set_text`](Task::set_text) and recomputed on load.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// UUID of the parent task when this is a subtask. Referencing the UUID rather than the
+    /// short ID keeps the link intact when IDs are reused.
+    #[serde(default)]
+    pub parent: Option<Uuid>,
+    /// UUIDs of the tasks that must be done before this one can start (`rusk block`).
+    #[serde(default)]
+    pub blocked_by: Vec<Uuid>,
+    /// Repeat rule; marking the task done creates the next occurrence.
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    /// Fields this build does not know (e.g. written by a newer rusk), kept so saving does not
+    /// drop them.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Task {
+    /// Records a modification at the current time.
+    pub fn touch(&mut self) {
+        self.updated_at = Some(Utc::now());
+    }
+
+    /// Replaces the task text and re-extracts its tags. Does not record a modification.
+    pub fn set_text(&mut self, text: String) {
+        self.tags = extract_tags(&text);
+        self.text = text;
+    }
+
+    /// True while any of this task's blockers in `tasks` is still open. Blockers that no longer
+    /// exist do not count.
+    pub fn is_blocked_in(&self, tasks: &[Task]) -> bool {
+        self.blocked_by
+            .iter()
+            .any(|b| tasks.iter().any(|t| t.uuid == *b && !t.done))
+    }
+
+    /// True while the start date is still ahead of `today`.
+    pub fn is_waiting(&self, today: NaiveDate) -> bool {
+        self.wait.is_some_and(|w| w > today)
+    }
+
+    /// Due moment for ordering and overdue checks. Tasks without a time are due at the end of
+    /// their day.
+    pub fn due_datetime(&self) -> Option<NaiveDateTime> {
+        let end_of_day = NaiveTime::from_hms_opt(23, 59, 59).unwrap();
+        self.date
+            .map(|d| d.and_time(self.time.unwrap_or(end_of_day)))
+    }
+
+    /// True for an open task whose due date, or due time today, has passed at `now`.
+    pub fn is_overdue(&self, now: NaiveDateTime) -> bool {
+        !self.done && self.due_datetime().is_some_and(|due| due < now)
+    }
+
+    /// Sets the done flag, keeping `completed_at` in sync, and records the modification.
+    pub fn set_done(&mut self, done: bool) {
+        self.done = done;
+        self.completed_at = if done { Some(Utc::now()) } else { None };
+        self.touch();
+    }
+}
+
+/// Blocker links that close a dependency cycle, as `(task index, blocker UUID)`. Dropping them
+/// leaves every task able to become unblocked; on each cycle the link found last in list order
+/// is the one reported.
+pub fn cycle_links(tasks: &[Task]) -> Vec<(usize, Uuid)> {
+    let index: HashMap<Uuid, usize> = tasks.iter().enumerate().map(|(i, t)| (t.uuid, i)).collect();
+    #[derive(Clone, Copy, PartialEq)]
+    enum Visit {
+        New,
+        OnPath,
+        Done,
+    }
+    let mut visit = vec![Visit::New; tasks.len()];
+    let mut links = Vec::new();
+    for root in 0..tasks.len() {
+        if visit[root] != Visit::New {
+            continue;
+        }
+        visit[root] = Visit::OnPath;
+        // Depth-first, with the next blocker to look at for each task on the path.
+        let mut path = vec![(root, 0)];
+        while let Some((node, next)) = path.last_mut() {
+            let node = *node;
+            let Some(blocker) = tasks[node].blocked_by.get(*next) else {
+                visit[node] = Visit::Done;
+                path.pop();
+                continue;
+            };
+            *next += 1;
+            match index.get(blocker).map(|&b| (b, visit[b])) {
+                Some((b, Visit::New)) => {
+                    visit[b] = Visit::OnPath;
+                    path.push((b, 0));
+                }
+                Some((_, Visit::OnPath)) => links.push((node, *blocker)),
+                _ => {}
+            }
+        }
+    }
+    links
+}
+
+/// Removes the links [`cycle_links`] finds. Returns them as `(task ID, blocker ID)`.
+pub fn break_cycles(tasks: &mut [Task]) -> Vec<(TaskId, TaskId)> {
+    let links = cycle_links(tasks);
+    let mut broken = Vec::new();
+    for (i, blocker) in links {
+        if let Some(b) = tasks.iter().find(|t| t.uuid == blocker) {
+            broken.push((tasks[i].id, b.id));
+        }
+        tasks[i].blocked_by.retain(|b| *b != blocker);
+    }
+    broken
 }
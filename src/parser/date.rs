@@ -1,5 +1,5 @@
 use anyhow::{Context, Result};
-use chrono::{Duration, Local, Months, NaiveDate};
+use chrono::{Duration, Local, Months, NaiveDate, NaiveTime};
 
 pub fn is_cli_date_help_value(s: &str) -> bool {
     matches!(s.trim(), "-h" | "--help")
@@ -31,8 +31,100 @@ pub fn parse_cli_date_for_edit(date_str: &str, task_date: Option<NaiveDate>) ->
     parse_cli_date(trimmed)
 }
 
+/// Time of day in 24-hour `H:MM` or `HH:MM` form.
+pub fn parse_cli_time(s: &str) -> Result<NaiveTime> {
+    let t = s.trim();
+    let parsed = t.split_once(':').and_then(|(h, m)| {
+        let digits = |p: &str, max_len: usize| {
+            !p.is_empty() && p.len() <= max_len && p.bytes().all(|b| b.is_ascii_digit())
+        };
+        if !digits(h, 2) || !digits(m, 2) || m.len() != 2 {
+            return None;
+        }
+        NaiveTime::from_hms_opt(h.parse().ok()?, m.parse().ok()?, 0)
+    });
+    parsed.with_context(|| {
+        format!(
+            "Invalid time '{}': use HH:MM (24-hour), e.g. 9:30 or 18:00",
+            t
+        )
+    })
+}
+
+/// Splits a due value into its date part and optional time: `2d@14:00`, `31-12-2026 18:00`
+/// (a trailing whitespace-separated `HH:MM`), or `@9:30` (empty date part).
+fn split_cli_due(s: &str) -> Result<(&str, Option<NaiveTime>)> {
+    let t = s.trim();
+    if let Some((date, time)) = t.rsplit_once('@') {
+        return Ok((date.trim(), Some(parse_cli_time(time)?)));
+    }
+    if let Some((date, last)) = t.rsplit_once(char::is_whitespace)
+        && let Ok(time) = parse_cli_time(last)
+    {
+        return Ok((date.trim(), Some(time)));
+    }
+    Ok((t, None))
+}
+
+/// Due date with an optional time of day. The date part follows [`parse_cli_date_for_edit`];
+/// a time is attached with `@` (`2d@14:00`) or a space (`31-12-2026 18:00`). A bare `@HH:MM`
+/// keeps `task_date` (today if none). A `+` offset without a time keeps `task_time`; any other
+/// date without a time clears it.
+pub fn parse_cli_due_for_edit(
+    due_str: &str,
+    task_date: Option<NaiveDate>,
+    task_time: Option<NaiveTime>,
+) -> Result<(NaiveDate, Option<NaiveTime>)> {
+    let (date_part, time) = split_cli_due(due_str)?;
+    if date_part.is_empty() {
+        let Some(time) = time else {
+            anyhow::bail!("Date cannot be empty");
+        };
+        let date = task_date.unwrap_or_else(|| Local::now().date_naive());
+        return Ok((date, Some(time)));
+    }
+    let date = parse_cli_date_for_edit(date_part, task_date)?;
+    let time = match time {
+        Some(t) => Some(t),
+        None if date_part.starts_with('+') => task_time,
+        None => None,
+    };
+    Ok((date, time))
+}
+
+/// Due date and optional time at the start of an editor line: one token (`2d`, `2d@14:00`,
+/// `@9:30`) or a date token followed by an `HH:MM` token. Returns the parsed due and the char
+/// length of the matched prefix, or `None` when the line does not start with a due date.
+pub fn parse_leading_due(
+    line: &str,
+    task_date: Option<NaiveDate>,
+    task_time: Option<NaiveTime>,
+) -> Option<(NaiveDate, Option<NaiveTime>, usize)> {
+    let token: String = line.chars().take_while(|c| !c.is_whitespace()).collect();
+    if token.is_empty() {
+        return None;
+    }
+    let token_len = token.chars().count();
+    let (date, time) = parse_cli_due_for_edit(&token, task_date, task_time).ok()?;
+    if !token.contains('@') {
+        let rest: String = line.chars().skip(token_len).collect();
+        let gap = rest.chars().take_while(|c| *c == ' ' || *c == '\t').count();
+        let next: String = rest
+            .chars()
+            .skip(gap)
+            .take_while(|c| !c.is_whitespace())
+            .collect();
+        if gap > 0
+            && let Ok(t) = parse_cli_time(&next)
+        {
+            return Some((date, Some(t), token_len + gap + next.chars().count()));
+        }
+    }
+    Some((date, time, token_len))
+}
+
 /// Syntax check for a `--date` value before bulk edit: `+` offsets are validated per segment,
-/// absolute dates parse the same for any base.
+/// absolute dates parse the same for any base, and an optional time must be a valid `HH:MM`.
 pub fn validate_cli_date_edit_arg(s: &str) -> Result<()> {
     let t = s.trim();
     if t.is_empty() {
@@ -41,15 +133,7 @@ pub fn validate_cli_date_edit_arg(s: &str) -> Result<()> {
     if is_cli_date_clear_value(t) {
         return Ok(());
     }
-    if let Some(rest) = t.strip_prefix('+') {
-        let rest = rest.trim_start();
-        if rest.is_empty() {
-            anyhow::bail!("Date cannot be empty");
-        }
-        parse_cli_date_with_base(rest, Local::now().date_naive())?;
-    } else {
-        parse_cli_date(t)?;
-    }
+    parse_cli_due_for_edit(t, None, None)?;
     Ok(())
 }
 
@@ -136,20 +220,20 @@ with a leading + when editing, count from the task's current due date (today if
 }
 
 pub fn normalize_date_string(date_str: &str) -> String {
-    let mut normalized = date_str.replace('/', "-").replace('.', "-");
+    let mut normalized = date_str.replace(['/', '.'], "-");
 
     let parts: Vec<&str> = normalized.split('-').collect();
-    if parts.len() == 3 {
-        if let Some(year_str) = parts.get(2) {
-            let year_str = year_str.trim();
-            if year_str.len() <= 2 && !year_str.is_empty() {
-                if let Ok(year) = year_str.parse::<u16>() {
-                    if year < 100 {
-                        let full_year = 2000 + year;
-                        normalized = format!("{}-{}-{}", parts[0], parts[1], full_year);
-                    }
-                }
-            }
+    if parts.len() == 3
+        && let Some(year_str) = parts.get(2)
+    {
+        let year_str = year_str.trim();
+        if year_str.len() <= 2
+            && !year_str.is_empty()
+            && let Ok(year) = year_str.parse::<u16>()
+            && year < 100
+        {
+            let full_year = 2000 + year;
+            normalized = format!("{}-{}-{}", parts[0], parts[1], full_year);
         }
     }
 
@@ -244,6 +328,16 @@ fn apply_relative_cli_segments(base: NaiveDate, segments: &[(u32, char)]) -> Res
     Ok(d)
 }
 
+/// Checks that `s` is a relative offset such as `1m` or `2w3d` (no leading `+`, no absolute date).
+pub fn validate_relative_interval(s: &str) -> Result<()> {
+    parse_relative_cli_segments(s.trim()).map(|_| ())
+}
+
+/// Moves `base` forward by a relative offset such as `1m` or `2w3d`.
+pub fn add_relative_interval(base: NaiveDate, interval: &str) -> Result<NaiveDate> {
+    parse_and_apply_relative_cli_date(interval.trim(), base)
+}
+
 fn parse_and_apply_relative_cli_date(trimmed: &str, base: NaiveDate) -> Result<NaiveDate> {
     let segments = parse_relative_cli_segments(trimmed)?;
     apply_relative_cli_segments(base, &segments)
@@ -341,6 +435,65 @@ mod tests {
         assert!(err.to_string().contains("Invalid date"));
     }
 
+    #[test]
+    fn relative_interval_helpers() {
+        use super::{add_relative_interval, validate_relative_interval};
+        assert!(validate_relative_interval("1m").is_ok());
+        assert!(validate_relative_interval("2w3d").is_ok());
+        assert!(validate_relative_interval("31-12-2026").is_err());
+        assert!(validate_relative_interval("+1m").is_err());
+        assert_eq!(
+            add_relative_interval(d(2026, 1, 31), "1m").unwrap(),
+            d(2026, 2, 28)
+        );
+    }
+
+    #[test]
+    fn due_with_time_of_day() {
+        use super::{parse_cli_due_for_edit, parse_leading_due, validate_cli_date_edit_arg};
+        use chrono::NaiveTime;
+        let t = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        let today = Local::now().date_naive();
+
+        assert_eq!(
+            parse_cli_due_for_edit("31-12-2026 18:00", None, None).unwrap(),
+            (d(2026, 12, 31), Some(t(18, 0)))
+        );
+        assert_eq!(
+            parse_cli_due_for_edit("2d@9:30", None, None).unwrap(),
+            (
+                parse_cli_date_with_base("2d", today).unwrap(),
+                Some(t(9, 30))
+            )
+        );
+        assert_eq!(
+            parse_cli_due_for_edit("@14:00", Some(d(2026, 3, 1)), None).unwrap(),
+            (d(2026, 3, 1), Some(t(14, 0)))
+        );
+        // `+` keeps the current time, absolute dates without a time drop it.
+        assert_eq!(
+            parse_cli_due_for_edit("+1d", Some(d(2026, 3, 1)), Some(t(8, 0))).unwrap(),
+            (d(2026, 3, 2), Some(t(8, 0)))
+        );
+        assert_eq!(
+            parse_cli_due_for_edit("1-4-26", Some(d(2026, 3, 1)), Some(t(8, 0))).unwrap(),
+            (d(2026, 4, 1), None)
+        );
+        assert!(parse_cli_due_for_edit("2d@25:00", None, None).is_err());
+        assert!(parse_cli_due_for_edit("2d@9", None, None).is_err());
+        assert!(validate_cli_date_edit_arg("31-12-2026@7:05").is_ok());
+
+        assert_eq!(
+            parse_leading_due("31-12-2026 18:00 Standup prep", None, None),
+            Some((d(2026, 12, 31), Some(t(18, 0)), 16))
+        );
+        assert_eq!(
+            parse_leading_due("31-12-2026 Call 555", None, None),
+            Some((d(2026, 12, 31), None, 10))
+        );
+        assert_eq!(parse_leading_due("Standup 9:30", None, None), None);
+    }
+
     #[test]
     fn optional_empty_returns_none() {
         assert_eq!(parse_cli_date_optional_empty("").unwrap(), None);
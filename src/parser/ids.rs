@@ -1,3 +1,5 @@
+use crate::model::TaskId;
+
 /// `-d` / `--date` on `edit` with no value (a value is required; bare `-d` is not supported).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct BareEditDateFlag;
@@ -34,22 +36,62 @@ pub fn strip_edit_date_flag(
 
 /// Parse comma-separated IDs from a single string segment.
 /// Shared logic used by both `parse_flexible_ids` and `parse_edit_args`.
-fn parse_comma_ids(s: &str) -> Vec<u8> {
+fn parse_comma_ids(s: &str) -> Vec<TaskId> {
     s.split(',')
         .filter_map(|part| {
             let trimmed = part.trim();
             if trimmed.is_empty() {
                 None
             } else {
-                trimmed.parse::<u8>().ok()
+                trimmed.parse::<TaskId>().ok()
             }
         })
         .collect()
 }
 
+/// Minimum length of a UUID prefix typed in place of a short ID.
+pub const MIN_UUID_PREFIX_LEN: usize = 4;
+
+/// True when `s` should be looked up as a task UUID prefix rather than parsed as a short ID:
+/// at least [`MIN_UUID_PREFIX_LEN`] hex digits (hyphens allowed), starting with a hex digit and
+/// containing at least one non-decimal character. All-digit tokens are always short IDs.
+pub fn is_uuid_prefix_token(s: &str) -> bool {
+    let hex_len = s.chars().filter(|c| *c != '-').count();
+    hex_len >= MIN_UUID_PREFIX_LEN
+        && s.starts_with(|c: char| c.is_ascii_hexdigit())
+        && s.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+        && !s.chars().all(|c| c.is_ascii_digit())
+}
+
+/// First ID-looking token in `args` (digits, optionally comma-separated) with a part too large
+/// for [`TaskId`]. Scanning stops at the first token that is not an ID list, so task text after
+/// the IDs (e.g. `rusk edit 1 call 99999999999`) is never reported.
+pub fn find_out_of_range_id(args: &[String]) -> Option<String> {
+    for arg in args {
+        let trimmed = arg.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if !trimmed
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == ',' || c.is_whitespace())
+        {
+            return None;
+        }
+        if let Some(part) = trimmed
+            .split(',')
+            .map(str::trim)
+            .find(|p| !p.is_empty() && p.parse::<TaskId>().is_err())
+        {
+            return Some(part.to_string());
+        }
+    }
+    None
+}
+
 /// Parse ID list for `del` / `mark`: use comma-separated tokens (e.g. `1,2,3`).
-/// If no argument contains a comma, only the first bare `u8` token is kept; extra argv words are ignored.
-pub fn parse_flexible_ids(args: &[String]) -> Vec<u8> {
+/// If no argument contains a comma, only the first bare ID token is kept; extra argv words are ignored.
+pub fn parse_flexible_ids(args: &[String]) -> Vec<TaskId> {
     let mut ids = Vec::new();
 
     if args.is_empty() {
@@ -65,17 +107,18 @@ pub fn parse_flexible_ids(args: &[String]) -> Vec<u8> {
         let trimmed_arg = arg.trim();
         if trimmed_arg.contains(',') || trimmed_arg.starts_with(',') {
             ids.extend(parse_comma_ids(trimmed_arg));
-        } else if !has_comma_args && let Ok(id) = trimmed_arg.parse::<u8>() {
-            if ids.is_empty() {
-                ids.push(id);
-            }
+        } else if !has_comma_args
+            && let Ok(id) = trimmed_arg.parse::<TaskId>()
+            && ids.is_empty()
+        {
+            ids.push(id);
         }
     }
 
     ids
 }
 
-pub type EditArgs = (Vec<u8>, Option<Vec<String>>);
+pub type EditArgs = (Vec<TaskId>, Option<Vec<String>>);
 
 /// Parse edit command arguments to separate IDs and text
 pub fn parse_edit_args(args: Vec<String>) -> EditArgs {
@@ -97,7 +140,7 @@ pub fn parse_edit_args(args: Vec<String>) -> EditArgs {
                 } else {
                     ids.extend(parsed);
                 }
-            } else if let Ok(id) = trimmed_arg.parse::<u8>() {
+            } else if let Ok(id) = trimmed_arg.parse::<TaskId>() {
                 if ids.is_empty() {
                     ids.push(id);
                 } else {
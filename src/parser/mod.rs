@@ -1,10 +1,15 @@
 pub mod date;
 pub mod ids;
+pub mod tags;
 
 pub use date::{
-    is_cli_date_help_value, normalize_date_string, parse_cli_date, parse_cli_date_for_edit,
-    parse_cli_date_optional_empty, parse_cli_date_with_base, validate_cli_date_edit_arg,
+    add_relative_interval, is_cli_date_help_value, normalize_date_string, parse_cli_date,
+    parse_cli_date_for_edit, parse_cli_date_optional_empty, parse_cli_date_with_base,
+    parse_cli_due_for_edit, parse_cli_time, parse_leading_due, validate_cli_date_edit_arg,
+    validate_relative_interval,
 };
 pub use ids::{
-    BareEditDateFlag, EditArgs, parse_edit_args, parse_flexible_ids, strip_edit_date_flag,
+    BareEditDateFlag, EditArgs, find_out_of_range_id, is_uuid_prefix_token, parse_edit_args,
+    parse_flexible_ids, strip_edit_date_flag,
 };
+pub use tags::{extract_tags, parse_tag_filter, tag_char_spans};
@@ -0,0 +1,57 @@
+/// True for characters allowed in a tag name after `#`.
+fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '/')
+}
+
+/// Char ranges (`start..end`, `#` included) of `#tag` tokens in `line`. A tag starts at the
+/// beginning of the line or after whitespace, so `a#b` and URL fragments are not tags.
+pub fn tag_char_spans(line: &str) -> Vec<(usize, usize)> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let at_boundary = i == 0 || chars[i - 1].is_whitespace();
+        if chars[i] == '#' && at_boundary {
+            let mut end = i + 1;
+            while end < chars.len() && is_tag_char(chars[end]) {
+                end += 1;
+            }
+            if end > i + 1 {
+                spans.push((i, end));
+            }
+            i = end.max(i + 1);
+        } else {
+            i += 1;
+        }
+    }
+    spans
+}
+
+/// Lowercased tag names (without `#`) in order of first appearance, duplicates removed.
+pub fn extract_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let chars: Vec<char> = line.chars().collect();
+        for (start, end) in tag_char_spans(line) {
+            let tag: String = chars[start + 1..end]
+                .iter()
+                .collect::<String>()
+                .to_lowercase();
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+    }
+    tags
+}
+
+/// Normalizes a `--tag` value: strips a leading `#` and lowercases. Returns the name and whether
+/// it was negated with a leading `-` or `!` (e.g. `--tag -backend`).
+pub fn parse_tag_filter(s: &str) -> (String, bool) {
+    let t = s.trim();
+    let (rest, negated) = match t.strip_prefix('-').or_else(|| t.strip_prefix('!')) {
+        Some(rest) => (rest, true),
+        None => (t, false),
+    };
+    (rest.trim_start_matches('#').to_lowercase(), negated)
+}
@@ -1,19 +1,198 @@
 use anyhow::{Context, Result};
 use colored::*;
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
-
-use crate::model::Task;
-use crate::parse_cli_date_for_edit;
+use uuid::Uuid;
+
+use crate::archive;
+use crate::atomic_write;
+use crate::backup;
+use crate::config;
+use crate::db_format::{self, DB_FORMAT_VERSION, DbMeta, Renumbering, Tombstone};
+use crate::journal::{self, EntryKind, JournalEntry};
+use crate::lock::{DbLock, lock_timeout};
+use crate::model::{Priority, Recurrence, Task, TaskId};
+use crate::parse_cli_due_for_edit;
 use crate::parser::date::is_cli_date_clear_value;
+use crate::parser::ids::is_uuid_prefix_token;
+use crate::parser::tags::extract_tags;
+
+pub type MarkResult = (Vec<(TaskId, bool)>, Vec<TaskId>);
+
+/// `(archive ID, new ID)` of each task brought back, and the IDs not in the archive.
+pub type UnarchiveResult = (Vec<(TaskId, TaskId)>, Vec<TaskId>);
+
+/// Directory that holds a project's database, found by walking up like git finds `.git`.
+pub const DB_DIR_NAME: &str = ".rusk";
+
+/// Name of the list used without `--list` (stored as `tasks.json`).
+pub const DEFAULT_LIST: &str = "tasks";
+
+/// Database chosen on the command line; see [`TaskManager::select_db`].
+#[derive(Debug, Clone, Default)]
+pub struct DbSelection {
+    /// `--db`: a database file, or a directory holding `tasks.json`.
+    pub db: Option<PathBuf>,
+    /// `--list`: a named list next to the default one.
+    pub list: Option<String>,
+}
+
+static DB_SELECTION: OnceLock<DbSelection> = OnceLock::new();
+
+/// Order of the new IDs for [`TaskManager::renumber`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum RenumberOrder {
+    /// The current list order.
+    #[default]
+    Position,
+    /// Due date, then time of day; undated tasks last.
+    Due,
+    /// Highest priority first, then by due date.
+    Priority,
+}
+
+impl RenumberOrder {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Position => "position",
+            Self::Due => "due",
+            Self::Priority => "priority",
+        }
+    }
+}
+
+/// File name of the list `name` (`work` → `work.json`).
+pub fn list_file_name(name: &str) -> String {
+    format!("{name}.json")
+}
+
+/// List names are file names: letters, digits, `-` and `_`.
+pub fn validate_list_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        anyhow::bail!("Invalid list name '{name}': use letters, digits, '-' and '_'");
+    }
+    Ok(())
+}
+
+/// Names of the lists in a database directory (`*.json` files), the default list first.
+pub fn list_names(dir: &Path) -> Result<Vec<String>> {
+    let mut names: Vec<String> = match fs::read_dir(dir) {
+        Ok(read_dir) => read_dir
+            .flatten()
+            .filter(|e| e.path().is_file())
+            .filter_map(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                name.strip_suffix(".json")
+                    .filter(|n| validate_list_name(n).is_ok())
+                    .map(str::to_string)
+            })
+            .collect(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read '{}'", dir.display()));
+        }
+    };
+    names.sort_by(|a, b| (a != DEFAULT_LIST).cmp(&(b != DEFAULT_LIST)).then(a.cmp(b)));
+    Ok(names)
+}
 
-pub type MarkResult = (Vec<(u8, bool)>, Vec<u8>);
+/// A `--db`/`RUSK_DB` value: a directory (existing, or written with a trailing `/`) means
+/// `tasks.json` inside it.
+pub fn db_file_at(path: &Path) -> PathBuf {
+    if path.is_dir() || path.to_string_lossy().ends_with('/') {
+        path.join(list_file_name(DEFAULT_LIST))
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// The nearest `.rusk` directory in `start` or one of its parents. The walk ends at the
+/// filesystem root, or after checking `stop_at` (the home directory) when it passes it, so a
+/// list in one project never leaks into a sibling through a shared ancestor higher up.
+pub fn find_db_dir(start: &Path, stop_at: Option<&Path>) -> Option<PathBuf> {
+    for dir in start.ancestors() {
+        let candidate = dir.join(DB_DIR_NAME);
+        if candidate.is_dir() {
+            return Some(candidate);
+        }
+        if stop_at == Some(dir) {
+            break;
+        }
+    }
+    None
+}
+
+/// Extra fields for a new task beyond its text and due date.
+#[derive(Debug, Clone, Default)]
+pub struct NewTaskOptions {
+    /// Short ID of the parent task (`rusk add --parent`).
+    pub parent: Option<TaskId>,
+    /// Repeat rule (`rusk add --every`).
+    pub recurrence: Option<Recurrence>,
+    /// Time of day on the due date (`--date 2d@14:00`). Ignored without a due date.
+    pub time: Option<chrono::NaiveTime>,
+    /// Start date before which the task stays hidden (`rusk add --wait`).
+    pub wait: Option<chrono::NaiveDate>,
+}
+
+/// What happens to the subtasks of a deleted task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubtaskPolicy {
+    /// Delete the whole subtree.
+    Cascade,
+    /// Move the subtasks up to the deleted task's own parent (top level if it had none).
+    Reparent,
+}
+
+/// A task edited in a session that kept the database unlocked (the interactive editor), as
+/// `(before, after)`.
+pub type TaskEdit = (Task, Task);
+
+/// An edit that could not be merged because another process changed or deleted the same task.
+#[derive(Debug, Clone)]
+pub struct EditConflict {
+    /// The task as this session edited it (not saved).
+    pub mine: Task,
+    /// The task as it is now on disk (kept); `None` when it was deleted.
+    pub theirs: Option<Task>,
+}
 
 /// Manages task operations and persistence
 pub struct TaskManager {
     pub tasks: Vec<Task>,
     pub db_path: PathBuf,
+    /// Envelope fields of the loaded file; [`save`](Self::save) adds tombstones for removed
+    /// tasks.
+    meta: RefCell<DbMeta>,
+    /// Held from load until the manager is dropped, so the read-modify-write cycle of one
+    /// process cannot interleave with another's.
+    lock: Option<DbLock>,
+    /// Hash of the file as last loaded or saved (`None` when it did not exist), to notice
+    /// writes by other processes while the lock was released.
+    disk_hash: Cell<Option<u64>>,
+    /// Tasks as last loaded or saved; [`save`](Self::save) journals the difference.
+    snapshot: RefCell<Vec<Task>>,
+}
+
+fn content_hash(data: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn join_ids(ids: &[TaskId]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 /// Byte offset (0-based) of the first character of each 1-based line in `s`.
@@ -63,7 +242,7 @@ fn error_byte_in_file(data: &str, line: usize, column: usize) -> Option<usize> {
 }
 
 fn json_error_line_context(data: &str, e: &serde_json::Error) -> Option<String> {
-    let n = e.line() as usize;
+    let n = e.line();
     if n == 0 {
         return None;
     }
@@ -122,7 +301,7 @@ impl Drop for DbReporter {
 }
 
 impl TaskManager {
-    fn is_test_mode() -> bool {
+    pub(crate) fn is_test_mode() -> bool {
         let env_check = std::env::var("RUST_TEST_THREADS").is_ok()
             || std::env::var("CARGO_TEST").is_ok()
             || std::env::var("__CARGO_TEST_CHANNEL").is_ok();
@@ -155,48 +334,94 @@ impl TaskManager {
         let next_week = today + chrono::Duration::days(7);
         let last_week = today - chrono::Duration::days(7);
 
-        vec![
-            Task { id: 1, text: "Simple task without date".to_string(), date: None, done: false, priority: false },
-            Task { id: 2, text: "Completed task without date".to_string(), date: None, done: true, priority: false },
-            Task { id: 3, text: "Overdue task from last week".to_string(), date: Some(last_week), done: false, priority: true },
-            Task { id: 4, text: "Completed overdue task".to_string(), date: Some(yesterday), done: true, priority: false },
-            Task { id: 5, text: "Task due today".to_string(), date: Some(today), done: false, priority: true },
-            Task { id: 6, text: "Completed task due today".to_string(), date: Some(today), done: true, priority: false },
-            Task { id: 7, text: "Task due tomorrow".to_string(), date: Some(tomorrow), done: false, priority: false },
-            Task { id: 8, text: "Completed future task".to_string(), date: Some(next_week), done: true, priority: false },
-            Task { id: 9, text: "Short".to_string(), date: None, done: false, priority: false },
-            Task { id: 10, text: "This is a very long task description that contains multiple words and demonstrates how the system handles longer text content".to_string(), date: Some(tomorrow), done: false, priority: false },
-            Task { id: 11, text: "Task with special chars: @#$%^&*()".to_string(), date: None, done: false, priority: false },
-            Task { id: 12, text: "Complete task 42 and review items 1-10".to_string(), date: Some(next_week), done: false, priority: false },
-            Task { id: 13, text: "Buy groceries: milk, bread, eggs, and cheese".to_string(), date: Some(tomorrow), done: false, priority: false },
-            Task { id: 14, text: "Long-term project milestone".to_string(), date: Some(today + chrono::Duration::days(30)), done: false, priority: false },
-        ]
+        let mut tasks = vec![
+            Task { id: 1, text: "Simple task without date".to_string(), date: None, done: false, ..Default::default() },
+            Task { id: 2, text: "Completed task without date".to_string(), date: None, done: true, ..Default::default() },
+            Task { id: 3, text: "Overdue task from last week #work".to_string(), date: Some(last_week), done: false, priority: Priority::High, ..Default::default() },
+            Task { id: 4, text: "Completed overdue task".to_string(), date: Some(yesterday), done: true, ..Default::default() },
+            Task { id: 5, text: "Task due today".to_string(), date: Some(today), done: false, priority: Priority::Medium, ..Default::default() },
+            Task { id: 6, text: "Completed task due today".to_string(), date: Some(today), done: true, ..Default::default() },
+            Task { id: 7, text: "Task due tomorrow".to_string(), date: Some(tomorrow), done: false, priority: Priority::Low, ..Default::default() },
+            Task { id: 8, text: "Completed future task".to_string(), date: Some(next_week), done: true, ..Default::default() },
+            Task { id: 9, text: "Short".to_string(), date: None, done: false, ..Default::default() },
+            Task { id: 10, text: "This is a very long task description that contains multiple words and demonstrates how the system handles longer text content".to_string(), date: Some(tomorrow), done: false, ..Default::default() },
+            Task { id: 11, text: "Task with special chars: @#$%^&*()".to_string(), date: None, done: false, ..Default::default() },
+            Task { id: 12, text: "Complete task 42 and review items 1-10".to_string(), date: Some(next_week), done: false, ..Default::default() },
+            Task { id: 13, text: "Buy groceries: milk, bread, eggs, and cheese #home".to_string(), date: Some(tomorrow), done: false, ..Default::default() },
+            Task { id: 14, text: "Long-term project milestone".to_string(), date: Some(today + chrono::Duration::days(30)), done: false, ..Default::default() },
+        ];
+        for task in &mut tasks {
+            task.tags = extract_tags(&task.text);
+        }
+        Self::backfill_uuids(&mut tasks);
+        tasks
     }
 
+    /// Opens the database for modification, holding its lock (waiting up to
+    /// [`lock_timeout`](crate::lock::lock_timeout) for another rusk process) until dropped.
     pub fn new() -> Result<Self> {
-        let db_path = Self::resolve_db_path();
-        let mut tasks = Self::load_tasks_from_path(&db_path)?;
+        let db_path = Self::locate_db_path()?;
+        let lock = DbLock::acquire(&db_path, lock_timeout())?;
+        Self::open(db_path, Some(lock))
+    }
+
+    /// Opens the database for commands that only read it, without waiting for the lock. Legacy
+    /// fix-ups (UUID backfill) are persisted only when the lock happens to be free.
+    pub fn new_read_only() -> Result<Self> {
+        Self::open(Self::locate_db_path()?, None)
+    }
+
+    fn open(db_path: PathBuf, lock: Option<DbLock>) -> Result<Self> {
+        let (mut tasks, meta, backfilled) = Self::load_tasks_with_backfill(&db_path)?;
         Self::maybe_log_db_path(&db_path);
 
-        if cfg!(debug_assertions) && !Self::is_test_mode() && tasks.is_empty() {
+        let needs_save = if backfilled {
+            // Persist the new UUIDs right away so they stay stable across runs.
+            !meta.is_newer_than_supported()
+        } else if cfg!(debug_assertions)
+            && !Self::is_test_mode()
+            && Self::selected_db().is_none()
+            && tasks.is_empty()
+        {
             tasks = Self::create_sample_tasks();
-            let tm = Self {
-                tasks,
-                db_path: db_path.clone(),
-            };
-            tm.save()?;
-            return Ok(tm);
-        }
+            true
+        } else {
+            false
+        };
 
-        Ok(Self { tasks, db_path })
+        let disk_hash = Cell::new(Self::file_hash(&db_path)?);
+        // Backfilled UUIDs and sample tasks are not operations worth undoing.
+        let snapshot = RefCell::new(tasks.clone());
+        let mut tm = Self {
+            tasks,
+            db_path,
+            meta: RefCell::new(meta),
+            lock,
+            disk_hash,
+            snapshot,
+        };
+        if needs_save {
+            if tm.lock.is_none() {
+                tm.lock = DbLock::try_acquire(&tm.db_path)?;
+            }
+            if tm.lock.is_some() {
+                tm.save()?;
+            }
+        }
+        Ok(tm)
     }
 
     pub fn new_for_restore() -> Result<Self> {
-        let db_path = Self::resolve_db_path();
+        let db_path = Self::locate_db_path()?;
+        let lock = DbLock::acquire(&db_path, lock_timeout())?;
         Self::maybe_log_db_path(&db_path);
         Ok(Self {
             tasks: Vec::new(),
             db_path,
+            meta: RefCell::default(),
+            lock: Some(lock),
+            disk_hash: Cell::new(None),
+            snapshot: RefCell::default(),
         })
     }
 
@@ -209,6 +434,10 @@ impl TaskManager {
         Ok(Self {
             tasks: Vec::new(),
             db_path,
+            meta: RefCell::default(),
+            lock: None,
+            disk_hash: Cell::new(None),
+            snapshot: RefCell::default(),
         })
     }
 
@@ -216,7 +445,163 @@ impl TaskManager {
         Self {
             tasks: Vec::new(),
             db_path: path,
+            meta: RefCell::default(),
+            lock: None,
+            disk_hash: Cell::new(None),
+            snapshot: RefCell::default(),
+        }
+    }
+
+    /// Loads the database at `path`, keeping its envelope fields for the next save.
+    pub fn new_with_path(path: PathBuf) -> Result<Self> {
+        let (tasks, meta, _) = Self::load_tasks_with_backfill(&path)?;
+        let disk_hash = Cell::new(Self::file_hash(&path)?);
+        let snapshot = RefCell::new(tasks.clone());
+        Ok(Self {
+            tasks,
+            db_path: path,
+            meta: RefCell::new(meta),
+            lock: None,
+            disk_hash,
+            snapshot,
+        })
+    }
+
+    /// Releases the database lock, e.g. while an editor session waits for the user. Call
+    /// [`reacquire_lock`](Self::reacquire_lock) before saving, and expect other processes to have
+    /// written in the meantime.
+    pub fn release_lock(&mut self) {
+        self.lock = None;
+    }
+
+    /// Takes the lock again after [`release_lock`](Self::release_lock), waiting like
+    /// [`new`](Self::new).
+    pub fn reacquire_lock(&mut self) -> Result<()> {
+        if self.lock.is_none() {
+            self.lock = Some(DbLock::acquire(&self.db_path, lock_timeout())?);
+        }
+        Ok(())
+    }
+
+    fn file_hash(path: &PathBuf) -> Result<Option<u64>> {
+        match fs::read(path) {
+            Ok(data) => Ok(Some(content_hash(&data))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).context("Failed to read the database file"),
+        }
+    }
+
+    /// True when the file is no longer what this manager last loaded or saved.
+    pub fn changed_on_disk(&self) -> Result<bool> {
+        Ok(Self::file_hash(&self.db_path)? != self.disk_hash.get())
+    }
+
+    /// Replaces the in-memory tasks with the file's current contents, dropping unsaved changes.
+    pub fn reload(&mut self) -> Result<()> {
+        let (tasks, meta, _) = Self::load_tasks_with_backfill(&self.db_path)?;
+        self.snapshot.replace(tasks.clone());
+        self.tasks = tasks;
+        self.meta.replace(meta);
+        self.disk_hash.set(Self::file_hash(&self.db_path)?);
+        Ok(())
+    }
+
+    /// Reverts the last `n` journaled operations, newest first, and returns them. Nothing is
+    /// changed when a task they touched was modified outside the journal since.
+    pub fn undo(&mut self, n: usize) -> Result<Vec<JournalEntry>> {
+        let entries = journal::read_journal(&self.db_path)?;
+        let (done, _) = journal::undo_redo_stacks(&entries);
+        if done.is_empty() {
+            anyhow::bail!("Nothing to undo");
+        }
+        let steps: Vec<JournalEntry> = done
+            .iter()
+            .rev()
+            .take(n)
+            .filter_map(|seq| entries.iter().find(|e| e.seq == *seq).cloned())
+            .collect();
+        self.replay(&steps, true)?;
+        Ok(steps)
+    }
+
+    /// Re-applies the last `n` undone operations, most recently undone first.
+    pub fn redo(&mut self, n: usize) -> Result<Vec<JournalEntry>> {
+        let entries = journal::read_journal(&self.db_path)?;
+        let (_, undone) = journal::undo_redo_stacks(&entries);
+        if undone.is_empty() {
+            anyhow::bail!("Nothing to redo");
+        }
+        let steps: Vec<JournalEntry> = undone
+            .iter()
+            .rev()
+            .take(n)
+            .filter_map(|seq| entries.iter().find(|e| e.seq == *seq).cloned())
+            .collect();
+        self.replay(&steps, false)?;
+        Ok(steps)
+    }
+
+    /// Applies `steps` in order (reversed for undo), checking all of them on a copy first so a
+    /// diverged task leaves the database untouched. Each step is saved and journaled on its own.
+    fn replay(&mut self, steps: &[JournalEntry], undo: bool) -> Result<()> {
+        let mut trial = self.tasks.clone();
+        for step in steps {
+            let diverged = journal::diverged(&trial, &step.changes, undo);
+            if !diverged.is_empty() {
+                anyhow::bail!(
+                    "Cannot {} `{}`: task(s) {} changed since in a way the journal does not \
+                     record. Use `rusk restore` to go back to a backup instead",
+                    if undo { "undo" } else { "redo" },
+                    step.command,
+                    join_ids(&diverged.iter().map(|t| t.id).collect::<Vec<_>>())
+                );
+            }
+            journal::apply(&mut trial, &step.changes, undo);
+        }
+        for step in steps {
+            journal::apply(&mut self.tasks, &step.changes, undo);
+            self.save_as(if undo {
+                EntryKind::Undo(step.seq)
+            } else {
+                EntryKind::Redo(step.seq)
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Saves the result of an unlocked edit session. When another process wrote the file in the
+    /// meantime, its version is re-read and only `edits` are applied on top of it. An edit whose
+    /// task was changed (text or due) or deleted there is not applied and comes back as a
+    /// conflict; changes to other fields, such as marking it done, merge cleanly.
+    pub fn save_edits(&mut self, edits: &[TaskEdit]) -> Result<Vec<EditConflict>> {
+        if !self.changed_on_disk()? {
+            self.save()?;
+            return Ok(Vec::new());
+        }
+        self.reload()?;
+        let mut conflicts = Vec::new();
+        for (before, after) in edits {
+            match self.tasks.iter_mut().find(|t| t.uuid == before.uuid) {
+                Some(theirs)
+                    if theirs.text == before.text
+                        && theirs.date == before.date
+                        && theirs.time == before.time =>
+                {
+                    theirs.set_text(after.text.clone());
+                    theirs.date = after.date;
+                    theirs.time = after.time;
+                    theirs.touch();
+                }
+                theirs => conflicts.push(EditConflict {
+                    mine: after.clone(),
+                    theirs: theirs.cloned(),
+                }),
+            }
         }
+        if conflicts.len() < edits.len() {
+            self.save()?;
+        }
+        Ok(conflicts)
     }
 
     pub fn tasks(&self) -> &[Task] {
@@ -233,11 +618,18 @@ impl TaskManager {
 
     pub fn add_task(&mut self, text: Vec<String>, date: Option<String>) -> Result<()> {
         let text = text.join(" ");
-        let date = match date {
-            None => None,
-            Some(d) => Some(parse_cli_date_for_edit(&d, None)?),
+        let (date, time) = match date {
+            None => (None, None),
+            Some(d) => {
+                let (date, time) = parse_cli_due_for_edit(&d, None, None)?;
+                (Some(date), time)
+            }
+        };
+        let options = NewTaskOptions {
+            time,
+            ..Default::default()
         };
-        self.add_task_with_parsed_date(text, date)
+        self.add_task_with_options(text, date, &options)
     }
 
     /// Like [`add_task`](Self::add_task) but with an already-parsed due date (avoids re-parsing after the editor).
@@ -245,58 +637,342 @@ impl TaskManager {
         &mut self,
         text: String,
         date: Option<chrono::NaiveDate>,
+    ) -> Result<()> {
+        self.add_task_with_options(text, date, &NewTaskOptions::default())
+    }
+
+    /// Adds a task with a parsed due date and the extra fields in `options`.
+    pub fn add_task_with_options(
+        &mut self,
+        text: String,
+        date: Option<chrono::NaiveDate>,
+        options: &NewTaskOptions,
     ) -> Result<()> {
         if text.trim().is_empty() {
             anyhow::bail!("Task text cannot be empty");
         }
+        let parent = match options.parent {
+            Some(pid) => {
+                let idx = self
+                    .find_task_by_id(pid)
+                    .with_context(|| format!("Parent task {pid} not found"))?;
+                Some(self.tasks[idx].uuid)
+            }
+            None => None,
+        };
         let id = self.generate_next_id()?;
-        let task = Task {
+        let now = chrono::Utc::now();
+        let mut task = Task {
             id,
-            text: text.clone(),
+            uuid: Uuid::new_v4(),
             date,
-            done: false,
-            priority: false,
+            time: date.and(options.time),
+            wait: options.wait,
+            created_at: Some(now),
+            updated_at: Some(now),
+            parent,
+            recurrence: options.recurrence.clone(),
+            ..Default::default()
         };
+        task.set_text(text);
         self.tasks.push(task);
         self.save()?;
         Ok(())
     }
 
-    pub fn delete_tasks(&mut self, ids: Vec<u8>) -> Result<Vec<u8>> {
-        let mut deleted_count = 0;
-        let mut not_found = Vec::new();
+    /// Deletes tasks by ID. Fails without deleting anything when one of them has subtasks that
+    /// are not deleted as well; use [`delete_tasks_with`](Self::delete_tasks_with) to choose.
+    pub fn delete_tasks(&mut self, ids: Vec<TaskId>) -> Result<Vec<TaskId>> {
+        self.delete_tasks_with(ids, None)
+    }
 
+    /// Deletes tasks by ID, handling their subtasks according to `policy`. With `None`, a task
+    /// whose subtasks are not all being deleted too is an error. Returns the IDs not found.
+    pub fn delete_tasks_with(
+        &mut self,
+        ids: Vec<TaskId>,
+        policy: Option<SubtaskPolicy>,
+    ) -> Result<Vec<TaskId>> {
         let mut sorted_ids = ids;
         sorted_ids.sort_by(|a, b| b.cmp(a));
 
+        // A repeated ID counts as not found the second time, as if deleted one by one.
+        let mut indices = Vec::new();
+        let mut not_found = Vec::new();
         for id in sorted_ids {
-            if let Some(idx) = self.find_task_by_id(id) {
-                self.tasks.remove(idx);
-                deleted_count += 1;
-            } else {
-                not_found.push(id);
+            match self.find_task_by_id(id) {
+                Some(idx) if !indices.contains(&idx) => indices.push(idx),
+                _ => not_found.push(id),
             }
         }
+        let mut doomed = indices.clone();
+
+        match policy {
+            None => {
+                for &idx in &indices {
+                    let orphaned: Vec<TaskId> = self
+                        .subtask_indices(idx)
+                        .into_iter()
+                        .filter(|c| !doomed.contains(c))
+                        .map(|c| self.tasks[c].id)
+                        .collect();
+                    if !orphaned.is_empty() {
+                        anyhow::bail!(
+                            "Task {} has subtasks ({}); pass --cascade to delete them too or --reparent to move them up a level",
+                            self.tasks[idx].id,
+                            join_ids(&orphaned)
+                        );
+                    }
+                }
+            }
+            Some(SubtaskPolicy::Cascade) => {
+                for &idx in &indices {
+                    for d in self.descendant_indices(idx) {
+                        if !doomed.contains(&d) {
+                            doomed.push(d);
+                        }
+                    }
+                }
+            }
+            Some(SubtaskPolicy::Reparent) => {}
+        }
 
-        if deleted_count > 0 {
+        if !doomed.is_empty() {
+            self.remove_task_indices(&doomed);
             self.save()?;
         }
 
         Ok(not_found)
     }
 
+    /// Deletes every done task. Open subtasks of a deleted task move up to its nearest
+    /// remaining ancestor.
     pub fn delete_all_done(&mut self) -> Result<usize> {
-        let done_count = self.tasks.iter().filter(|t| t.done).count();
-        if done_count == 0 {
+        let doomed: Vec<usize> = (0..self.tasks.len())
+            .filter(|&i| self.tasks[i].done)
+            .collect();
+        if doomed.is_empty() {
             Ok(0)
         } else {
-            self.tasks.retain(|t| !t.done);
+            self.remove_task_indices(&doomed);
+            self.save()?;
+            Ok(doomed.len())
+        }
+    }
+
+    /// Moves the tasks with these IDs, with all their subtasks, to the archive (see
+    /// [`archive`]). Returns the IDs moved, subtasks included, and the IDs not found.
+    pub fn archive_tasks(&mut self, ids: Vec<TaskId>) -> Result<(Vec<TaskId>, Vec<TaskId>)> {
+        let mut indices = Vec::new();
+        let mut not_found = Vec::new();
+        for id in ids {
+            let Some(idx) = self.find_task_by_id(id) else {
+                not_found.push(id);
+                continue;
+            };
+            for i in std::iter::once(idx).chain(self.descendant_indices(idx)) {
+                if !indices.contains(&i) {
+                    indices.push(i);
+                }
+            }
+        }
+        let moved = self.move_to_archive(&indices)?;
+        if !moved.is_empty() {
+            self.save()?;
+        }
+        Ok((moved, not_found))
+    }
+
+    /// Moves every done task whose subtasks are all done as well to the archive, together with
+    /// those subtasks. Returns the number of tasks moved.
+    pub fn archive_done(&mut self) -> Result<usize> {
+        let indices = self.archivable_done(|_| true);
+        let moved = self.move_to_archive(&indices)?;
+        if !moved.is_empty() {
+            self.save()?;
+        }
+        Ok(moved.len())
+    }
+
+    /// Indices of the done tasks accepted by `filter` whose subtasks are all done, followed by
+    /// those subtasks.
+    fn archivable_done(&self, filter: impl Fn(&Task) -> bool) -> Vec<usize> {
+        let mut indices = Vec::new();
+        for (idx, task) in self.tasks.iter().enumerate() {
+            if !task.done || !filter(task) || indices.contains(&idx) {
+                continue;
+            }
+            let descendants = self.descendant_indices(idx);
+            if descendants.iter().all(|&d| self.tasks[d].done) {
+                for i in std::iter::once(idx).chain(descendants) {
+                    if !indices.contains(&i) {
+                        indices.push(i);
+                    }
+                }
+            }
+        }
+        indices
+    }
+
+    /// Appends the tasks at `indices` to the archive and removes them here, without saving.
+    /// Returns their IDs. Their tombstones are marked as archived, so `rusk sync` archives the
+    /// other database's copies instead of deleting them.
+    fn move_to_archive(&mut self, indices: &[usize]) -> Result<Vec<TaskId>> {
+        if indices.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut sorted = indices.to_vec();
+        sorted.sort_unstable();
+        let tasks: Vec<Task> = sorted.iter().map(|&i| self.tasks[i].clone()).collect();
+        let ids = tasks.iter().map(|t| t.id).collect();
+        archive::add(&archive::archive_path(&self.db_path), tasks.clone())?;
+        let now = chrono::Utc::now();
+        let meta = self.meta.get_mut();
+        for task in tasks.iter().filter(|t| !t.uuid.is_nil()) {
+            meta.deleted.retain(|d| d.uuid != task.uuid);
+            meta.deleted.push(Tombstone {
+                uuid: task.uuid,
+                deleted_at: now,
+                archived: true,
+            });
+        }
+        self.remove_task_indices(&sorted);
+        Ok(ids)
+    }
+
+    /// With `archive.after_days` set, archives the done tasks completed and last changed more
+    /// than that many days ago, and saves. Returns the number of tasks moved.
+    pub fn auto_archive(&mut self) -> Result<usize> {
+        let Some(days) = config::current().archive_after_days() else {
+            return Ok(0);
+        };
+        if self.meta.borrow().is_newer_than_supported() {
+            return Ok(0);
+        }
+        // A cutoff before the earliest representable time archives nothing.
+        let Some(cutoff) = chrono::TimeDelta::try_days(days)
+            .and_then(|age| chrono::Utc::now().checked_sub_signed(age))
+        else {
+            return Ok(0);
+        };
+        let indices = self.archivable_done(|t| {
+            t.completed_at.is_some_and(|at| at < cutoff)
+                && t.updated_at.is_none_or(|at| at < cutoff)
+        });
+        let moved = self.move_to_archive(&indices)?;
+        if !moved.is_empty() {
             self.save()?;
-            Ok(done_count)
+        }
+        Ok(moved.len())
+    }
+
+    /// Tasks in the archive of this database, except copies of tasks that are here (put back
+    /// by `rusk undo`, say).
+    pub fn archived_tasks(&self) -> Result<Vec<Task>> {
+        let (mut archived, _) = archive::read(&archive::archive_path(&self.db_path))?;
+        archived.retain(|a| !self.tasks.iter().any(|t| t.uuid == a.uuid));
+        Ok(archived)
+    }
+
+    /// Brings the archived tasks with these archive IDs back, with their archived subtasks.
+    /// They keep their ID unless it is taken.
+    pub fn unarchive(&mut self, ids: Vec<TaskId>) -> Result<UnarchiveResult> {
+        let archived = self.archived_tasks()?;
+        let mut chosen: Vec<usize> = Vec::new();
+        let mut not_found = Vec::new();
+        for id in ids {
+            match archived.iter().position(|t| t.id == id) {
+                Some(i) if !chosen.contains(&i) => chosen.push(i),
+                Some(_) => {}
+                None => not_found.push(id),
+            }
+        }
+        // Subtasks archived along with their parent come back with it.
+        let mut i = 0;
+        while i < chosen.len() {
+            let uuid = archived[chosen[i]].uuid;
+            for (j, task) in archived.iter().enumerate() {
+                if task.parent == Some(uuid) && !chosen.contains(&j) {
+                    chosen.push(j);
+                }
+            }
+            i += 1;
+        }
+        chosen.sort_unstable();
+
+        let mut restored = Vec::new();
+        let mut uuids = Vec::new();
+        for &i in &chosen {
+            let mut task = archived[i].clone();
+            if task.id == 0 || self.find_task_by_id(task.id).is_some() {
+                task.id = self.generate_next_id()?;
+            }
+            // Moving it back counts as a change, so auto-archiving leaves it alone for a while.
+            task.touch();
+            restored.push((archived[i].id, task.id));
+            uuids.push(task.uuid);
+            self.tasks.push(task);
+        }
+        // Drop links to tasks that are not in this database.
+        let present: HashSet<Uuid> = self.tasks.iter().map(|t| t.uuid).collect();
+        for task in self.tasks.iter_mut().filter(|t| uuids.contains(&t.uuid)) {
+            task.parent = task.parent.filter(|p| present.contains(p));
+            task.blocked_by.retain(|b| present.contains(b));
+        }
+
+        if !restored.is_empty() {
+            self.save()?;
+            archive::remove(&archive::archive_path(&self.db_path), &uuids)?;
+        }
+        Ok((restored, not_found))
+    }
+
+    /// Removes the tasks at `doomed` and re-links surviving subtasks to their nearest surviving
+    /// ancestor.
+    fn remove_task_indices(&mut self, doomed: &[usize]) {
+        let removed: Vec<(Uuid, Option<Uuid>)> = doomed
+            .iter()
+            .map(|&i| (self.tasks[i].uuid, self.tasks[i].parent))
+            .collect();
+        let mut idx = 0;
+        self.tasks.retain(|_| {
+            let keep = !doomed.contains(&idx);
+            idx += 1;
+            keep
+        });
+        for task in &mut self.tasks {
+            let mut parent = task.parent;
+            let mut hops = 0;
+            while let Some(p) = parent
+                && let Some((_, up)) = removed.iter().find(|(u, _)| *u == p)
+                && hops <= removed.len()
+            {
+                parent = *up;
+                hops += 1;
+            }
+            let before = task.blocked_by.len();
+            task.blocked_by
+                .retain(|b| !removed.iter().any(|(u, _)| u == b));
+            if parent != task.parent || task.blocked_by.len() != before {
+                task.parent = parent;
+                task.touch();
+            }
         }
     }
 
-    pub fn mark_tasks(&mut self, ids: Vec<u8>) -> Result<MarkResult> {
+    /// Toggles the done flag of the given tasks.
+    pub fn mark_tasks(&mut self, ids: Vec<TaskId>) -> Result<MarkResult> {
+        self.mark_tasks_with_subtasks(ids, &[])
+    }
+
+    /// Like [`mark_tasks`](Self::mark_tasks), and additionally marks done every open subtask
+    /// (at any depth) of the tasks in `finish_subtasks_of` that end up done. Those subtasks are
+    /// appended to the returned list.
+    pub fn mark_tasks_with_subtasks(
+        &mut self,
+        ids: Vec<TaskId>,
+        finish_subtasks_of: &[TaskId],
+    ) -> Result<MarkResult> {
         let mut not_found = Vec::new();
         let mut marked = Vec::new();
         let ids_len = ids.len();
@@ -304,13 +980,39 @@ impl TaskManager {
         for id in ids {
             if let Some(idx) = self.find_task_by_id(id) {
                 let task = &mut self.tasks[idx];
-                task.done = !task.done;
+                task.set_done(!task.done);
                 marked.push((id, task.done));
             } else {
                 not_found.push(id);
             }
         }
 
+        for &pid in finish_subtasks_of {
+            let Some(idx) = self.find_task_by_id(pid) else {
+                continue;
+            };
+            if !self.tasks[idx].done {
+                continue;
+            }
+            for d in self.descendant_indices(idx) {
+                let task = &mut self.tasks[d];
+                if !task.done {
+                    task.set_done(true);
+                    marked.push((task.id, true));
+                }
+            }
+        }
+
+        let mut completed: Vec<TaskId> = marked
+            .iter()
+            .filter(|(_, done)| *done)
+            .map(|(id, _)| *id)
+            .collect();
+        completed.dedup();
+        for id in completed {
+            self.spawn_next_occurrence(id)?;
+        }
+
         if not_found.len() < ids_len {
             self.save()?;
         }
@@ -318,9 +1020,258 @@ impl TaskManager {
         Ok((marked, not_found))
     }
 
-    /// Toggles the `priority` flag for the given task ids. Returns `(Vec<(id, new_priority)>, not_found)`.
+    /// When the done task `id` repeats, appends its next occurrence (same text, priority and
+    /// parent, due date moved by the rule) and moves the rule onto it, so re-marking the old
+    /// task cannot spawn a duplicate. Returns the new task's ID.
+    fn spawn_next_occurrence(&mut self, id: TaskId) -> Result<Option<TaskId>> {
+        let Some(idx) = self.find_task_by_id(id) else {
+            return Ok(None);
+        };
+        let task = &self.tasks[idx];
+        let Some(rule) = task.recurrence.clone().filter(|_| task.done) else {
+            return Ok(None);
+        };
+        let completed = task
+            .completed_at
+            .map(|t| t.with_timezone(&chrono::Local).date_naive())
+            .unwrap_or_else(|| chrono::Local::now().date_naive());
+        let next_date = rule.next_due(task.date, completed)?;
+        // Keep the same lead time between start and due date on the next occurrence.
+        let next_wait = match (task.wait, task.date) {
+            (Some(wait), Some(due)) => next_date.checked_sub_signed(due - wait),
+            _ => None,
+        };
+        let new_id = self.generate_next_id()?;
+        let now = chrono::Utc::now();
+        let next = Task {
+            id: new_id,
+            uuid: Uuid::new_v4(),
+            text: task.text.clone(),
+            tags: task.tags.clone(),
+            date: Some(next_date),
+            time: task.time,
+            wait: next_wait,
+            priority: task.priority,
+            parent: task.parent,
+            recurrence: Some(rule),
+            created_at: Some(now),
+            updated_at: Some(now),
+            ..Default::default()
+        };
+        self.tasks[idx].recurrence = None;
+        self.tasks.push(next);
+        Ok(Some(new_id))
+    }
+
+    /// Sets or clears (`None`) the repeat rule of task `id`. Returns whether it changed.
+    pub fn set_recurrence(&mut self, id: TaskId, recurrence: Option<Recurrence>) -> Result<bool> {
+        let idx = self
+            .find_task_by_id(id)
+            .with_context(|| format!("Task {id} not found"))?;
+        let task = &mut self.tasks[idx];
+        if task.recurrence == recurrence {
+            return Ok(false);
+        }
+        task.recurrence = recurrence;
+        task.touch();
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Sets or clears (`None`) the start date of task `id`. Returns whether it changed.
+    pub fn set_wait(&mut self, id: TaskId, wait: Option<chrono::NaiveDate>) -> Result<bool> {
+        let idx = self
+            .find_task_by_id(id)
+            .with_context(|| format!("Task {id} not found"))?;
+        let task = &mut self.tasks[idx];
+        if task.wait == wait {
+            return Ok(false);
+        }
+        task.wait = wait;
+        task.touch();
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Makes task `id` wait for `blockers`. Fails without changing anything when a blocker is
+    /// missing, is the task itself, or would close a dependency cycle. Returns whether anything
+    /// was added.
+    pub fn block_task(&mut self, id: TaskId, blockers: &[TaskId]) -> Result<bool> {
+        let idx = self
+            .find_task_by_id(id)
+            .with_context(|| format!("Task {id} not found"))?;
+        let mut new_blockers: Vec<Uuid> = Vec::new();
+        for &bid in blockers {
+            let bidx = self
+                .find_task_by_id(bid)
+                .with_context(|| format!("Task {bid} not found"))?;
+            if bidx == idx {
+                anyhow::bail!("Task {id} cannot block itself");
+            }
+            if self.depends_on(bidx, idx) {
+                anyhow::bail!(
+                    "Blocking task {id} on {bid} would create a cycle: {bid} already waits for {id}"
+                );
+            }
+            let uuid = self.tasks[bidx].uuid;
+            if !self.tasks[idx].blocked_by.contains(&uuid) && !new_blockers.contains(&uuid) {
+                new_blockers.push(uuid);
+            }
+        }
+        if new_blockers.is_empty() {
+            return Ok(false);
+        }
+        let task = &mut self.tasks[idx];
+        task.blocked_by.extend(new_blockers);
+        task.touch();
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Removes `blockers` from task `id`, or every blocker when `blockers` is `None`. Returns
+    /// whether anything was removed.
+    pub fn unblock_task(&mut self, id: TaskId, blockers: Option<&[TaskId]>) -> Result<bool> {
+        let idx = self
+            .find_task_by_id(id)
+            .with_context(|| format!("Task {id} not found"))?;
+        let remove: Option<Vec<Uuid>> = blockers.map(|ids| {
+            ids.iter()
+                .filter_map(|&b| self.find_task_by_id(b).map(|i| self.tasks[i].uuid))
+                .collect()
+        });
+        let task = &mut self.tasks[idx];
+        let before = task.blocked_by.len();
+        match remove {
+            Some(uuids) => task.blocked_by.retain(|b| !uuids.contains(b)),
+            None => task.blocked_by.clear(),
+        }
+        if task.blocked_by.len() == before {
+            return Ok(false);
+        }
+        task.touch();
+        self.save()?;
+        Ok(true)
+    }
+
+    /// True when the task at `from` waits, directly or transitively, on the task at `target`.
+    fn depends_on(&self, from: usize, target: usize) -> bool {
+        let target_uuid = self.tasks[target].uuid;
+        let mut stack = vec![from];
+        let mut seen: Vec<usize> = Vec::new();
+        while let Some(current) = stack.pop() {
+            if seen.contains(&current) {
+                continue;
+            }
+            seen.push(current);
+            for b in &self.tasks[current].blocked_by {
+                if *b == target_uuid {
+                    return true;
+                }
+                if let Some(i) = self.tasks.iter().position(|t| t.uuid == *b) {
+                    stack.push(i);
+                }
+            }
+        }
+        false
+    }
+
+    /// IDs of the tasks currently waiting on an open blocker.
+    pub fn blocked_task_ids(&self) -> Vec<TaskId> {
+        self.tasks
+            .iter()
+            .filter(|t| t.is_blocked_in(&self.tasks))
+            .map(|t| t.id)
+            .collect()
+    }
+
+    /// IDs of the open blockers of task `id`.
+    pub fn open_blocker_ids(&self, id: TaskId) -> Vec<TaskId> {
+        let Some(idx) = self.find_task_by_id(id) else {
+            return Vec::new();
+        };
+        self.tasks[idx]
+            .blocked_by
+            .iter()
+            .filter_map(|b| self.tasks.iter().find(|t| t.uuid == *b && !t.done))
+            .map(|t| t.id)
+            .collect()
+    }
+
+    /// Indices of the direct subtasks of the task at `idx`, in storage order.
+    pub fn subtask_indices(&self, idx: usize) -> Vec<usize> {
+        let uuid = self.tasks[idx].uuid;
+        self.tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.parent == Some(uuid))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Indices of all subtasks below the task at `idx`. Safe against parent cycles in
+    /// hand-edited databases.
+    pub fn descendant_indices(&self, idx: usize) -> Vec<usize> {
+        let mut out: Vec<usize> = Vec::new();
+        let mut stack = vec![idx];
+        while let Some(current) = stack.pop() {
+            for child in self.subtask_indices(current).into_iter().rev() {
+                if child != idx && !out.contains(&child) {
+                    out.push(child);
+                    stack.push(child);
+                }
+            }
+        }
+        out
+    }
+
+    /// IDs of the open subtasks (at any depth) of task `id`.
+    pub fn open_subtask_ids(&self, id: TaskId) -> Vec<TaskId> {
+        match self.find_task_by_id(id) {
+            Some(idx) => self
+                .descendant_indices(idx)
+                .into_iter()
+                .filter(|&i| !self.tasks[i].done)
+                .map(|i| self.tasks[i].id)
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Toggles the given task ids between no priority and [`Priority::High`] (`mark -p` without a
+    /// level). Returns `(Vec<(id, has_priority)>, not_found)`.
     /// Does not touch `done`: the priority is preserved across later done toggles.
-    pub fn mark_priority_tasks(&mut self, ids: Vec<u8>) -> Result<MarkResult> {
+    pub fn mark_priority_tasks(&mut self, ids: Vec<TaskId>) -> Result<MarkResult> {
+        let (marked, not_found) = self.update_priority(ids, |p| match p {
+            Priority::None => Priority::High,
+            _ => Priority::None,
+        })?;
+        let marked = marked
+            .into_iter()
+            .filter_map(|id| self.find_task_by_id(id))
+            .map(|idx| {
+                (
+                    self.tasks[idx].id,
+                    self.tasks[idx].priority != Priority::None,
+                )
+            })
+            .collect();
+        Ok((marked, not_found))
+    }
+
+    /// Sets the priority of the given task ids to `level`. Returns `(ids, not_found)`.
+    pub fn set_priority_tasks(
+        &mut self,
+        ids: Vec<TaskId>,
+        level: Priority,
+    ) -> Result<(Vec<TaskId>, Vec<TaskId>)> {
+        self.update_priority(ids, |_| level)
+    }
+
+    fn update_priority(
+        &mut self,
+        ids: Vec<TaskId>,
+        next: impl Fn(Priority) -> Priority,
+    ) -> Result<(Vec<TaskId>, Vec<TaskId>)> {
         let mut not_found = Vec::new();
         let mut marked = Vec::new();
         let ids_len = ids.len();
@@ -328,8 +1279,9 @@ impl TaskManager {
         for id in ids {
             if let Some(idx) = self.find_task_by_id(id) {
                 let task = &mut self.tasks[idx];
-                task.priority = !task.priority;
-                marked.push((id, task.priority));
+                task.priority = next(task.priority);
+                task.touch();
+                marked.push(id);
             } else {
                 not_found.push(id);
             }
@@ -344,10 +1296,10 @@ impl TaskManager {
 
     pub fn edit_tasks(
         &mut self,
-        ids: Vec<u8>,
+        ids: Vec<TaskId>,
         text: Option<Vec<String>>,
         date: Option<String>,
-    ) -> Result<(Vec<u8>, Vec<u8>, Vec<u8>)> {
+    ) -> Result<(Vec<TaskId>, Vec<TaskId>, Vec<TaskId>)> {
         let mut not_found = Vec::new();
         let mut edited = Vec::new();
         let mut unchanged = Vec::new();
@@ -360,7 +1312,7 @@ impl TaskManager {
                 if let Some(words) = &text {
                     let joined = words.join(" ");
                     if task.text != joined {
-                        task.text = joined;
+                        task.set_text(joined);
                         was_changed = true;
                     }
                 }
@@ -369,18 +1321,22 @@ impl TaskManager {
                     if is_cli_date_clear_value(new_date) {
                         if task.date.is_some() {
                             task.date = None;
+                            task.time = None;
                             was_changed = true;
                         }
                     } else {
-                        let parsed_date = parse_cli_date_for_edit(new_date, task.date)?;
-                        if task.date != Some(parsed_date) {
+                        let (parsed_date, parsed_time) =
+                            parse_cli_due_for_edit(new_date, task.date, task.time)?;
+                        if task.date != Some(parsed_date) || task.time != parsed_time {
                             task.date = Some(parsed_date);
+                            task.time = parsed_time;
                             was_changed = true;
                         }
                     }
                 }
 
                 if was_changed {
+                    task.touch();
                     edited.push(id);
                 } else {
                     unchanged.push(id);
@@ -397,11 +1353,101 @@ impl TaskManager {
         Ok((edited, unchanged, not_found))
     }
 
-    pub fn find_task_by_id(&self, id: u8) -> Option<usize> {
+    /// Every tag in use with the number of tasks carrying it, sorted by count (descending)
+    /// then name.
+    pub fn tag_counts(&self) -> Vec<(String, usize)> {
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        for tag in self.tasks.iter().flat_map(|t| &t.tags) {
+            match counts.iter_mut().find(|(t, _)| t == tag) {
+                Some((_, n)) => *n += 1,
+                None => counts.push((tag.clone(), 1)),
+            }
+        }
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    }
+
+    pub fn find_task_by_id(&self, id: TaskId) -> Option<usize> {
         self.tasks.iter().position(|t| t.id == id)
     }
 
-    pub fn find_tasks_by_ids(&self, ids: &[u8]) -> (Vec<usize>, Vec<u8>) {
+    /// Index of the task whose UUID starts with `prefix` (case-insensitive; hyphens are ignored).
+    /// Errors when the prefix matches more than one task.
+    pub fn find_task_by_uuid_prefix(&self, prefix: &str) -> Result<Option<usize>> {
+        let needle: String = prefix
+            .chars()
+            .filter(|c| *c != '-')
+            .collect::<String>()
+            .to_ascii_lowercase();
+        if needle.is_empty() {
+            return Ok(None);
+        }
+        let matches: Vec<usize> = self
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| !t.uuid.is_nil() && t.uuid.simple().to_string().starts_with(&needle))
+            .map(|(i, _)| i)
+            .collect();
+        match matches.as_slice() {
+            [] => Ok(None),
+            [idx] => Ok(Some(*idx)),
+            _ => {
+                let ids = matches
+                    .iter()
+                    .map(|&i| self.tasks[i].id.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                anyhow::bail!("UUID prefix '{prefix}' is ambiguous (matches tasks {ids})")
+            }
+        }
+    }
+
+    /// Replaces UUID-prefix tokens in the ID position of `del` / `mark` / `edit` argv with the
+    /// matching short IDs, so the regular ID parsers can handle them. A leading run of comma lists
+    /// is resolved part by part; a bare token is only resolved when it comes first. Scanning stops
+    /// at the first bare token after that (task text for `edit`, ignored extra words for `del` /
+    /// `mark`).
+    pub fn resolve_uuid_prefix_args(&self, args: &[String]) -> Result<Vec<String>> {
+        let mut out = Vec::with_capacity(args.len());
+        let mut in_ids = true;
+        let mut first = true;
+        for arg in args {
+            let trimmed = arg.trim();
+            if !in_ids || trimmed.is_empty() {
+                out.push(arg.clone());
+                continue;
+            }
+            if trimmed.contains(',') {
+                let parts = trimmed
+                    .split(',')
+                    .map(|part| self.resolve_uuid_prefix_token(part))
+                    .collect::<Result<Vec<_>>>()?;
+                out.push(parts.join(","));
+            } else if first {
+                in_ids = false;
+                out.push(self.resolve_uuid_prefix_token(trimmed)?);
+            } else {
+                in_ids = false;
+                out.push(arg.clone());
+            }
+            first = false;
+        }
+        Ok(out)
+    }
+
+    fn resolve_uuid_prefix_token(&self, token: &str) -> Result<String> {
+        let trimmed = token.trim();
+        if !is_uuid_prefix_token(trimmed) {
+            return Ok(token.to_string());
+        }
+        match self.find_task_by_uuid_prefix(trimmed)? {
+            Some(idx) => Ok(self.tasks[idx].id.to_string()),
+            None => anyhow::bail!("No task with UUID prefix '{trimmed}'"),
+        }
+    }
+
+    pub fn find_tasks_by_ids(&self, ids: &[TaskId]) -> (Vec<usize>, Vec<TaskId>) {
         let mut found_indices = Vec::new();
         let mut not_found = Vec::new();
 
@@ -416,118 +1462,281 @@ impl TaskManager {
         (found_indices, not_found)
     }
 
-    pub fn generate_next_id(&self) -> Result<u8> {
-        let mut used: Vec<u8> = self.tasks.iter().map(|t| t.id).collect();
+    /// ID for a new task: the lowest free one, or with `ids.reuse` off one above every ID
+    /// handed out so far.
+    pub fn generate_next_id(&self) -> Result<TaskId> {
+        if !config::current().reuse_ids() {
+            let highest = self.tasks.iter().map(|t| t.id).max().unwrap_or(0);
+            return highest
+                .max(self.meta.borrow().last_id)
+                .checked_add(1)
+                .with_context(|| format!("Maximum task ID ({}) reached", TaskId::MAX));
+        }
+        let mut used: Vec<TaskId> = self.tasks.iter().map(|t| t.id).collect();
         used.sort_unstable();
 
-        let mut id = 1u8;
+        let mut id: TaskId = 1;
         for &used_id in &used {
             if id == used_id {
-                id += 1;
-            } else {
+                id = id
+                    .checked_add(1)
+                    .with_context(|| format!("Maximum task ID ({}) reached", TaskId::MAX))?;
+            } else if used_id > id {
                 break;
             }
         }
 
-        if id == 0 {
-            anyhow::bail!("Maximum number of tasks (255) reached");
-        }
-
         Ok(id)
     }
 
     pub fn save(&self) -> Result<()> {
+        self.save_as(EntryKind::Op)
+    }
+
+    /// Appends what changed since the last load or save to the journal. A journal that cannot
+    /// be written only costs undo history, so it warns instead of failing the save.
+    fn journal_changes(&self, kind: EntryKind) {
+        let changes = journal::diff(&self.snapshot.borrow(), &self.tasks);
+        self.snapshot.replace(self.tasks.clone());
+        if changes.is_empty() {
+            return;
+        }
+        if let Err(e) = journal::append(&self.db_path, kind, journal::current_command(), changes) {
+            eprintln!(
+                "{}",
+                format!("Warning: Failed to write the undo journal: {e}").yellow()
+            );
+        }
+    }
+
+    /// Adds a tombstone for each task removed since the last load or save, and drops those of
+    /// tasks that are back (after an undo, for example).
+    fn record_deletions(&self) {
+        let present: HashSet<Uuid> = self.tasks.iter().map(|t| t.uuid).collect();
+        let now = chrono::Utc::now();
+        let mut meta = self.meta.borrow_mut();
+        for task in self.snapshot.borrow().iter() {
+            if !task.uuid.is_nil()
+                && !present.contains(&task.uuid)
+                && !meta.deleted.iter().any(|d| d.uuid == task.uuid)
+            {
+                meta.deleted.push(Tombstone {
+                    uuid: task.uuid,
+                    deleted_at: now,
+                    archived: false,
+                });
+            }
+        }
+        meta.deleted.retain(|d| !present.contains(&d.uuid));
+    }
+
+    /// Renumbers the tasks 1..n in `order`, moving them into that order too. Returns the IDs
+    /// that changed as `(old, new)`; the mapping is also kept in the database (see
+    /// [`renumberings`](Self::renumberings)). Saves the change.
+    pub fn renumber(&mut self, order: RenumberOrder) -> Result<Vec<(TaskId, TaskId)>> {
+        match order {
+            RenumberOrder::Position => {}
+            RenumberOrder::Due => self
+                .tasks
+                .sort_by_key(|t| (t.date.is_none(), t.due_datetime())),
+            RenumberOrder::Priority => self.tasks.sort_by_key(|t| {
+                (
+                    std::cmp::Reverse(t.priority),
+                    t.date.is_none(),
+                    t.due_datetime(),
+                )
+            }),
+        }
+        let mut mapping = Vec::new();
+        for (new_id, task) in (1..).zip(self.tasks.iter_mut()) {
+            if task.id != new_id {
+                mapping.push((task.id, new_id));
+                task.id = new_id;
+            }
+        }
+        if !mapping.is_empty() {
+            let meta = self.meta.get_mut();
+            // Compacting is the point: count on from the new highest ID.
+            if meta.last_id != 0 {
+                meta.last_id = self.tasks.iter().map(|t| t.id).max().unwrap_or(0);
+            }
+            meta.renumbered.push(Renumbering {
+                at: chrono::Utc::now(),
+                order: order.as_str().to_string(),
+                ids: mapping.clone(),
+            });
+        }
+        self.save()?;
+        Ok(mapping)
+    }
+
+    /// Past renumberings, oldest first.
+    pub fn renumberings(&self) -> Vec<Renumbering> {
+        self.meta.borrow().renumbered.clone()
+    }
+
+    /// Highest ID handed out, as tracked while `ids.reuse` is off; 0 when it is not tracked.
+    pub fn last_id(&self) -> TaskId {
+        self.meta.borrow().last_id
+    }
+
+    /// Records that IDs up to `id` have been handed out (written on the next save).
+    pub fn raise_last_id(&mut self, id: TaskId) {
+        let meta = self.meta.get_mut();
+        meta.last_id = meta.last_id.max(id);
+    }
+
+    /// Tombstones of the tasks deleted from this database.
+    pub fn tombstones(&self) -> Vec<Tombstone> {
+        self.meta.borrow().deleted.clone()
+    }
+
+    /// Replaces the tombstones written on the next save. Those of tasks still present are
+    /// dropped then.
+    pub fn set_tombstones(&mut self, deleted: Vec<Tombstone>) {
+        self.meta.get_mut().deleted = deleted;
+    }
+
+    fn save_as(&self, kind: EntryKind) -> Result<()> {
+        let version = self.meta.borrow().version;
+        if version > DB_FORMAT_VERSION {
+            anyhow::bail!(
+                "'{}' uses database format version {}, but this rusk only understands up to \
+                 version {}. Refusing to write so newer data is not lost; upgrade rusk.",
+                self.db_path.display(),
+                version,
+                DB_FORMAT_VERSION
+            );
+        }
         if let Some(parent) = self.db_path.parent() {
             fs::create_dir_all(parent)
                 .context("Failed to create directory for the database file")?;
         }
 
-        if self.db_path.exists() {
-            let backup_path = self.db_path.with_extension("json.backup");
-            if let Err(e) = fs::copy(&self.db_path, &backup_path) {
-                eprintln!(
-                    "{}",
-                    format!("Warning: Failed to create backup: {e}").yellow()
-                );
-            }
+        if let Err(e) = backup::create_backup(&self.db_path, backup::backup_retention()) {
+            eprintln!(
+                "{}",
+                format!("Warning: Failed to create backup: {e}").yellow()
+            );
         }
 
-        let data =
-            serde_json::to_string_pretty(&self.tasks).context("Failed to serialize tasks")?;
+        self.record_deletions();
+        if !config::current().reuse_ids() {
+            // Tasks deleted by this change count too (renumbered ones do not).
+            let present: HashSet<Uuid> = self.tasks.iter().map(|t| t.uuid).collect();
+            let snapshot = self.snapshot.borrow();
+            let deleted = snapshot.iter().filter(|t| !present.contains(&t.uuid));
+            let highest = self.tasks.iter().chain(deleted).map(|t| t.id).max();
+            let highest = highest.unwrap_or(0);
+            let mut meta = self.meta.borrow_mut();
+            meta.last_id = meta.last_id.max(highest);
+        }
+        let data = db_format::encode(&self.tasks, &self.meta.borrow())
+            .context("Failed to serialize tasks")?;
+        let written_hash = content_hash(data.as_bytes());
 
-        let temp_path = self.db_path.with_extension("json.tmp");
+        atomic_write::write_atomic(&self.db_path, data.as_bytes())
+            .context("Failed to save tasks")?;
 
-        if let Some(temp_parent) = temp_path.parent() {
-            fs::create_dir_all(temp_parent)
-                .context("Failed to create directory for temporary file")?;
-        }
+        self.disk_hash.set(Some(written_hash));
+        self.journal_changes(kind);
+        Ok(())
+    }
 
-        fs::write(&temp_path, &data).context("Failed to write temporary database file")?;
+    /// Database path: `--db` when given, the debug location in debug builds, `RUSK_DB` when set,
+    /// otherwise `tasks.json` in the nearest `.rusk` directory (see [`find_db_dir`]), then
+    /// `database.path` from the user config; `--list` then picks a sibling file. Falls back to
+    /// `./.rusk` when there is no `.rusk` directory; [`locate_db_path`](Self::locate_db_path)
+    /// reports that case as an error instead.
+    pub fn resolve_db_path() -> PathBuf {
+        Self::locate_db_path().unwrap_or_else(|_| {
+            PathBuf::from(DB_DIR_NAME).join(list_file_name(Self::selected_list()))
+        })
+    }
 
-        let ensure_dir = || {
-            if let Some(parent) = self.db_path.parent() {
-                fs::create_dir_all(parent)
-            } else {
-                Ok(())
-            }
+    /// Like [`resolve_db_path`](Self::resolve_db_path), but fails when no `.rusk` directory
+    /// exists here or above, so commands do not silently start a new list in a subfolder.
+    pub fn locate_db_path() -> Result<PathBuf> {
+        let base = match DB_SELECTION.get().and_then(|s| s.db.as_deref()) {
+            Some(db) => db_file_at(db),
+            None => Self::default_db_path()?,
         };
+        Ok(match DB_SELECTION.get().and_then(|s| s.list.as_deref()) {
+            Some(list) => base.with_file_name(list_file_name(list)),
+            None => base,
+        })
+    }
 
-        match fs::rename(&temp_path, &self.db_path) {
-            Ok(_) => {}
-            Err(e) => {
-                ensure_dir().ok();
-
-                match fs::copy(&temp_path, &self.db_path) {
-                    Ok(_) => {
-                        let _ = fs::remove_file(&temp_path);
-                        if !Self::is_test_mode() {
-                            eprintln!(
-                                "{}",
-                                format!(
-                                    "Warning: Atomic rename failed ({e}), used copy+remove instead"
-                                )
-                                .yellow()
-                            );
-                        }
-                    }
-                    Err(copy_err) => {
-                        ensure_dir().ok();
-                        let _ = fs::remove_file(&temp_path);
-                        fs::write(&self.db_path, data).context("Failed to write database file")?;
-                        if !Self::is_test_mode() {
-                            eprintln!(
-                                "{}",
-                                format!(
-                                    "Warning: Atomic write failed ({e}), copy fallback also failed ({copy_err}), used direct write instead"
-                                )
-                                .yellow()
-                            );
-                        }
-                    }
-                }
-            }
+    fn default_db_path() -> Result<PathBuf> {
+        if Self::is_test_mode() || cfg!(debug_assertions) {
+            return Ok(std::env::temp_dir()
+                .join("rusk_debug")
+                .join(list_file_name(DEFAULT_LIST)));
+        }
+        if let Ok(db_path) = std::env::var("RUSK_DB") {
+            return Ok(db_file_at(Path::new(&db_path)));
+        }
+        let cwd = std::env::current_dir().context("Failed to read the current directory")?;
+        if let Some(dir) = find_db_dir(&cwd, dirs::home_dir().as_deref()) {
+            return Ok(dir.join(list_file_name(DEFAULT_LIST)));
         }
+        match config::fallback_db_path() {
+            Some(path) => Ok(db_file_at(&path)),
+            None => anyhow::bail!(
+                "No rusk database in '{}' or its parent directories. Run `rusk init` to create \
+                 one here, or choose one with --db or RUSK_DB.",
+                cwd.display()
+            ),
+        }
+    }
 
-        Ok(())
+    /// Chooses the database for this process from the `--db` and `--list` options. Call once,
+    /// before anything resolves the database path.
+    pub fn select_db(selection: DbSelection) -> Result<()> {
+        if let Some(list) = &selection.list {
+            validate_list_name(list)?;
+        }
+        DB_SELECTION
+            .set(selection)
+            .map_err(|_| anyhow::anyhow!("The database was already selected"))
     }
 
-    pub fn resolve_db_path() -> PathBuf {
-        if Self::is_test_mode() {
-            std::env::temp_dir().join("rusk_debug").join("tasks.json")
-        } else if cfg!(debug_assertions) {
-            std::env::temp_dir().join("rusk_debug").join("tasks.json")
-        } else {
-            if let Ok(db_path) = std::env::var("RUSK_DB") {
-                let path = PathBuf::from(db_path);
-                if path.is_dir() || path.to_string_lossy().ends_with('/') {
-                    path.join("tasks.json")
-                } else {
-                    path
-                }
-            } else {
-                PathBuf::from(".rusk").join("tasks.json")
-            }
+    /// `--db` given on the command line, if any.
+    pub fn selected_db() -> Option<&'static Path> {
+        DB_SELECTION.get().and_then(|s| s.db.as_deref())
+    }
+
+    /// Name of the list chosen with `--list`, or [`DEFAULT_LIST`].
+    pub fn selected_list() -> &'static str {
+        DB_SELECTION
+            .get()
+            .and_then(|s| s.list.as_deref())
+            .unwrap_or(DEFAULT_LIST)
+    }
+
+    /// Creates an empty database (the `--list` one, if given) in `dir/.rusk` for
+    /// [`find_db_dir`] to find. Fails when it already exists.
+    pub fn init_db(dir: &Path) -> Result<PathBuf> {
+        let db_path = dir
+            .join(DB_DIR_NAME)
+            .join(list_file_name(Self::selected_list()));
+        Self::create_db(&db_path)?;
+        Ok(db_path)
+    }
+
+    /// Writes an empty database to `db_path`, creating its directory. Fails when the file exists.
+    pub fn create_db(db_path: &Path) -> Result<()> {
+        if db_path.exists() {
+            anyhow::bail!("A rusk database already exists at '{}'", db_path.display());
+        }
+        if let Some(dir) = db_path.parent()
+            && !dir.as_os_str().is_empty()
+        {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create '{}'", dir.display()))?;
         }
+        let data = db_format::encode(&[], &DbMeta::default())?;
+        atomic_write::write_atomic(db_path, data.as_bytes())
     }
 
     pub fn get_db_dir() -> PathBuf {
@@ -535,14 +1744,44 @@ impl TaskManager {
         db_path.parent().unwrap_or(&db_path).to_path_buf()
     }
 
+    /// Assigns a fresh UUID to every task that has none yet. Returns whether anything changed.
+    fn backfill_uuids(tasks: &mut [Task]) -> bool {
+        let mut changed = false;
+        for task in tasks.iter_mut().filter(|t| t.uuid.is_nil()) {
+            task.uuid = Uuid::new_v4();
+            changed = true;
+        }
+        changed
+    }
+
+    /// [`load_tasks_from_path`](Self::load_tasks_from_path) plus the envelope metadata and whether
+    /// UUIDs had to be backfilled (the caller should save so they stay stable). Tags are always
+    /// re-derived from the text, so hand-edited databases stay consistent.
+    fn load_tasks_with_backfill(path: &PathBuf) -> Result<(Vec<Task>, DbMeta, bool)> {
+        let (mut tasks, meta) = Self::read_tasks_file(path)?;
+        for task in &mut tasks {
+            task.tags = extract_tags(&task.text);
+        }
+        let backfilled = Self::backfill_uuids(&mut tasks);
+        Ok((tasks, meta, backfilled))
+    }
+
+    /// Loads tasks from `path` (empty when the file does not exist), upgrading older formats.
+    /// Tasks without a UUID get a fresh one in memory.
     pub fn load_tasks_from_path(path: &PathBuf) -> Result<Vec<Task>> {
+        Self::load_tasks_with_backfill(path).map(|(tasks, _, _)| tasks)
+    }
+
+    fn read_tasks_file(path: &PathBuf) -> Result<(Vec<Task>, DbMeta)> {
         if !path.exists() {
-            Ok(Vec::new())
+            Ok((Vec::new(), DbMeta::default()))
         } else {
             let data = fs::read_to_string(path).context("Failed to read the database file")?;
 
             match serde_json::from_str(&data) {
-                Ok(tasks) => Ok(tasks),
+                Ok(doc) => db_format::decode(doc).with_context(|| {
+                    format!("Failed to parse the database file at '{}'", path.display())
+                }),
                 Err(e) => {
                     let context_line = json_error_line_context(&data, &e)
                         .map(|c| format!(" Context: {c}"))
@@ -552,9 +1791,9 @@ impl TaskManager {
                         JSON parsing error: {}{}\n\
                         \n\
                         To fix this issue, you can:\n\
-                        1. Delete the corrupted file: rm '{}'\n\
-                        2. Or restore from backup if you have one\n\
-                        3. The application will create a new empty database on next run",
+                        1. Run `rusk doctor` to see what is wrong and `rusk doctor --fix` to repair it\n\
+                        2. Or restore from backup: `rusk restore --list`\n\
+                        3. Or delete the corrupted file: rm '{}' (a new empty database is created on next run)",
                         path.display(),
                         e,
                         context_line,
@@ -566,29 +1805,43 @@ impl TaskManager {
         }
     }
 
+    /// Restores the newest backup (see [`backup::list_backups`]).
     pub fn restore_from_backup(&mut self) -> Result<()> {
-        let backup_path = self.db_path.with_extension("json.backup");
+        let newest = backup::list_backups(&self.db_path)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                anyhow::anyhow!("No backup file found for '{}'", self.db_path.display())
+            })?;
+        self.restore_from_path(&newest.path)
+    }
 
+    /// Replaces the database with the backup (or any database file) at `backup_path`. The
+    /// current file is backed up first when it is readable, so a restore can itself be undone.
+    pub fn restore_from_path(&mut self, backup_path: &std::path::Path) -> Result<()> {
         if !backup_path.exists() {
             anyhow::bail!("No backup file found at '{}'", backup_path.display());
         }
 
-        let backup_tasks = Self::load_tasks_from_path(&backup_path)?;
+        let backup_path = backup_path.to_path_buf();
+        let (backup_tasks, backup_meta, _) = Self::load_tasks_with_backfill(&backup_path)?;
+        // Read before backing up the current file: pruning may remove the file being restored.
+        let data = fs::read(&backup_path).context("Failed to read the backup file")?;
 
+        let mut previous = None;
         if self.db_path.exists() {
-            let current_backup_path = self.db_path.with_extension("json.before_restore");
             match Self::load_tasks_from_path(&self.db_path) {
-                Ok(_) => {
-                    if let Err(e) = fs::copy(&self.db_path, &current_backup_path) {
-                        eprintln!(
+                Ok(current) => {
+                    previous = Some(current);
+                    match backup::create_backup(&self.db_path, backup::backup_retention().max(1)) {
+                        Ok(Some(path)) => {
+                            println!("Current database backed up to: {}", path.display())
+                        }
+                        Ok(None) => {}
+                        Err(e) => eprintln!(
                             "{}",
                             format!("Warning: Failed to backup current database: {e}").yellow()
-                        );
-                    } else {
-                        println!(
-                            "Current database backed up to: {}",
-                            current_backup_path.display()
-                        );
+                        ),
                     }
                 }
                 Err(_) => {
@@ -597,9 +1850,20 @@ impl TaskManager {
             }
         }
 
-        fs::copy(&backup_path, &self.db_path).context("Failed to restore from backup")?;
+        if let Some(parent) = self.db_path.parent() {
+            fs::create_dir_all(parent)
+                .context("Failed to create directory for the database file")?;
+        }
+        atomic_write::write_atomic(&self.db_path, &data)
+            .context("Failed to restore from backup")?;
 
         self.tasks = backup_tasks;
+        self.meta.replace(backup_meta);
+        self.disk_hash.set(Some(content_hash(&data)));
+        // Journal the restore against the replaced tasks (nothing to compare when unreadable).
+        self.snapshot
+            .replace(previous.unwrap_or_else(|| self.tasks.clone()));
+        self.journal_changes(EntryKind::Op);
 
         println!(
             "Successfully restored {} tasks from backup",
@@ -625,4 +1889,49 @@ mod tests {
         assert_eq!(tm.tasks[0].text, "Hello");
         assert_eq!(tm.tasks[0].date, Some(d));
     }
+
+    #[test]
+    fn failed_save_leaves_database_and_journal_untouched() {
+        use crate::atomic_write::{SaveStep, fail_at};
+
+        for at in [
+            SaveStep::CreateTemp,
+            SaveStep::WriteTemp,
+            SaveStep::SyncTemp,
+            SaveStep::Rename,
+        ] {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("tasks.json");
+            let mut tm = TaskManager::new_empty_with_path(path.clone());
+            tm.add_task_with_parsed_date("Kept".to_string(), None)
+                .unwrap();
+            let saved = std::fs::read(&path).unwrap();
+            let journal = crate::journal::read_journal(&path).unwrap().len();
+
+            tm.tasks[0].text = "Changed".to_string();
+            fail_at(Some(at));
+            let result = tm.save();
+            fail_at(None);
+
+            assert!(result.is_err(), "{at:?} should fail the save");
+            assert_eq!(std::fs::read(&path).unwrap(), saved, "{at:?}");
+            assert_eq!(
+                crate::journal::read_journal(&path).unwrap().len(),
+                journal,
+                "{at:?}"
+            );
+            let stray: Vec<_> = std::fs::read_dir(dir.path())
+                .unwrap()
+                .flatten()
+                .filter(|e| e.file_name().to_string_lossy().ends_with(".tmp"))
+                .collect();
+            assert!(stray.is_empty(), "{at:?}");
+
+            tm.save().unwrap();
+            assert_eq!(
+                TaskManager::new_with_path(path).unwrap().tasks[0].text,
+                "Changed"
+            );
+        }
+    }
 }
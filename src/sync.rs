@@ -0,0 +1,400 @@
+//! Two-way sync of task databases (`rusk sync`).
+//!
+//! Tasks are matched by UUID. A task only one side has is copied to the other, unless the other
+//! side has a [`Tombstone`] for it: then the deletion travels instead, and a task archived on
+//! one side goes to the other side's archive too. When both sides have a task and it differs,
+//! the side that changed it since the last sync of the pair wins; when both did (or the pair
+//! never synced), the more recent change wins, comparing `updated_at` and `deleted_at`. Each
+//! database keeps its own IDs; copied tasks take the next free ID when theirs is taken, or the
+//! next one above the highest ever handed out in a database that does not reuse IDs.
+//!
+//! Blocker links that only form a cycle once both sides are combined are dropped on both.
+//!
+//! The time of the last sync is kept per database pair in a `.sync` file next to the local
+//! database.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::atomic_write;
+use crate::db_format::{self, Tombstone};
+use crate::model::{self, Task, TaskId};
+
+/// Database that received a change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// From the other database into the local one.
+    Pulled,
+    /// From the local database into the other one.
+    Pushed,
+}
+
+/// What was copied across.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Added,
+    Changed,
+    Deleted,
+    /// Moved to the archive.
+    Archived,
+}
+
+/// One task that differed between the databases.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub uuid: Uuid,
+    pub text: String,
+    /// ID of the task in the local database (before the sync, for a deletion), if it has one.
+    pub local_id: Option<TaskId>,
+    /// ID of the task in the other database, the same way.
+    pub other_id: Option<TaskId>,
+    pub direction: Direction,
+    pub change: Change,
+    /// Both sides changed the task; the more recent change was kept.
+    pub conflict: bool,
+}
+
+/// One database going into [`sync`].
+#[derive(Debug, Clone, Copy)]
+pub struct Side<'a> {
+    pub tasks: &'a [Task],
+    pub deleted: &'a [Tombstone],
+    /// Highest ID ever handed out when the database does not reuse IDs (`ids.reuse = false`);
+    /// `None` when it does.
+    pub last_id: Option<TaskId>,
+}
+
+impl<'a> Side<'a> {
+    /// A database that reuses IDs.
+    pub fn new(tasks: &'a [Task], deleted: &'a [Tombstone]) -> Self {
+        Self {
+            tasks,
+            deleted,
+            last_id: None,
+        }
+    }
+}
+
+/// Result of [`sync`]: the new contents of both databases.
+#[derive(Debug, Clone)]
+pub struct Synced {
+    pub local: Vec<Task>,
+    pub other: Vec<Task>,
+    /// Tombstones for both databases.
+    pub deleted: Vec<Tombstone>,
+    pub events: Vec<Event>,
+    /// Tasks to move to each database's archive, as they were there.
+    pub local_archived: Vec<Task>,
+    pub other_archived: Vec<Task>,
+    /// Blocker links dropped from both databases because they made a cycle, as `(task,
+    /// blocker)` IDs in the local database.
+    pub unblocked: Vec<(TaskId, TaskId)>,
+    /// New highest IDs handed out, for the databases that do not reuse IDs.
+    pub local_last_id: Option<TaskId>,
+    pub other_last_id: Option<TaskId>,
+}
+
+/// A task's state in one database.
+#[derive(Clone, Copy)]
+enum State<'a> {
+    Present(&'a Task),
+    /// Deleted, or archived when the flag is set.
+    Deleted(DateTime<Utc>, bool),
+    Absent,
+}
+
+impl State<'_> {
+    fn changed_at(&self) -> Option<DateTime<Utc>> {
+        match self {
+            State::Present(task) => task.updated_at,
+            State::Deleted(at, _) => Some(*at),
+            State::Absent => None,
+        }
+    }
+}
+
+fn state<'a>(tasks: &HashMap<Uuid, &'a Task>, deleted: &[Tombstone], uuid: Uuid) -> State<'a> {
+    match tasks.get(&uuid) {
+        Some(task) => State::Present(task),
+        None => deleted
+            .iter()
+            .find(|d| d.uuid == uuid)
+            .map_or(State::Absent, |d| State::Deleted(d.deleted_at, d.archived)),
+    }
+}
+
+/// Whether two versions of a task differ in anything but their ID and derived fields.
+fn differ(a: &Task, b: &Task) -> bool {
+    let strip = |task: &Task| -> Map<String, Value> {
+        let mut map = match serde_json::to_value(task) {
+            Ok(Value::Object(map)) => map,
+            _ => Map::new(),
+        };
+        map.remove("id");
+        map.remove("tags");
+        map
+    };
+    strip(a) != strip(b)
+}
+
+/// Lowest ID not in `used`, or `wanted` when it is free.
+fn free_id(wanted: TaskId, used: &mut HashSet<TaskId>) -> Result<TaskId> {
+    let mut id = wanted;
+    if id == 0 || used.contains(&id) {
+        id = 1;
+        while used.contains(&id) {
+            id = id
+                .checked_add(1)
+                .with_context(|| format!("Maximum task ID ({}) reached", TaskId::MAX))?;
+        }
+    }
+    used.insert(id);
+    Ok(id)
+}
+
+/// One database while the sync is applied to it.
+struct Target {
+    tasks: Vec<Task>,
+    used: HashSet<TaskId>,
+    /// Highest ID handed out so far, when IDs are not reused.
+    last_id: Option<TaskId>,
+    /// Tasks removed to go to the archive.
+    archived: Vec<Task>,
+}
+
+impl Target {
+    fn new(side: Side) -> Self {
+        let highest = side.tasks.iter().map(|t| t.id).max().unwrap_or(0);
+        Self {
+            tasks: side.tasks.to_vec(),
+            used: side.tasks.iter().map(|t| t.id).collect(),
+            last_id: side.last_id.map(|last| last.max(highest)),
+            archived: Vec::new(),
+        }
+    }
+
+    /// ID for a task copied in with `wanted`: `wanted` unless it is taken, or in a database that
+    /// does not reuse IDs, unless it was ever handed out.
+    fn allocate(&mut self, wanted: TaskId) -> Result<TaskId> {
+        let Some(last) = self.last_id else {
+            return free_id(wanted, &mut self.used);
+        };
+        let id = if wanted > last {
+            wanted
+        } else {
+            last.checked_add(1)
+                .with_context(|| format!("Maximum task ID ({}) reached", TaskId::MAX))?
+        };
+        self.used.insert(id);
+        self.last_id = Some(id);
+        Ok(id)
+    }
+
+    /// Puts `task` in place of this database's version, or adds it.
+    fn put(&mut self, task: &Task) -> Result<()> {
+        if let Some(existing) = self.tasks.iter_mut().find(|t| t.uuid == task.uuid) {
+            *existing = Task {
+                id: existing.id,
+                ..task.clone()
+            };
+            return Ok(());
+        }
+        let id = self.allocate(task.id)?;
+        self.tasks.push(Task { id, ..task.clone() });
+        Ok(())
+    }
+
+    fn remove(&mut self, uuid: Uuid) {
+        self.tasks.retain(|t| t.uuid != uuid);
+    }
+
+    /// Removes the task for the archive.
+    fn archive(&mut self, uuid: Uuid) {
+        if let Some(i) = self.tasks.iter().position(|t| t.uuid == uuid) {
+            self.archived.push(self.tasks.remove(i));
+        }
+    }
+
+    fn id_of(&self, uuid: Uuid) -> Option<TaskId> {
+        self.tasks.iter().find(|t| t.uuid == uuid).map(|t| t.id)
+    }
+}
+
+/// Syncs the local database with the other one. `last_sync` is when the two were last synced,
+/// if ever.
+pub fn sync(local: Side, other: Side, last_sync: Option<DateTime<Utc>>) -> Result<Synced> {
+    let local_by_uuid: HashMap<Uuid, &Task> = local.tasks.iter().map(|t| (t.uuid, t)).collect();
+    let other_by_uuid: HashMap<Uuid, &Task> = other.tasks.iter().map(|t| (t.uuid, t)).collect();
+    let mut uuids: Vec<Uuid> = Vec::new();
+    let mut seen = HashSet::new();
+    for uuid in local.tasks.iter().chain(other.tasks).map(|t| t.uuid) {
+        if seen.insert(uuid) {
+            uuids.push(uuid);
+        }
+    }
+
+    let since_last = |at: Option<DateTime<Utc>>| match last_sync {
+        Some(last) => at.is_some_and(|at| at > last),
+        None => true,
+    };
+    let (mut local_out, mut other_out) = (Target::new(local), Target::new(other));
+    let mut events = Vec::new();
+    for uuid in uuids {
+        let ours = state(&local_by_uuid, local.deleted, uuid);
+        let theirs = state(&other_by_uuid, other.deleted, uuid);
+        if let (State::Present(a), State::Present(b)) = (ours, theirs)
+            && !differ(a, b)
+        {
+            continue;
+        }
+
+        let (direction, conflict) = match (ours, theirs) {
+            (State::Present(_), State::Absent) => (Direction::Pushed, false),
+            (State::Absent, State::Present(_)) => (Direction::Pulled, false),
+            _ => {
+                let (ours_at, theirs_at) = (ours.changed_at(), theirs.changed_at());
+                match (since_last(ours_at), since_last(theirs_at)) {
+                    (true, false) => (Direction::Pushed, false),
+                    (false, true) => (Direction::Pulled, false),
+                    _ if theirs_at > ours_at => (Direction::Pulled, true),
+                    _ => (Direction::Pushed, true),
+                }
+            }
+        };
+        let (winner, loser_had_it) = match direction {
+            Direction::Pushed => (ours, matches!(theirs, State::Present(_))),
+            Direction::Pulled => (theirs, matches!(ours, State::Present(_))),
+        };
+        let target = match direction {
+            Direction::Pushed => &mut other_out,
+            Direction::Pulled => &mut local_out,
+        };
+        let (change, text) = match winner {
+            State::Present(task) => {
+                target.put(task)?;
+                let change = if loser_had_it {
+                    Change::Changed
+                } else {
+                    Change::Added
+                };
+                (change, task.text.clone())
+            }
+            _ => {
+                let text = match direction {
+                    Direction::Pushed => other_by_uuid.get(&uuid),
+                    Direction::Pulled => local_by_uuid.get(&uuid),
+                }
+                .map(|t| t.text.clone())
+                .unwrap_or_default();
+                if matches!(winner, State::Deleted(_, true)) {
+                    target.archive(uuid);
+                    (Change::Archived, text)
+                } else {
+                    target.remove(uuid);
+                    (Change::Deleted, text)
+                }
+            }
+        };
+        events.push(Event {
+            uuid,
+            text,
+            local_id: local_out
+                .id_of(uuid)
+                .or(local_by_uuid.get(&uuid).map(|t| t.id)),
+            other_id: other_out
+                .id_of(uuid)
+                .or(other_by_uuid.get(&uuid).map(|t| t.id)),
+            direction,
+            change,
+            conflict,
+        });
+    }
+
+    let links: Vec<(Uuid, Uuid)> = model::cycle_links(&local_out.tasks)
+        .into_iter()
+        .map(|(i, blocker)| (local_out.tasks[i].uuid, blocker))
+        .collect();
+    for (uuid, blocker) in &links {
+        for target in [&mut local_out, &mut other_out] {
+            if let Some(task) = target.tasks.iter_mut().find(|t| t.uuid == *uuid) {
+                task.blocked_by.retain(|b| b != blocker);
+            }
+        }
+    }
+    let unblocked = links
+        .iter()
+        .filter_map(|(uuid, blocker)| Some((local_out.id_of(*uuid)?, local_out.id_of(*blocker)?)))
+        .collect();
+
+    // Both sides end up with every tombstone whose task is gone everywhere.
+    let present: HashSet<Uuid> = local_out
+        .tasks
+        .iter()
+        .chain(&other_out.tasks)
+        .map(|t| t.uuid)
+        .collect();
+    let mut deleted: Vec<Tombstone> = Vec::new();
+    for tombstone in local.deleted.iter().chain(other.deleted) {
+        if present.contains(&tombstone.uuid) {
+            continue;
+        }
+        // The more recent one wins, archived or not.
+        match deleted.iter_mut().find(|d| d.uuid == tombstone.uuid) {
+            Some(d) if tombstone.deleted_at > d.deleted_at => *d = tombstone.clone(),
+            Some(_) => {}
+            None => deleted.push(tombstone.clone()),
+        }
+    }
+
+    Ok(Synced {
+        local: local_out.tasks,
+        other: other_out.tasks,
+        deleted,
+        events,
+        local_archived: local_out.archived,
+        other_archived: other_out.archived,
+        unblocked,
+        local_last_id: local_out.last_id,
+        other_last_id: other_out.last_id,
+    })
+}
+
+/// File holding the time of the last sync with each other database: `<db file>.sync`.
+pub fn state_path(db_path: &Path) -> PathBuf {
+    db_format::sibling_path(db_path, ".sync")
+}
+
+/// Key of the other database in the state file: its absolute path.
+fn peer_key(other: &Path) -> String {
+    fs::canonicalize(other)
+        .unwrap_or_else(|_| other.to_path_buf())
+        .display()
+        .to_string()
+}
+
+fn read_state(db_path: &Path) -> BTreeMap<String, DateTime<Utc>> {
+    fs::read_to_string(state_path(db_path))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+/// When `db_path` was last synced with `other`. An unreadable state file counts as never.
+pub fn last_sync(db_path: &Path, other: &Path) -> Option<DateTime<Utc>> {
+    read_state(db_path).get(&peer_key(other)).copied()
+}
+
+/// Records that `db_path` was synced with `other` at `at`.
+pub fn record_sync(db_path: &Path, other: &Path, at: DateTime<Utc>) -> Result<()> {
+    let mut state = read_state(db_path);
+    state.insert(peer_key(other), at);
+    let data = serde_json::to_string_pretty(&state)?;
+    atomic_write::write_atomic(&state_path(db_path), data.as_bytes())
+        .context("Failed to record the sync time")
+}
@@ -0,0 +1,135 @@
+// Archiving tasks next to the database: rusk archive / unarchive, list --archived, auto-archive
+
+use std::fs;
+
+use chrono::{Duration, Utc};
+use rusk::archive::{self, archive_path};
+use rusk::db_format::{self, DbMeta};
+use rusk::{NewTaskOptions, Task, TaskManager};
+use tempfile::tempdir;
+use uuid::Uuid;
+
+mod common;
+
+fn setup(dir: &tempfile::TempDir) -> TaskManager {
+    let mut tm = common::manager_with_tasks(dir.path(), &["Parent", "Other"]);
+    tm.add_task_with_options(
+        "Child".to_string(),
+        None,
+        &NewTaskOptions {
+            parent: Some(1),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    tm
+}
+
+#[test]
+fn test_archive_done_keeps_tasks_with_open_subtasks() {
+    let dir = tempdir().unwrap();
+    let mut tm = setup(&dir);
+    tm.mark_tasks(vec![1, 2]).unwrap();
+    assert_eq!(tm.archive_done().unwrap(), 1);
+    assert_eq!(
+        common::ids_and_texts(tm.tasks()),
+        vec![(1, "Parent"), (3, "Child")]
+    );
+    assert_eq!(
+        common::ids_and_texts(&tm.archived_tasks().unwrap()),
+        vec![(2, "Other")]
+    );
+
+    tm.mark_tasks(vec![3]).unwrap();
+    assert_eq!(tm.archive_done().unwrap(), 2);
+    assert!(tm.tasks().is_empty());
+    let (archived, _) = archive::read(&archive_path(&tm.db_path)).unwrap();
+    assert_eq!(
+        common::ids_and_texts(&archived),
+        vec![(2, "Other"), (1, "Parent"), (3, "Child")]
+    );
+}
+
+#[test]
+fn test_unarchive_brings_subtasks_back_under_a_free_id() {
+    let dir = tempdir().unwrap();
+    let mut tm = setup(&dir);
+    let (moved, not_found) = tm.archive_tasks(vec![1, 9]).unwrap();
+    assert_eq!((moved, not_found), (vec![1, 3], vec![9]));
+    tm.add_task(vec!["Newcomer".to_string()], None).unwrap();
+    assert_eq!(tm.tasks()[1].id, 1);
+
+    let (restored, not_found) = tm.unarchive(vec![1, 7]).unwrap();
+    assert_eq!(restored, vec![(1, 3), (3, 4)]);
+    assert_eq!(not_found, vec![7]);
+    let parent = tm.tasks()[2].uuid;
+    assert_eq!(tm.tasks()[3].parent, Some(parent));
+    assert!(tm.archived_tasks().unwrap().is_empty());
+}
+
+#[test]
+fn test_unarchived_subtask_without_its_parent_becomes_top_level() {
+    let dir = tempdir().unwrap();
+    let mut tm = setup(&dir);
+    tm.archive_tasks(vec![1]).unwrap();
+    tm.unarchive(vec![3]).unwrap();
+    let child = tm.tasks().iter().find(|t| t.text == "Child").unwrap();
+    assert_eq!(child.parent, None);
+    assert_eq!(
+        common::ids_and_texts(&tm.archived_tasks().unwrap()),
+        vec![(1, "Parent")]
+    );
+}
+
+#[test]
+fn test_undone_archive_is_not_listed_twice() {
+    let dir = tempdir().unwrap();
+    let mut tm = setup(&dir);
+    tm.archive_tasks(vec![2]).unwrap();
+    tm.undo(1).unwrap();
+    assert_eq!(tm.tasks().len(), 3);
+    assert!(tm.archived_tasks().unwrap().is_empty());
+}
+
+#[test]
+fn test_old_done_tasks_are_archived_after_a_change() {
+    let temp_dir = tempdir().unwrap();
+    let db = temp_dir.path().join("db/");
+    assert!(common::run_rusk(&db, &["init"]).status.success());
+    let long_ago = Utc::now() - Duration::days(40);
+    let recent = Utc::now() - Duration::days(2);
+    let done = |id, text: &str, at| Task {
+        uuid: Uuid::new_v4(),
+        done: true,
+        completed_at: Some(at),
+        updated_at: Some(at),
+        ..common::create_test_task(id, text, true)
+    };
+    let tasks = vec![
+        done(1, "finished long ago", long_ago),
+        done(2, "just finished", recent),
+    ];
+    fs::write(
+        db.join("tasks.json"),
+        db_format::encode(&tasks, &DbMeta::default()).unwrap(),
+    )
+    .unwrap();
+
+    let out = common::run_rusk(&db, &["config", "set", "archive.after_days", "30"]);
+    assert!(out.status.success(), "{out:?}");
+    assert!(common::run_rusk(&db, &["add", "new task"]).status.success());
+
+    let out = common::run_rusk(&db, &["list"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(!stdout.contains("finished long ago"), "{stdout}");
+    assert!(stdout.contains("just finished"), "{stdout}");
+    let out = common::run_rusk(&db, &["list", "--archived"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("finished long ago"), "{stdout}");
+
+    // Brought back on purpose, it stays until it is left alone for another 30 days.
+    assert!(common::run_rusk(&db, &["unarchive", "1"]).status.success());
+    assert!(common::run_rusk(&db, &["add", "another"]).status.success());
+    let out = common::run_rusk(&db, &["list"]);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("finished long ago"));
+}
@@ -1,5 +1,5 @@
 use chrono::{Local, NaiveDate};
-use rusk::{TaskManager, normalize_date_string, parse_cli_date_with_base};
+use rusk::{TaskId, TaskManager, normalize_date_string, parse_cli_date_with_base};
 
 #[test]
 fn test_cli_add_command() {
@@ -64,7 +64,7 @@ fn test_cli_delete_command() {
     assert_eq!(tm.tasks.len(), 2);
 
     // After deletion, remaining tasks should have IDs 2 and 4
-    let remaining_ids: Vec<u8> = tm.tasks.iter().map(|t| t.id).collect();
+    let remaining_ids: Vec<TaskId> = tm.tasks.iter().map(|t| t.id).collect();
     assert!(remaining_ids.contains(&2));
     assert!(remaining_ids.contains(&4));
 
@@ -113,7 +113,7 @@ fn test_cli_delete_error_no_ids_no_done() {
 
     // Test error message when neither IDs nor --done flag are provided
     // This should print error message but not fail
-    let result = HandlerCLI::handle_delete_tasks(&mut tm, vec![], false);
+    let result = HandlerCLI::handle_delete_tasks(&mut tm, vec![], false, None);
     assert!(result.is_ok()); // Function succeeds but prints error message
     assert_eq!(tm.tasks.len(), 1); // Task should remain
 }
@@ -573,3 +573,22 @@ fn test_ml_delete_word_left_at_line_start_joins_with_prev() {
     assert_eq!(lines, vec!["abcdef".to_string()]);
     assert_eq!((row, col), (0, 3));
 }
+
+#[test]
+fn test_format_age_buckets() {
+    use chrono::{Duration, TimeZone, Utc};
+    let now = Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap();
+    let age = |d: Duration| HandlerCLI::format_age(now - d, now);
+    assert_eq!(age(Duration::seconds(30)), "just now");
+    assert_eq!(age(Duration::minutes(5)), "5m ago");
+    assert_eq!(age(Duration::hours(3)), "3h ago");
+    assert_eq!(age(Duration::days(2)), "2d ago");
+    assert_eq!(age(Duration::days(21)), "3w ago");
+    assert_eq!(age(Duration::days(120)), "4mo ago");
+    assert_eq!(age(Duration::days(800)), "2y ago");
+    // Clock skew: a timestamp slightly in the future is not negative
+    assert_eq!(
+        HandlerCLI::format_age(now + Duration::minutes(2), now),
+        "just now"
+    );
+}
@@ -1,7 +1,8 @@
 use chrono::NaiveDate;
-use rusk::Task;
+use rusk::{Priority, Task, TaskId, TaskManager};
 use std::env;
 use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
 
 fn rusk_bin_name() -> String {
     format!("rusk{}", env::consts::EXE_SUFFIX)
@@ -14,10 +15,7 @@ fn push_unique(paths: &mut Vec<PathBuf>, path: PathBuf) {
 }
 
 fn push_profile_paths(paths: &mut Vec<PathBuf>, target_dir: &Path, profile: &str) {
-    push_unique(
-        paths,
-        target_dir.join(profile).join(rusk_bin_name()),
-    );
+    push_unique(paths, target_dir.join(profile).join(rusk_bin_name()));
     if let Ok(triple) = env::var("HOST") {
         push_unique(
             paths,
@@ -60,10 +58,7 @@ fn rusk_bin_candidates() -> Vec<PathBuf> {
         }
     }
 
-    push_unique(
-        &mut paths,
-        PathBuf::from(env!("CARGO_BIN_EXE_rusk")),
-    );
+    push_unique(&mut paths, PathBuf::from(env!("CARGO_BIN_EXE_rusk")));
 
     if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
         let target_dir = PathBuf::from(manifest_dir).join("target");
@@ -94,7 +89,10 @@ pub fn rusk_bin_path() -> PathBuf {
 /// Ensures rusk binary exists.
 #[allow(dead_code)]
 pub fn require_rusk_bin() -> anyhow::Result<PathBuf> {
-    if let Some(path) = rusk_bin_candidates().into_iter().find(|candidate| candidate.exists()) {
+    if let Some(path) = rusk_bin_candidates()
+        .into_iter()
+        .find(|candidate| candidate.exists())
+    {
         return Ok(path);
     }
 
@@ -106,35 +104,75 @@ pub fn require_rusk_bin() -> anyhow::Result<PathBuf> {
     )
 }
 
+/// Runs the rusk binary with `--db db` (a database file or directory) and `args`.
+#[allow(dead_code)]
+pub fn run_rusk(db: &Path, args: &[&str]) -> Output {
+    let bin = require_rusk_bin().expect("rusk binary not found, run cargo build");
+    Command::new(bin)
+        .arg("--db")
+        .arg(db)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// A `tasks.json` in `dir` holding one top-level task per text, with IDs from 1.
+#[allow(dead_code)]
+pub fn manager_with_tasks(dir: &Path, texts: &[&str]) -> TaskManager {
+    let mut tm = TaskManager::new_empty_with_path(dir.join("tasks.json"));
+    for text in texts {
+        tm.add_task(vec![text.to_string()], None).unwrap();
+    }
+    tm
+}
+
+/// `(id, text)` of each task, for comparing task lists in assertions.
+#[allow(dead_code)]
+pub fn ids_and_texts(tasks: &[Task]) -> Vec<(TaskId, &str)> {
+    tasks.iter().map(|t| (t.id, t.text.as_str())).collect()
+}
+
+/// The calendar date `y-m-day`, panicking if it does not exist.
+#[allow(dead_code)]
+pub fn ymd(y: i32, m: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, day).unwrap()
+}
+
 #[allow(dead_code)]
-pub fn create_test_task(id: u8, text: &str, done: bool) -> Task {
+pub fn create_test_task(id: TaskId, text: &str, done: bool) -> Task {
     Task {
         id,
         text: text.to_string(),
         date: None,
         done,
-        priority: false,
+        ..Default::default()
     }
 }
 
 #[allow(dead_code)]
-pub fn create_test_task_with_date(id: u8, text: &str, done: bool, date: &str) -> Task {
+pub fn create_test_task_with_date(id: TaskId, text: &str, done: bool, date: &str) -> Task {
     Task {
         id,
         text: text.to_string(),
         date: NaiveDate::parse_from_str(date, "%d-%m-%Y").ok(),
         done,
-        priority: false,
+        ..Default::default()
     }
 }
 
 #[allow(dead_code)]
-pub fn create_test_task_with_priority(id: u8, text: &str, done: bool, priority: bool) -> Task {
+pub fn create_test_task_with_priority(
+    id: TaskId,
+    text: &str,
+    done: bool,
+    priority: Priority,
+) -> Task {
     Task {
         id,
         text: text.to_string(),
         date: None,
         done,
         priority,
+        ..Default::default()
     }
 }
@@ -1,5 +1,7 @@
 #![cfg(feature = "completions")]
 
+mod common;
+
 #[path = "completions/rust/completion_tests.rs"]
 mod completion_tests;
 
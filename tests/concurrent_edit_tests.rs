@@ -0,0 +1,92 @@
+use rusk::{TaskEdit, TaskId, TaskManager};
+use tempfile::tempdir;
+
+mod common;
+
+/// Opens `path` like a second rusk process would.
+fn open(path: &std::path::Path) -> TaskManager {
+    TaskManager::new_with_path(path.to_path_buf()).unwrap()
+}
+
+/// Edits task `id`'s text in memory the way the interactive editor does, returning the edit.
+fn edit_text(tm: &mut TaskManager, id: TaskId, text: &str) -> TaskEdit {
+    let idx = tm.find_task_by_id(id).unwrap();
+    let before = tm.tasks()[idx].clone();
+    let task = &mut tm.tasks_mut()[idx];
+    task.set_text(text.to_string());
+    task.touch();
+    (before, task.clone())
+}
+
+#[test]
+fn test_save_edits_without_concurrent_change() {
+    let dir = tempdir().unwrap();
+    let db_path = common::manager_with_tasks(dir.path(), &["First", "Second"]).db_path;
+    let mut session = open(&db_path);
+    let edit = edit_text(&mut session, 1, "First, reworded");
+    assert!(!session.changed_on_disk().unwrap());
+
+    assert!(session.save_edits(&[edit]).unwrap().is_empty());
+    assert_eq!(open(&db_path).tasks()[0].text, "First, reworded");
+}
+
+#[test]
+fn test_concurrent_add_and_mark_are_kept() {
+    let dir = tempdir().unwrap();
+    let db_path = common::manager_with_tasks(dir.path(), &["First", "Second"]).db_path;
+    let mut session = open(&db_path);
+    let edit = edit_text(&mut session, 1, "First, reworded");
+
+    let mut other = open(&db_path);
+    other
+        .add_task(vec!["Added meanwhile".to_string()], None)
+        .unwrap();
+    other.mark_tasks(vec![1]).unwrap();
+    assert!(session.changed_on_disk().unwrap());
+
+    assert!(session.save_edits(&[edit]).unwrap().is_empty());
+    let tasks = open(&db_path).tasks;
+    assert_eq!(tasks.len(), 3);
+    assert_eq!(tasks[0].text, "First, reworded");
+    assert!(tasks[0].done, "the concurrent mark must survive");
+    assert_eq!(tasks[2].text, "Added meanwhile");
+}
+
+#[test]
+fn test_same_task_changed_on_both_sides_is_a_conflict() {
+    let dir = tempdir().unwrap();
+    let db_path = common::manager_with_tasks(dir.path(), &["First", "Second"]).db_path;
+    let mut session = open(&db_path);
+    let first = edit_text(&mut session, 1, "Mine");
+    let second = edit_text(&mut session, 2, "Second, mine");
+
+    let mut other = open(&db_path);
+    other
+        .edit_tasks(vec![1], Some(vec!["Theirs".to_string()]), None)
+        .unwrap();
+
+    let conflicts = session.save_edits(&[first, second]).unwrap();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].mine.text, "Mine");
+    assert_eq!(conflicts[0].theirs.as_ref().unwrap().text, "Theirs");
+
+    let tasks = open(&db_path).tasks;
+    assert_eq!(tasks[0].text, "Theirs");
+    assert_eq!(tasks[1].text, "Second, mine");
+}
+
+#[test]
+fn test_task_deleted_meanwhile_is_a_conflict() {
+    let dir = tempdir().unwrap();
+    let db_path = common::manager_with_tasks(dir.path(), &["First", "Second"]).db_path;
+    let mut session = open(&db_path);
+    let edit = edit_text(&mut session, 2, "Second, mine");
+
+    let mut other = open(&db_path);
+    other.delete_tasks(vec![2]).unwrap();
+
+    let conflicts = session.save_edits(&[edit]).unwrap();
+    assert_eq!(conflicts.len(), 1);
+    assert!(conflicts[0].theirs.is_none());
+    assert_eq!(open(&db_path).tasks.len(), 1);
+}
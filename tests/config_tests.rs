@@ -0,0 +1,137 @@
+// config.toml settings: precedence, validation, rusk config set
+
+use std::fs;
+use std::process::Command;
+use std::time::Duration;
+
+use rusk::config::{Config, Scope, Source, set};
+use tempfile::tempdir;
+use toml::Value;
+
+mod common;
+
+#[test]
+fn test_project_file_beats_user_file_beats_default() {
+    let temp_dir = tempdir().unwrap();
+    let project = temp_dir.path().join("project.toml");
+    let user = temp_dir.path().join("user.toml");
+    fs::write(&project, "[list]\nmode = \"compact\"\n").unwrap();
+    fs::write(
+        &user,
+        "[list]\nmode = \"full\"\n[editor]\nautosave_secs = 0\n",
+    )
+    .unwrap();
+
+    let config = Config::from_files(Some(&project), Some(&user));
+    assert_eq!(
+        config.get("list.mode").unwrap(),
+        (
+            Value::String("compact".into()),
+            Source::File(Scope::Project)
+        )
+    );
+    assert_eq!(config.autosave_interval(), None);
+    assert_eq!(
+        config.get("confirm.delete").unwrap(),
+        (Value::Boolean(true), Source::Default)
+    );
+    assert!(config.list_compact());
+
+    let defaults = Config::from_files(None, None);
+    assert!(!defaults.list_compact());
+    assert_eq!(defaults.autosave_interval(), Some(Duration::from_secs(3)));
+    assert_eq!(defaults.date_format(), None);
+    assert!(defaults.get("no.such_key").is_err());
+}
+
+#[test]
+fn test_bad_entries_are_skipped_and_the_rest_kept() {
+    let temp_dir = tempdir().unwrap();
+    let project = temp_dir.path().join("config.toml");
+    fs::write(
+        &project,
+        "unknown = 1\n[confirm]\ndelete = \"maybe\"\n[display]\ndate_format = \"%Y-%m-%d\"\n\
+         [database]\npath = \"/elsewhere.json\"\n",
+    )
+    .unwrap();
+    let config = Config::from_files(Some(&project), None);
+    assert!(config.confirm_delete());
+    assert_eq!(config.date_format().as_deref(), Some("%Y-%m-%d"));
+    // database.path is only read from the user file.
+    assert_eq!(config.database_path(), None);
+
+    fs::write(&project, "[list\nmode = ").unwrap();
+    assert!(!Config::from_files(Some(&project), None).list_compact());
+}
+
+#[test]
+fn test_set_validates_and_keeps_other_entries() {
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("nested").join("config.toml");
+    set(&path, Scope::Project, "list.mode", "compact").unwrap();
+    set(&path, Scope::Project, "confirm.delete", "no").unwrap();
+    assert!(set(&path, Scope::Project, "list.mode", "wide").is_err());
+    assert!(set(&path, Scope::Project, "display.date_format", "%Q").is_err());
+    // Time and zone specifiers cannot format a date alone.
+    assert!(set(&path, Scope::Project, "display.date_format", "%H:%M").is_err());
+    assert!(set(&path, Scope::Project, "display.date_format", "%Y-%m-%d %z").is_err());
+    assert!(set(&path, Scope::Project, "database.path", "x.json").is_err());
+    assert!(set(&path, Scope::Project, "archive.after_days", "-1").is_err());
+    assert!(set(&path, Scope::Project, "archive.after_days", "100000000").is_err());
+    assert!(set(&path, Scope::Project, "archive.after_days", "36500").is_ok());
+    assert!(set(&path, Scope::User, "database.path", "x.json").is_ok());
+
+    let config = Config::from_files(Some(&path), None);
+    assert!(config.list_compact());
+    assert!(!config.confirm_delete());
+}
+
+#[test]
+fn test_no_color_env_beats_config() {
+    let temp_dir = tempdir().unwrap();
+    let user = temp_dir.path().join("user.toml");
+    fs::write(&user, "[display]\ncolor = \"always\"\n").unwrap();
+    let config = Config::from_files(None, Some(&user));
+    unsafe {
+        std::env::set_var("RUSK_NO_COLOR", "1");
+    }
+    let (value, source) = config.get("display.color").unwrap();
+    unsafe {
+        std::env::remove_var("RUSK_NO_COLOR");
+    }
+    assert_eq!(value, Value::String("never".into()));
+    assert_eq!(source, Source::Env("RUSK_NO_COLOR"));
+}
+
+#[test]
+fn test_config_set_changes_list_output() {
+    let temp_dir = tempdir().unwrap();
+    let db = temp_dir.path().join("db/");
+    let rusk = |args: &[&str]| {
+        let bin = common::require_rusk_bin().expect("rusk binary not found, run cargo build");
+        Command::new(bin)
+            .env("RUSK_CONFIG", temp_dir.path().join("user.toml"))
+            .arg("--db")
+            .arg(&db)
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    assert!(rusk(&["init"]).status.success());
+    assert!(rusk(&["add", "dated", "-d", "31-12-2030"]).status.success());
+    let out = rusk(&["config", "set", "display.date_format", "%Y/%m/%d"]);
+    assert!(out.status.success(), "{out:?}");
+    assert!(db.join("config.toml").exists());
+
+    let out = rusk(&["list"]);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("2030/12/31"));
+    let out = rusk(&["config", "get", "display.date_format"]);
+    assert_eq!(String::from_utf8_lossy(&out.stdout).trim(), "%Y/%m/%d");
+
+    // A time-only pattern written by hand is ignored with a warning instead of breaking the list.
+    fs::write(db.join("config.toml"), "[display]\ndate_format = \"%H\"\n").unwrap();
+    let out = rusk(&["list"]);
+    assert!(out.status.success(), "{out:?}");
+    assert!(String::from_utf8_lossy(&out.stdout).contains("31-dec-30"));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("date_format"));
+}
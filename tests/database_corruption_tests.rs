@@ -31,7 +31,8 @@ fn test_corrupted_database_error_message() {
     assert!(error_msg.contains("corrupted"));
     assert!(error_msg.contains("trailing characters"));
     assert!(error_msg.contains("To fix this issue"));
-    assert!(error_msg.contains("Delete the corrupted file"));
+    assert!(error_msg.contains("rusk doctor"));
+    assert!(error_msg.contains("delete the corrupted file"));
 }
 
 #[test]
@@ -54,7 +55,7 @@ fn test_invalid_json_structure() {
     let temp_dir = tempdir().unwrap();
     let db_path = temp_dir.path().join("invalid_structure.json");
 
-    // Create JSON with wrong structure (object instead of array)
+    // Create JSON with wrong structure (object without a format version)
     let invalid_json = r#"{
   "tasks": [
     {
@@ -80,7 +81,6 @@ fn test_invalid_json_structure() {
 fn test_backup_creation_on_save() {
     let temp_dir = tempdir().unwrap();
     let db_path = temp_dir.path().join("test_backup.json");
-    let backup_path = db_path.with_extension("json.backup");
 
     let mut tm = TaskManager::new_empty_with_path(db_path.clone());
 
@@ -91,11 +91,14 @@ fn test_backup_creation_on_save() {
     // Add another task (should create backup)
     tm.add_task(vec!["Second task".to_string()], None).unwrap();
 
-    // Check that backup was created
+    // Check that a timestamped backup was created
+    let backups = rusk::backup::list_backups(&db_path).unwrap();
+    assert_eq!(backups.len(), 1);
+    let backup_path = &backups[0].path;
     assert!(backup_path.exists());
 
     // Check that backup contains the previous state
-    let backup_tasks = TaskManager::load_tasks_from_path(&backup_path).unwrap();
+    let backup_tasks = TaskManager::load_tasks_from_path(backup_path).unwrap();
     assert_eq!(backup_tasks.len(), 1);
     assert_eq!(backup_tasks[0].text, "Initial task");
 
@@ -0,0 +1,55 @@
+use std::fs;
+
+use rusk::TaskManager;
+use rusk::storage::find_db_dir;
+use tempfile::tempdir;
+
+#[test]
+fn test_nearest_rusk_dir_above_is_found() {
+    let temp_dir = tempdir().unwrap();
+    let project = temp_dir.path().join("project");
+    let nested = project.join("src").join("components");
+    fs::create_dir_all(&nested).unwrap();
+    assert_eq!(find_db_dir(&nested, None), None);
+
+    let db_path = TaskManager::init_db(&project).unwrap();
+    assert_eq!(db_path, project.join(".rusk").join("tasks.json"));
+    assert_eq!(find_db_dir(&nested, None), Some(project.join(".rusk")));
+    assert!(
+        TaskManager::new_with_path(db_path)
+            .unwrap()
+            .tasks
+            .is_empty()
+    );
+
+    // A database further down takes over for its own subtree.
+    TaskManager::init_db(&project.join("src")).unwrap();
+    assert_eq!(
+        find_db_dir(&nested, None),
+        Some(project.join("src").join(".rusk"))
+    );
+}
+
+#[test]
+fn test_search_stops_at_home() {
+    let temp_dir = tempdir().unwrap();
+    let home = temp_dir.path().join("home");
+    let work = home.join("work");
+    fs::create_dir_all(&work).unwrap();
+    // Above the home directory: never reached.
+    TaskManager::init_db(temp_dir.path()).unwrap();
+    assert_eq!(find_db_dir(&work, Some(&home)), None);
+
+    // The home directory itself is still checked.
+    TaskManager::init_db(&home).unwrap();
+    assert_eq!(find_db_dir(&work, Some(&home)), Some(home.join(".rusk")));
+}
+
+#[test]
+fn test_init_refuses_existing_database() {
+    let temp_dir = tempdir().unwrap();
+    let db_path = TaskManager::init_db(temp_dir.path()).unwrap();
+    fs::write(&db_path, r#"{"version": 2, "tasks": []}"#).unwrap();
+    let err = TaskManager::init_db(temp_dir.path()).unwrap_err();
+    assert!(err.to_string().contains("already exists"), "{err}");
+}
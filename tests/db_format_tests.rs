@@ -0,0 +1,99 @@
+use rusk::TaskManager;
+use rusk::db_format::{self, DB_FORMAT_VERSION};
+use tempfile::tempdir;
+
+fn read_json(path: &std::path::Path) -> serde_json::Value {
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[test]
+fn test_bare_array_is_upgraded_on_save() {
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("tasks.json");
+    std::fs::write(
+        &db_path,
+        r#"[{"id":1,"text":"Legacy","date":null,"done":false,"priority":true}]"#,
+    )
+    .unwrap();
+
+    let mut tm = TaskManager::new_with_path(db_path.clone()).unwrap();
+    assert_eq!(tm.tasks.len(), 1);
+    tm.add_task(vec!["New".to_string()], None).unwrap();
+
+    let doc = read_json(&db_path);
+    assert_eq!(doc["version"], DB_FORMAT_VERSION);
+    assert_eq!(doc["tasks"][0]["text"], "Legacy");
+    assert_eq!(doc["tasks"][0]["priority"], "high");
+    assert_eq!(doc["tasks"][1]["text"], "New");
+}
+
+#[test]
+fn test_unknown_fields_survive_a_save() {
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("tasks.json");
+    std::fs::write(
+        &db_path,
+        format!(
+            r#"{{"version":{DB_FORMAT_VERSION},"owner":"team-a",
+                "tasks":[{{"id":1,"text":"Keep","date":null,"done":false,"estimate":{{"hours":2}}}}]}}"#
+        ),
+    )
+    .unwrap();
+
+    let mut tm = TaskManager::new_with_path(db_path.clone()).unwrap();
+    tm.mark_tasks(vec![1]).unwrap();
+
+    let doc = read_json(&db_path);
+    assert_eq!(doc["owner"], "team-a");
+    assert_eq!(doc["tasks"][0]["done"], true);
+    assert_eq!(doc["tasks"][0]["estimate"]["hours"], 2);
+}
+
+#[test]
+fn test_newer_version_is_read_but_never_written() {
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("tasks.json");
+    let original = format!(
+        r#"{{"version":{},"tasks":[{{"id":1,"text":"Future","date":null,"done":false}}]}}"#,
+        DB_FORMAT_VERSION + 1
+    );
+    std::fs::write(&db_path, &original).unwrap();
+
+    let mut tm = TaskManager::new_with_path(db_path.clone()).unwrap();
+    assert_eq!(tm.tasks[0].text, "Future");
+
+    let err = tm.add_task(vec!["Other".to_string()], None).unwrap_err();
+    assert!(err.to_string().contains("upgrade rusk"), "{err}");
+    assert_eq!(std::fs::read_to_string(&db_path).unwrap(), original);
+}
+
+#[test]
+fn test_read_file_for_rewriting() {
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("tasks.json");
+    assert!(db_format::read_file(&path).unwrap().0.is_empty());
+    std::fs::write(&path, "\n").unwrap();
+    assert!(db_format::read_file(&path).unwrap().0.is_empty());
+
+    std::fs::write(
+        &path,
+        r#"[{"id":1,"text":"Legacy","date":null,"done":false}]"#,
+    )
+    .unwrap();
+    let (tasks, meta) = db_format::read_file(&path).unwrap();
+    assert_eq!(tasks[0].text, "Legacy");
+    assert_eq!(meta.version, DB_FORMAT_VERSION);
+
+    std::fs::write(
+        &path,
+        format!(r#"{{"version":{},"tasks":[]}}"#, DB_FORMAT_VERSION + 1),
+    )
+    .unwrap();
+    let err = db_format::read_file(&path).unwrap_err();
+    assert!(err.to_string().contains("upgrade rusk"), "{err}");
+
+    assert_eq!(
+        db_format::sibling_path(&path, ".lock"),
+        temp_dir.path().join("tasks.json.lock")
+    );
+}
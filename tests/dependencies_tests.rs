@@ -0,0 +1,75 @@
+use rusk::TaskManager;
+use tempfile::tempdir;
+
+mod common;
+
+#[test]
+fn test_block_and_blocked_state() {
+    let dir = tempdir().unwrap();
+    let mut tm = common::manager_with_tasks(dir.path(), &["Task 1", "Task 2", "Task 3"]);
+    assert!(tm.block_task(3, &[1, 2]).unwrap());
+    assert_eq!(tm.blocked_task_ids(), vec![3]);
+    assert_eq!(tm.open_blocker_ids(3), vec![1, 2]);
+
+    // Blocking on the same tasks again changes nothing
+    assert!(!tm.block_task(3, &[2]).unwrap());
+
+    tm.mark_tasks(vec![1]).unwrap();
+    assert_eq!(tm.open_blocker_ids(3), vec![2]);
+    tm.mark_tasks(vec![2]).unwrap();
+    assert!(tm.blocked_task_ids().is_empty());
+
+    // Reopening a blocker blocks the task again
+    tm.mark_tasks(vec![2]).unwrap();
+    assert_eq!(tm.blocked_task_ids(), vec![3]);
+}
+
+#[test]
+fn test_block_persists_by_uuid() {
+    let dir = tempdir().unwrap();
+    let mut tm = common::manager_with_tasks(dir.path(), &["Task 1", "Task 2"]);
+    tm.block_task(2, &[1]).unwrap();
+    let reloaded = TaskManager::load_tasks_from_path(tm.db_path()).unwrap();
+    assert_eq!(reloaded[1].blocked_by, vec![reloaded[0].uuid]);
+    assert!(reloaded[1].is_blocked_in(&reloaded));
+}
+
+#[test]
+fn test_block_rejects_self_missing_and_cycles() {
+    let dir = tempdir().unwrap();
+    let mut tm = common::manager_with_tasks(dir.path(), &["Task 1", "Task 2", "Task 3"]);
+    assert!(tm.block_task(1, &[1]).is_err());
+    assert!(tm.block_task(1, &[9]).is_err());
+
+    tm.block_task(2, &[1]).unwrap();
+    tm.block_task(3, &[2]).unwrap();
+    // 1 -> 3 would close 3 -> 2 -> 1
+    let err = tm.block_task(1, &[3]).unwrap_err();
+    assert!(err.to_string().contains("cycle"));
+    assert!(tm.tasks[0].blocked_by.is_empty());
+}
+
+#[test]
+fn test_unblock() {
+    let dir = tempdir().unwrap();
+    let mut tm = common::manager_with_tasks(dir.path(), &["Task 1", "Task 2", "Task 3"]);
+    tm.block_task(3, &[1, 2]).unwrap();
+
+    assert!(tm.unblock_task(3, Some(&[1])).unwrap());
+    assert_eq!(tm.open_blocker_ids(3), vec![2]);
+    assert!(!tm.unblock_task(3, Some(&[1])).unwrap());
+
+    assert!(tm.unblock_task(3, None).unwrap());
+    assert!(tm.tasks[2].blocked_by.is_empty());
+    assert!(!tm.unblock_task(3, None).unwrap());
+}
+
+#[test]
+fn test_deleting_blocker_releases_task() {
+    let dir = tempdir().unwrap();
+    let mut tm = common::manager_with_tasks(dir.path(), &["Task 1", "Task 2"]);
+    tm.block_task(2, &[1]).unwrap();
+    tm.delete_tasks(vec![1]).unwrap();
+    assert!(tm.tasks[0].blocked_by.is_empty());
+    assert!(tm.blocked_task_ids().is_empty());
+}
@@ -60,7 +60,6 @@ fn test_backup_files_in_same_directory() -> Result<()> {
     let temp_dir = TempDir::new()?;
     let rusk_dir = temp_dir.path().join("rusk");
     let db_path = rusk_dir.join("tasks.json");
-    let backup_path = rusk_dir.join("tasks.json.backup");
 
     // Create TaskManager with custom path
     let mut tm = TaskManager::new_empty_with_path(db_path.clone());
@@ -72,6 +71,9 @@ fn test_backup_files_in_same_directory() -> Result<()> {
     tm.save()?;
 
     // Verify backup was created in same directory
+    let backups = rusk::backup::list_backups(&db_path)?;
+    assert_eq!(backups.len(), 1);
+    let backup_path = &backups[0].path;
     assert!(backup_path.exists());
     assert!(backup_path.is_file());
 
@@ -121,8 +123,6 @@ fn test_restore_files_in_custom_directory() -> Result<()> {
     let temp_dir = TempDir::new()?;
     let custom_dir = temp_dir.path().join("custom_rusk_dir");
     let db_path = custom_dir.join("custom.json");
-    let backup_path = custom_dir.join("custom.json.backup");
-    let before_restore_path = custom_dir.join("custom.json.before_restore");
 
     // Create TaskManager with custom directory
     let mut tm = TaskManager::new_empty_with_path(db_path.clone());
@@ -136,11 +136,16 @@ fn test_restore_files_in_custom_directory() -> Result<()> {
     // Restore from backup
     tm.restore_from_backup()?;
 
-    // Verify all restore-related files are in custom directory
-    assert!(backup_path.exists());
-    assert!(before_restore_path.exists());
-    assert_eq!(backup_path.parent(), Some(custom_dir.as_path()));
-    assert_eq!(before_restore_path.parent(), Some(custom_dir.as_path()));
+    // Verify all restore-related files are in custom directory: the backup that was restored
+    // and the one taken of the modified state before restoring
+    let backups = rusk::backup::list_backups(&db_path)?;
+    assert_eq!(backups.len(), 2);
+    for backup in &backups {
+        assert!(backup.path.exists());
+        assert_eq!(backup.path.parent(), Some(custom_dir.as_path()));
+    }
+    let newest = TaskManager::load_tasks_from_path(&backups[0].path)?;
+    assert_eq!(newest[0].text, "Modified task");
 
     // Verify restoration worked
     assert_eq!(tm.tasks[0].text, "Original task");
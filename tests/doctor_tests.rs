@@ -0,0 +1,256 @@
+use std::fs;
+
+use rusk::TaskManager;
+use rusk::doctor::{Issue, check, quarantine_path, repair, salvage};
+use rusk::journal::read_journal;
+use tempfile::tempdir;
+
+const BROKEN_DB: &str = r#"{"version": 2, "tasks": [
+    {"id": 1, "text": "first", "date": null, "done": false},
+    {"id": 1, "text": "second", "date": null, "done": false},
+    {"id": 0, "text": "zero", "date": null, "done": false},
+    {"id": 4, "text": " ", "date": null, "done": false},
+    {"id": 5, "text": "bad", "date": "2025-02-30", "done": false}
+]}"#;
+
+#[test]
+fn test_check_reports_each_problem() {
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("tasks.json");
+    fs::write(&db_path, BROKEN_DB).unwrap();
+    fs::write(temp_dir.path().join(".tasks.json.1.abc.tmp"), "{").unwrap();
+
+    let diagnosis = check(&db_path).unwrap();
+    let issues = &diagnosis.issues;
+    assert!(issues.contains(&Issue::DuplicateId { id: 1, count: 2 }));
+    assert!(issues.contains(&Issue::ZeroId { entry: 3 }));
+    assert!(issues.contains(&Issue::EmptyText { entry: 4, id: 4 }));
+    assert!(issues.iter().any(|i| matches!(
+        i,
+        Issue::InvalidEntry { entry: 5, reason, .. } if reason.contains("2025-02-30")
+    )));
+    assert!(
+        issues
+            .iter()
+            .any(|i| matches!(i, Issue::StaleTempFile { .. }))
+    );
+    assert_eq!(diagnosis.fixable_count(), issues.len());
+}
+
+#[test]
+fn test_repair_renumbers_quarantines_and_cleans_up() {
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("tasks.json");
+    fs::write(&db_path, BROKEN_DB).unwrap();
+    let temp_file = temp_dir.path().join(".tasks.json.1.abc.tmp");
+    fs::write(&temp_file, "{").unwrap();
+    let draft = temp_dir.path().join("editor.draft");
+    fs::write(&draft, r#"{"key": "new-task", "text": "half typed"}"#).unwrap();
+
+    let summary = repair(&check(&db_path).unwrap()).unwrap();
+    assert_eq!(summary.renumbered, vec![(1, 2), (0, 3)]);
+    assert_eq!(summary.quarantined, 3);
+    assert!(!temp_file.exists());
+    assert!(!draft.exists());
+
+    let quarantined = fs::read_to_string(quarantine_path(&db_path)).unwrap();
+    assert_eq!(quarantined.lines().count(), 3);
+    assert!(quarantined.contains("2025-02-30"));
+    assert!(quarantined.contains("half typed"));
+
+    let tm = TaskManager::new_with_path(db_path.clone()).unwrap();
+    let ids: Vec<_> = tm.tasks.iter().map(|t| (t.id, t.text.as_str())).collect();
+    assert_eq!(ids, vec![(1, "first"), (2, "second"), (3, "zero")]);
+    assert!(check(&db_path).unwrap().issues.is_empty());
+
+    let journal = read_journal(&db_path).unwrap();
+    assert_eq!(journal.len(), 1);
+    assert_eq!(journal[0].counts(), (0, 1, 2));
+}
+
+#[test]
+fn test_unsalvageable_file_is_not_touched() {
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("tasks.json");
+    fs::write(&db_path, "not json").unwrap();
+    let legacy_temp = temp_dir.path().join("tasks.json.tmp");
+    fs::write(&legacy_temp, "[]").unwrap();
+
+    let diagnosis = check(&db_path).unwrap();
+    assert!(matches!(
+        diagnosis.issues[0],
+        Issue::Corrupt { recovered: 0, .. }
+    ));
+    assert!(!diagnosis.issues[0].is_fixable());
+    assert!(matches!(
+        &diagnosis.issues[1],
+        Issue::StaleTempFile { path, removable: false } if *path == legacy_temp
+    ));
+    assert_eq!(diagnosis.fixable_count(), 0);
+
+    repair(&diagnosis).unwrap();
+    assert_eq!(fs::read_to_string(&db_path).unwrap(), "not json");
+    // The temporary file may be the only good copy left.
+    assert!(legacy_temp.exists());
+}
+
+const DAMAGED_DB: &str = r#"{
+  "version": 2,
+  "tasks": [
+    {
+      "id": 1,
+      "text": "kept, {braces} in text",
+      "date": null,
+      "done": false,
+      "recurrence": {"every": "1w"}
+    },
+    {
+      "id": 2,
+      "text": "stray "quote",
+      "date": null,
+      "done": false
+    },
+    {
+      "id": 3,
+      "text": "also kept",
+      "date": null,
+      "done": true
+    },
+    {
+      "id": 4,
+      "text": "truncat"#;
+
+#[test]
+fn test_salvage_keeps_intact_objects() {
+    let salvaged = salvage(DAMAGED_DB);
+    let texts: Vec<_> = salvaged.tasks.iter().map(|t| t.text.as_str()).collect();
+    assert_eq!(texts, vec!["kept, {braces} in text", "also kept"]);
+    assert_eq!(salvaged.fragments.len(), 2);
+    assert_eq!(salvaged.fragments[0].line, 11);
+    assert!(salvaged.fragments[0].text.contains("stray"));
+    assert_eq!(salvaged.fragments[1].line, 23);
+    assert!(salvaged.fragments[1].text.ends_with("truncat"));
+}
+
+#[test]
+fn test_salvage_upgrades_legacy_entries() {
+    let salvaged = salvage(
+        r#"[{"id": 1, "text": "old", "date": null, "done": false, "priority": true},
+            {"id": 2, "text": "cut"#,
+    );
+    assert_eq!(salvaged.tasks.len(), 1);
+    assert_eq!(salvaged.tasks[0].priority, rusk::Priority::High);
+    assert_eq!(salvaged.fragments.len(), 1);
+}
+
+#[test]
+fn test_repair_writes_salvaged_tasks_and_keeps_the_damaged_file() {
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("tasks.json");
+    fs::write(&db_path, DAMAGED_DB).unwrap();
+
+    let diagnosis = check(&db_path).unwrap();
+    assert!(matches!(
+        diagnosis.issues[0],
+        Issue::Corrupt {
+            recovered: 2,
+            fragments: 2,
+            ..
+        }
+    ));
+    // Checking alone writes nothing.
+    assert_eq!(fs::read_to_string(&db_path).unwrap(), DAMAGED_DB);
+
+    let summary = repair(&diagnosis).unwrap();
+    assert_eq!(summary.salvaged, Some(2));
+    assert_eq!(summary.quarantined, 2);
+    let tm = TaskManager::new_with_path(db_path.clone()).unwrap();
+    assert_eq!(tm.tasks.len(), 2);
+    assert_eq!(
+        fs::read_to_string(quarantine_path(&db_path))
+            .unwrap()
+            .lines()
+            .count(),
+        2
+    );
+    let backups = rusk::backup::list_backups(&db_path).unwrap();
+    assert_eq!(fs::read_to_string(&backups[0].path).unwrap(), DAMAGED_DB);
+}
+
+#[test]
+fn test_salvage_keeps_envelope_fields() {
+    let damaged = r#"{
+  "version": 2,
+  "tasks": [
+    {"id": 3, "text": "kept", "date": null "done": false},
+    {"id": 4, "text": "also kept", "date": null, "done": false}
+  ],
+  "deleted": [{"uuid": "3fa85f64-5717-4562-b3fc-2c963f66afa6", "deleted_at": "2026-01-01T00:00:00Z"}],
+  "last_id": 9,
+  "sync_peer": {"name": "laptop"},
+  "broken": [
+}"#;
+    let salvaged = salvage(damaged);
+    assert_eq!(salvaged.tasks.len(), 1);
+    assert_eq!(salvaged.fragments.len(), 1);
+    assert_eq!(salvaged.meta.deleted.len(), 1);
+    assert_eq!(salvaged.meta.last_id, 9);
+    assert_eq!(salvaged.meta.extra["sync_peer"]["name"], "laptop");
+    assert!(!salvaged.meta.extra.contains_key("broken"));
+
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("tasks.json");
+    fs::write(&db_path, damaged).unwrap();
+    repair(&check(&db_path).unwrap()).unwrap();
+    let data = fs::read_to_string(&db_path).unwrap();
+    assert!(
+        data.contains("\"last_id\": 9") && data.contains("laptop"),
+        "{data}"
+    );
+    let tm = TaskManager::new_with_path(db_path).unwrap();
+    assert_eq!(tm.tombstones().len(), 1);
+}
+
+#[test]
+fn test_damaged_newer_database_is_not_salvaged() {
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("tasks.json");
+    let damaged = r#"{"version": 99, "tasks": [
+    {"id": 1, "text": "kept", "date": null, "done": false},
+    {"id": 2, "text": "cut"#;
+    fs::write(&db_path, damaged).unwrap();
+
+    let diagnosis = check(&db_path).unwrap();
+    assert!(matches!(&diagnosis.issues[0], Issue::Unreadable(reason) if reason.contains("99")));
+    assert_eq!(diagnosis.fixable_count(), 0);
+    repair(&diagnosis).unwrap();
+    assert_eq!(fs::read_to_string(&db_path).unwrap(), damaged);
+}
+
+#[test]
+fn test_dependency_cycle_is_reported_and_broken() {
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("tasks.json");
+    let (a, b) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+    fs::write(
+        &db_path,
+        format!(
+            r#"{{"version": 2, "tasks": [
+    {{"id": 1, "uuid": "{a}", "text": "a", "date": null, "done": false, "blocked_by": ["{b}"]}},
+    {{"id": 2, "uuid": "{b}", "text": "b", "date": null, "done": false, "blocked_by": ["{a}"]}}
+]}}"#
+        ),
+    )
+    .unwrap();
+
+    let diagnosis = check(&db_path).unwrap();
+    assert_eq!(
+        diagnosis.issues,
+        vec![Issue::DependencyCycle { id: 2, blocker: 1 }]
+    );
+    let summary = repair(&diagnosis).unwrap();
+    assert_eq!(summary.unblocked, vec![(2, 1)]);
+    let tm = TaskManager::new_with_path(db_path.clone()).unwrap();
+    assert_eq!(tm.blocked_task_ids(), vec![1]);
+    assert!(check(&db_path).unwrap().issues.is_empty());
+}
@@ -0,0 +1,131 @@
+use chrono::NaiveTime;
+use rusk::{NewTaskOptions, Recurrence, RecurrenceMode, Task, TaskManager};
+use tempfile::tempdir;
+
+mod common;
+use common::ymd;
+
+fn t(h: u32, m: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(h, m, 0).unwrap()
+}
+
+#[test]
+fn test_add_task_parses_due_time() {
+    let temp_dir = tempdir().unwrap();
+    let mut tm = TaskManager::new_empty_with_path(temp_dir.path().join("tasks.json"));
+    tm.add_task(
+        vec!["Standup prep".to_string()],
+        Some("31-12-2026@09:30".to_string()),
+    )
+    .unwrap();
+    tm.add_task(
+        vec!["Review".to_string()],
+        Some("31-12-2026 18:00".to_string()),
+    )
+    .unwrap();
+    tm.add_task(vec!["Someday".to_string()], Some("31-12-2026".to_string()))
+        .unwrap();
+
+    assert_eq!(tm.tasks[0].date, Some(ymd(2026, 12, 31)));
+    assert_eq!(tm.tasks[0].time, Some(t(9, 30)));
+    assert_eq!(tm.tasks[1].time, Some(t(18, 0)));
+    assert_eq!(tm.tasks[2].time, None);
+    assert!(
+        tm.add_task(vec!["Bad".to_string()], Some("2d@24:00".to_string()))
+            .is_err()
+    );
+}
+
+#[test]
+fn test_edit_date_keeps_or_clears_time() {
+    let temp_dir = tempdir().unwrap();
+    let mut tm = TaskManager::new_empty_with_path(temp_dir.path().join("tasks.json"));
+    tm.add_task(vec!["Call".to_string()], Some("1-3-2026@14:00".to_string()))
+        .unwrap();
+
+    // `+` offsets move the day and keep the time
+    tm.edit_tasks(vec![1], None, Some("+1d".to_string()))
+        .unwrap();
+    assert_eq!(tm.tasks[0].date, Some(ymd(2026, 3, 2)));
+    assert_eq!(tm.tasks[0].time, Some(t(14, 0)));
+
+    // `@HH:MM` alone changes only the time
+    tm.edit_tasks(vec![1], None, Some("@8:15".to_string()))
+        .unwrap();
+    assert_eq!(tm.tasks[0].date, Some(ymd(2026, 3, 2)));
+    assert_eq!(tm.tasks[0].time, Some(t(8, 15)));
+
+    // An absolute date without a time drops it
+    tm.edit_tasks(vec![1], None, Some("5-3-2026".to_string()))
+        .unwrap();
+    assert_eq!(tm.tasks[0].time, None);
+
+    tm.edit_tasks(vec![1], None, Some("5-3-2026 7:00".to_string()))
+        .unwrap();
+    tm.edit_tasks(vec![1], None, Some("_".to_string())).unwrap();
+    assert_eq!(tm.tasks[0].date, None);
+    assert_eq!(tm.tasks[0].time, None);
+}
+
+#[test]
+fn test_overdue_uses_time_of_day() {
+    let now = ymd(2026, 10, 16).and_time(t(12, 0));
+    let task = |date, time| Task {
+        date: Some(date),
+        time,
+        ..Default::default()
+    };
+
+    assert!(task(ymd(2026, 10, 16), Some(t(11, 59))).is_overdue(now));
+    assert!(!task(ymd(2026, 10, 16), Some(t(12, 30))).is_overdue(now));
+    // Without a time the task stays due until the day is over
+    assert!(!task(ymd(2026, 10, 16), None).is_overdue(now));
+    assert!(task(ymd(2026, 10, 15), None).is_overdue(now));
+
+    let mut done = task(ymd(2026, 10, 16), Some(t(9, 0)));
+    done.done = true;
+    assert!(!done.is_overdue(now));
+}
+
+#[test]
+fn test_due_datetime_orders_times_within_a_day() {
+    let task = |date, time| Task {
+        date: Some(date),
+        time,
+        ..Default::default()
+    };
+    let mut due = [
+        task(ymd(2026, 10, 16), None),
+        task(ymd(2026, 10, 16), Some(t(14, 0))),
+        task(ymd(2026, 10, 15), None),
+        task(ymd(2026, 10, 16), Some(t(9, 0))),
+    ];
+    due.sort_by_key(|t| t.due_datetime());
+    let order: Vec<_> = due.iter().map(|t| (t.date.unwrap(), t.time)).collect();
+    assert_eq!(
+        order,
+        vec![
+            (ymd(2026, 10, 15), None),
+            (ymd(2026, 10, 16), Some(t(9, 0))),
+            (ymd(2026, 10, 16), Some(t(14, 0))),
+            (ymd(2026, 10, 16), None),
+        ]
+    );
+}
+
+#[test]
+fn test_next_occurrence_keeps_due_time() {
+    let temp_dir = tempdir().unwrap();
+    let mut tm = TaskManager::new_empty_with_path(temp_dir.path().join("tasks.json"));
+    let options = NewTaskOptions {
+        recurrence: Some(Recurrence::new("1w", RecurrenceMode::FromDue).unwrap()),
+        time: Some(t(9, 30)),
+        ..Default::default()
+    };
+    tm.add_task_with_options("Standup".to_string(), Some(ymd(2026, 11, 2)), &options)
+        .unwrap();
+
+    tm.mark_tasks(vec![1]).unwrap();
+    assert_eq!(tm.tasks[1].date, Some(ymd(2026, 11, 9)));
+    assert_eq!(tm.tasks[1].time, Some(t(9, 30)));
+}
@@ -1,5 +1,7 @@
 use chrono::NaiveDate;
-use rusk::{TaskManager, parse_cli_date_with_base};
+use rusk::{TaskId, TaskManager, parse_cli_date_with_base};
+mod common;
+use common::create_test_task;
 
 #[test]
 fn test_edge_case_empty_inputs() {
@@ -184,11 +186,11 @@ fn test_edge_case_id_generation_under_load() {
     }
 
     // Verify all IDs are unique and sequential
-    let mut ids: Vec<u8> = tm.tasks.iter().map(|t| t.id).collect();
+    let mut ids: Vec<TaskId> = tm.tasks.iter().map(|t| t.id).collect();
     ids.sort();
 
     for (i, &id) in ids.iter().enumerate() {
-        assert_eq!(id, (i + 1) as u8);
+        assert_eq!(id, (i + 1) as TaskId);
     }
 
     // Delete some tasks and add new ones
@@ -305,10 +307,29 @@ fn test_edge_case_date_dot_separator() {
     );
 }
 
-// Note: test_edge_case_id_boundaries removed because generate_next_id
-// has a bug where it panics on overflow when id reaches 255.
-// The existing test_generate_next_id_max_reached in lib_tests.rs covers
-// the normal case up to 200 tasks, which is sufficient for testing.
+#[test]
+fn test_edge_case_id_boundaries() {
+    let mut tm = TaskManager::new_empty().unwrap();
+
+    // More than 255 tasks: IDs keep counting past the old u8 limit
+    for i in 1..=300 {
+        tm.tasks
+            .push(create_test_task(i, &format!("Task {i}"), false));
+    }
+    assert_eq!(tm.generate_next_id().unwrap(), 301);
+
+    // The largest ID does not overflow; the lowest free slot is still used
+    tm.tasks = vec![create_test_task(TaskId::MAX, "Last", false)];
+    assert_eq!(tm.generate_next_id().unwrap(), 1);
+
+    // Duplicate IDs in a hand-edited file do not stop the search for a free slot
+    tm.tasks = vec![
+        create_test_task(1, "A", false),
+        create_test_task(1, "B", false),
+        create_test_task(2, "C", false),
+    ];
+    assert_eq!(tm.generate_next_id().unwrap(), 3);
+}
 
 #[test]
 fn test_edge_case_delete_all_tasks() {
@@ -351,9 +372,9 @@ fn test_edge_case_edit_with_same_values() {
         )
         .unwrap();
 
-    assert_eq!(edited, vec![] as Vec<u8>);
+    assert_eq!(edited, vec![] as Vec<TaskId>);
     assert_eq!(unchanged, vec![1]);
-    assert_eq!(not_found, vec![] as Vec<u8>);
+    assert_eq!(not_found, vec![] as Vec<TaskId>);
 
     // Verify task unchanged
     assert_eq!(tm.tasks[0].text, "Original text");
@@ -411,7 +432,7 @@ fn test_edge_case_mark_empty_task_list() {
 
     // Try to mark tasks when list is empty
     let (marked, not_found) = tm.mark_tasks(vec![1, 2, 3]).unwrap();
-    assert_eq!(marked, vec![] as Vec<(u8, bool)>);
+    assert_eq!(marked, vec![] as Vec<(TaskId, bool)>);
     assert_eq!(not_found, vec![1, 2, 3]);
 }
 
@@ -428,7 +449,7 @@ fn test_edge_case_edit_empty_task_list() {
         )
         .unwrap();
 
-    assert_eq!(edited, vec![] as Vec<u8>);
-    assert_eq!(unchanged, vec![] as Vec<u8>);
+    assert_eq!(edited, vec![] as Vec<TaskId>);
+    assert_eq!(unchanged, vec![] as Vec<TaskId>);
     assert_eq!(not_found, vec![1, 2]);
 }
@@ -43,6 +43,15 @@ fn read_db() -> String {
     }
 }
 
+/// Tasks of the database file: the `tasks` array of the versioned envelope, or the bare array
+/// a test wrote when rusk did not save.
+fn read_db_tasks() -> Vec<serde_json::Value> {
+    match serde_json::from_str(&read_db()).unwrap() {
+        serde_json::Value::Array(tasks) => tasks,
+        doc => doc["tasks"].as_array().cloned().unwrap_or_default(),
+    }
+}
+
 #[test]
 fn test_binary_del_help() {
     let out = rusk_command().args(["del", "--help"]).output().unwrap();
@@ -82,7 +91,7 @@ fn test_binary_mark_help_after_id_leaves_db_unchanged() {
         "expected mark subcommand help on stdout: {stdout}"
     );
 
-    let db_after: Vec<serde_json::Value> = serde_json::from_str(&read_db()).unwrap();
+    let db_after = read_db_tasks();
     let t1 = db_after.iter().find(|t| t["id"] == 1).unwrap();
     assert!(
         !t1["done"].as_bool().unwrap(),
@@ -262,7 +271,7 @@ fn test_binary_edit_plus_relative_from_existing_date() {
         String::from_utf8_lossy(&out.stderr)
     );
 
-    let db_after: Vec<serde_json::Value> = serde_json::from_str(&read_db()).unwrap();
+    let db_after = read_db_tasks();
     assert_eq!(db_after[0]["date"], "2025-06-08");
 }
 
@@ -298,12 +307,37 @@ fn test_binary_edit_with_date_flag_sets_date_and_text() {
         .unwrap();
     assert!(out.status.success(), "edit with -d and text should succeed");
 
-    let db_after: Vec<serde_json::Value> = serde_json::from_str(&read_db()).unwrap();
+    let db_after = read_db_tasks();
     let t = &db_after[0];
     assert_eq!(t["text"], "Updated text");
     assert_eq!(t["date"], "2025-06-15");
 }
 
+#[test]
+fn test_binary_edit_id_list_keeps_hex_looking_text() {
+    let _guard = BIN_TEST_MUTEX.lock().unwrap();
+
+    let db = r#"[
+        {"id":1,"text":"One","date":null,"done":false,"priority":false},
+        {"id":2,"text":"Two","date":null,"done":false,"priority":false}
+    ]"#;
+    setup_test_db(db);
+
+    let out = rusk_command()
+        .args(["edit", "1,2", "added", "tests"])
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "text after an ID list is not a UUID prefix: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    let db_after = read_db_tasks();
+    assert_eq!(db_after[0]["text"], "added tests");
+    assert_eq!(db_after[1]["text"], "added tests");
+}
+
 #[test]
 fn test_binary_mark_error_when_only_flags() {
     let _guard = BIN_TEST_MUTEX.lock().unwrap();
@@ -368,11 +402,11 @@ fn test_binary_mark_priority_toggles_and_preserves_across_done() {
 
     setup_test_db(r#"[{"id":1,"text":"Task","date":null,"done":false,"priority":false}]"#);
 
-    // `rusk m 1 -p` → priority=true, done=false.
+    // `rusk m 1 -p` → priority=high, done=false.
     let out = rusk_command().args(["mark", "1", "-p"]).output().unwrap();
     assert!(out.status.success(), "mark -p should succeed: {out:?}");
-    let db: Vec<serde_json::Value> = serde_json::from_str(&read_db()).unwrap();
-    assert_eq!(db[0]["priority"], true);
+    let db = read_db_tasks();
+    assert_eq!(db[0]["priority"], "high");
     assert_eq!(db[0]["done"], false);
     let stdout = String::from_utf8_lossy(&out.stdout);
     assert!(
@@ -382,21 +416,37 @@ fn test_binary_mark_priority_toggles_and_preserves_across_done() {
 
     // `rusk m 1` → done=true, priority preserved.
     rusk_command().args(["mark", "1"]).output().unwrap();
-    let db: Vec<serde_json::Value> = serde_json::from_str(&read_db()).unwrap();
+    let db = read_db_tasks();
     assert_eq!(db[0]["done"], true);
-    assert_eq!(db[0]["priority"], true);
+    assert_eq!(db[0]["priority"], "high");
 
-    // `rusk m 1` → reverts to priority (done=false, priority still true).
+    // `rusk m 1` → reverts to priority (done=false, priority still high).
     rusk_command().args(["mark", "1"]).output().unwrap();
-    let db: Vec<serde_json::Value> = serde_json::from_str(&read_db()).unwrap();
+    let db = read_db_tasks();
     assert_eq!(db[0]["done"], false);
-    assert_eq!(db[0]["priority"], true);
+    assert_eq!(db[0]["priority"], "high");
 
     // `rusk m 1 -p` again → priority cleared.
     rusk_command().args(["mark", "1", "-p"]).output().unwrap();
-    let db: Vec<serde_json::Value> = serde_json::from_str(&read_db()).unwrap();
+    let db = read_db_tasks();
     assert_eq!(db[0]["done"], false);
-    assert_eq!(db[0]["priority"], false);
+    assert_eq!(db[0]["priority"], "none");
+
+    // Explicit levels, also with the flag before the IDs.
+    rusk_command()
+        .args(["mark", "1", "-p", "low"])
+        .output()
+        .unwrap();
+    let db = read_db_tasks();
+    assert_eq!(db[0]["priority"], "low");
+    rusk_command().args(["mark", "-p", "1"]).output().unwrap();
+    let db = read_db_tasks();
+    assert_eq!(db[0]["priority"], "none");
+    let out = rusk_command()
+        .args(["mark", "1", "-p", "urgent"])
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
 }
 
 #[test]
@@ -418,3 +468,331 @@ fn test_binary_rusk_no_color_empty_does_not_disable() {
         "empty RUSK_NO_COLOR should not disable colors:\n{stderr:?}"
     );
 }
+
+#[test]
+fn test_binary_list_tag_filter_and_tags_command() {
+    let _guard = BIN_TEST_MUTEX.lock().unwrap();
+    setup_test_db(
+        r#"[
+        {"id":1,"text":"Fix API #backend","date":null,"done":false,"priority":false},
+        {"id":2,"text":"Polish UI #frontend","date":null,"done":false,"priority":false},
+        {"id":3,"text":"Rotate keys #backend #ops","date":null,"done":false,"priority":false}
+    ]"#,
+    );
+
+    let out = rusk_command()
+        .env("RUSK_NO_COLOR", "1")
+        .args(["list", "--tag", "backend", "--tag", "-ops"])
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "list --tag should succeed: {out:?}");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Fix API"), "{stdout}");
+    assert!(!stdout.contains("Polish UI"), "{stdout}");
+    assert!(!stdout.contains("Rotate keys"), "{stdout}");
+
+    let out = rusk_command()
+        .env("RUSK_NO_COLOR", "1")
+        .args(["list", "--tag", "missing"])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&out.stdout).contains("No matching tasks"));
+
+    let out = rusk_command()
+        .env("RUSK_NO_COLOR", "1")
+        .arg("tags")
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let lines: Vec<&str> = stdout.lines().filter(|l| !l.trim().is_empty()).collect();
+    assert!(
+        lines[0].contains("#backend") && lines[0].trim_end().ends_with('2'),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("#frontend") && stdout.contains("#ops"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn test_binary_list_draws_subtask_tree() {
+    let _guard = BIN_TEST_MUTEX.lock().unwrap();
+    setup_test_db(
+        r#"[
+        {"id":1,"uuid":"00000000-0000-4000-8000-000000000001","text":"Release","date":null,"done":false,"priority":false},
+        {"id":2,"uuid":"00000000-0000-4000-8000-000000000002","text":"Changelog","date":null,"done":true,"priority":false,"parent":"00000000-0000-4000-8000-000000000001"},
+        {"id":3,"uuid":"00000000-0000-4000-8000-000000000003","text":"Tag","date":null,"done":false,"priority":false,"parent":"00000000-0000-4000-8000-000000000001"}
+    ]"#,
+    );
+
+    let out = rusk_command()
+        .env("RUSK_NO_COLOR", "1")
+        .arg("list")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("├─ Changelog"), "{stdout}");
+    assert!(stdout.contains("└─ Tag"), "{stdout}");
+
+    let out = rusk_command()
+        .env("RUSK_NO_COLOR", "1")
+        .args(["list", "-c"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Release [1/2]"), "{stdout}");
+    assert!(!stdout.contains("Changelog"), "{stdout}");
+}
+
+#[test]
+fn test_binary_del_parent_needs_cascade_or_reparent() {
+    let _guard = BIN_TEST_MUTEX.lock().unwrap();
+    setup_test_db(
+        r#"[
+        {"id":1,"uuid":"00000000-0000-4000-8000-000000000001","text":"Release","date":null,"done":false,"priority":false},
+        {"id":2,"uuid":"00000000-0000-4000-8000-000000000002","text":"Changelog","date":null,"done":false,"priority":false,"parent":"00000000-0000-4000-8000-000000000001"}
+    ]"#,
+    );
+
+    let out = rusk_command().args(["del", "1"]).output().unwrap();
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("--cascade"), "{stderr}");
+    assert!(read_db().contains("Release"));
+
+    let out = rusk_command()
+        .args(["del", "1", "--cascade", "--reparent"])
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+}
+
+#[test]
+fn test_binary_block_hide_blocked_and_unblock_report() {
+    let _guard = BIN_TEST_MUTEX.lock().unwrap();
+    setup_test_db(
+        r#"[
+        {"id":1,"text":"Design schema","date":null,"done":false,"priority":false},
+        {"id":2,"text":"Write migration","date":null,"done":false,"priority":false}
+    ]"#,
+    );
+
+    let out = rusk_command()
+        .args(["block", "2", "--on", "1"])
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "{out:?}");
+
+    let out = rusk_command()
+        .env("RUSK_NO_COLOR", "1")
+        .arg("list")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("⊘  2"), "{stdout}");
+
+    let out = rusk_command()
+        .env("RUSK_NO_COLOR", "1")
+        .args(["list", "--hide-blocked"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(!stdout.contains("Write migration"), "{stdout}");
+
+    let out = rusk_command()
+        .args(["block", "1", "--on", "2"])
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("cycle"));
+
+    let out = rusk_command()
+        .env("RUSK_NO_COLOR", "1")
+        .args(["mark", "1"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Unblocked task: 2"), "{stdout}");
+}
+
+#[test]
+fn test_binary_list_sort_due_orders_times_within_day() {
+    let _guard = BIN_TEST_MUTEX.lock().unwrap();
+    setup_test_db(
+        r#"[
+        {"id":1,"text":"Undated","date":null,"done":false,"priority":false},
+        {"id":2,"text":"Afternoon","date":"2030-01-15","time":"14:00:00","done":false,"priority":false},
+        {"id":3,"text":"All day","date":"2030-01-15","done":false,"priority":false},
+        {"id":4,"text":"Morning","date":"2030-01-15","time":"09:30:00","done":false,"priority":false}
+    ]"#,
+    );
+
+    let out = rusk_command()
+        .env("RUSK_NO_COLOR", "1")
+        .args(["list", "--sort", "due"])
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "list --sort due should succeed: {out:?}"
+    );
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let pos = |s: &str| {
+        stdout
+            .find(s)
+            .unwrap_or_else(|| panic!("{s} missing: {stdout}"))
+    };
+    assert!(stdout.contains("15-jan-30 09:30"), "{stdout}");
+    assert!(pos("Morning") < pos("Afternoon"), "{stdout}");
+    assert!(pos("Afternoon") < pos("All day"), "{stdout}");
+    assert!(pos("All day") < pos("Undated"), "{stdout}");
+}
+
+#[test]
+fn test_binary_list_hides_waiting_tasks() {
+    let _guard = BIN_TEST_MUTEX.lock().unwrap();
+    setup_test_db(
+        r#"[
+        {"id":1,"text":"Ready now","date":null,"done":false,"priority":false},
+        {"id":2,"text":"Next year","date":null,"wait":"2099-01-01","done":false,"priority":false}
+    ]"#,
+    );
+
+    let list = |args: &[&str]| {
+        let out = rusk_command()
+            .env("RUSK_NO_COLOR", "1")
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(out.status.success(), "{args:?} should succeed: {out:?}");
+        String::from_utf8_lossy(&out.stdout).into_owned()
+    };
+
+    let stdout = list(&["list"]);
+    assert!(stdout.contains("Ready now"), "{stdout}");
+    assert!(!stdout.contains("Next year"), "{stdout}");
+    assert!(stdout.contains("1 waiting task(s) hidden"), "{stdout}");
+
+    let stdout = list(&["list", "--waiting"]);
+    assert!(!stdout.contains("Ready now"), "{stdout}");
+    assert!(stdout.contains("Next year"), "{stdout}");
+
+    let stdout = list(&["list", "--all"]);
+    assert!(
+        stdout.contains("Ready now") && stdout.contains("Next year"),
+        "{stdout}"
+    );
+
+    list(&["wait", "2", "_"]);
+    let stdout = list(&["list"]);
+    assert!(stdout.contains("Next year"), "{stdout}");
+}
+
+#[test]
+fn test_binary_waits_for_lock_and_names_holder() {
+    let _guard = BIN_TEST_MUTEX.lock().unwrap();
+    let db = r#"[{"id":1,"text":"Task 1","date":null,"done":false}]"#;
+    setup_test_db(db);
+
+    // A lock held by a running process (this test) with a recognisable command line.
+    let lock_path = rusk::lock::lock_path(&debug_db_path());
+    let holder = rusk::lock::LockHolder {
+        pid: std::process::id(),
+        command: "rusk edit 7".to_string(),
+        since: "2026-01-01T09:15:00+00:00".to_string(),
+    };
+    fs::write(&lock_path, serde_json::to_string(&holder).unwrap()).unwrap();
+
+    let out = rusk_command()
+        .env("RUSK_NO_COLOR", "1")
+        .env("RUSK_LOCK_TIMEOUT", "0.3")
+        .args(["add", "Blocked", "write"])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(!out.status.success(), "add must fail while locked: {out:?}");
+    assert!(stderr.contains("rusk edit 7"), "{stderr}");
+    assert!(
+        stderr.contains(&format!("pid {}", std::process::id())),
+        "{stderr}"
+    );
+    assert_eq!(read_db(), db);
+
+    // Reading does not need the lock.
+    let out = rusk_command().args(["list"]).output().unwrap();
+    assert!(out.status.success(), "list should not wait: {out:?}");
+
+    fs::remove_file(&lock_path).unwrap();
+    let out = rusk_command()
+        .args(["add", "Now", "free"])
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "{out:?}");
+    assert!(!lock_path.exists(), "lock must be released on exit");
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_binary_takes_over_stale_lock() {
+    let _guard = BIN_TEST_MUTEX.lock().unwrap();
+    setup_test_db(r#"[{"id":1,"text":"Task 1","date":null,"done":false}]"#);
+
+    let lock_path = rusk::lock::lock_path(&debug_db_path());
+    let holder = rusk::lock::LockHolder {
+        pid: u32::MAX - 1,
+        command: "rusk add crashed".to_string(),
+        since: "2026-01-01T09:15:00+00:00".to_string(),
+    };
+    fs::write(&lock_path, serde_json::to_string(&holder).unwrap()).unwrap();
+
+    let out = rusk_command()
+        .env("RUSK_LOCK_TIMEOUT", "0")
+        .args(["mark", "1"])
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "{out:?}");
+    assert_eq!(read_db_tasks()[0]["done"], true);
+    assert!(!lock_path.exists());
+}
+
+#[test]
+fn test_binary_restore_list_and_numbered_restore() {
+    let _guard = BIN_TEST_MUTEX.lock().unwrap();
+    let db_path = debug_db_path();
+    for backup in rusk::backup::list_backups(&db_path).unwrap() {
+        fs::remove_file(backup.path).unwrap();
+    }
+    setup_test_db(r#"[{"id":1,"text":"Keep me","date":null,"done":false}]"#);
+
+    let run = |args: &[&str]| {
+        let out = rusk_command()
+            .env("RUSK_NO_COLOR", "1")
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(out.status.success(), "{args:?} should succeed: {out:?}");
+        String::from_utf8_lossy(&out.stdout).into_owned()
+    };
+    run(&["add", "Second"]);
+    run(&["mark", "1"]);
+
+    // Backups: before `mark`, before `add`, and the legacy file before UUIDs were backfilled
+    let listing = run(&["restore", "--list"]);
+    let lines: Vec<&str> = listing.lines().collect();
+    assert_eq!(lines.len(), 3, "{listing}");
+    assert!(lines[0].trim_start().starts_with('1') && lines[0].contains("2 tasks"));
+    assert!(lines[1].contains("1 tasks") && lines[1].contains("+0 -1 ~1"));
+
+    let out = run(&["restore", "2"]);
+    assert!(out.contains("~ 1 Keep me (undone)"), "{out}");
+    assert!(out.contains("- 2 Second"), "{out}");
+    let tasks = read_db_tasks();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0]["done"], false);
+
+    let out = rusk_command().args(["restore", "9"]).output().unwrap();
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("no backup 9"));
+}
@@ -0,0 +1,114 @@
+use rusk::TaskManager;
+use rusk::journal::{
+    EntryKind, JOURNAL_RETENTION, append, journal_path, read_journal, undo_redo_stacks,
+};
+use tempfile::tempdir;
+
+mod common;
+
+fn texts(tm: &TaskManager) -> Vec<&str> {
+    tm.tasks().iter().map(|t| t.text.as_str()).collect()
+}
+
+#[test]
+fn test_every_save_is_journaled_with_before_and_after() {
+    let dir = tempdir().unwrap();
+    let mut tm = common::manager_with_tasks(dir.path(), &["One", "Two", "Three"]);
+    tm.mark_tasks(vec![2]).unwrap();
+
+    let entries = read_journal(&tm.db_path).unwrap();
+    assert_eq!(entries.len(), 4);
+    let mark = &entries[3];
+    assert_eq!(mark.kind, EntryKind::Op);
+    assert_eq!(mark.counts(), (0, 0, 1));
+    let change = &mark.changes[0];
+    assert!(!change.before.as_ref().unwrap().done);
+    assert!(change.after.as_ref().unwrap().done);
+}
+
+#[test]
+fn test_undo_delete_puts_tasks_back_in_place() {
+    let dir = tempdir().unwrap();
+    let mut tm = common::manager_with_tasks(dir.path(), &["One", "Two", "Three"]);
+    tm.mark_tasks(vec![1, 3]).unwrap();
+    tm.delete_all_done().unwrap();
+    assert_eq!(texts(&tm), ["Two"]);
+
+    let undone = tm.undo(1).unwrap();
+    assert_eq!(undone[0].counts().1, 2);
+    assert_eq!(texts(&tm), ["One", "Two", "Three"]);
+    assert!(tm.tasks()[0].done && tm.tasks()[2].done);
+
+    // Persisted, and undoable further back
+    let reloaded = TaskManager::load_tasks_from_path(&tm.db_path).unwrap();
+    assert_eq!(reloaded.len(), 3);
+    tm.undo(1).unwrap();
+    assert!(tm.tasks().iter().all(|t| !t.done));
+}
+
+#[test]
+fn test_redo_and_new_operation_clears_redo() {
+    let dir = tempdir().unwrap();
+    let mut tm = common::manager_with_tasks(dir.path(), &["One", "Two", "Three"]);
+    tm.edit_tasks(vec![1], Some(vec!["Uno".to_string()]), None)
+        .unwrap();
+
+    tm.undo(2).unwrap();
+    assert_eq!(texts(&tm), ["One", "Two"]);
+    tm.redo(1).unwrap();
+    assert_eq!(texts(&tm), ["One", "Two", "Three"]);
+
+    let entries = read_journal(&tm.db_path).unwrap();
+    let (_, undone) = undo_redo_stacks(&entries);
+    assert_eq!(undone.len(), 1, "the edit can still be redone");
+
+    tm.add_task(vec!["Four".to_string()], None).unwrap();
+    assert!(
+        tm.redo(1)
+            .unwrap_err()
+            .to_string()
+            .contains("Nothing to redo")
+    );
+    assert_eq!(texts(&tm), ["One", "Two", "Three", "Four"]);
+}
+
+#[test]
+fn test_undo_refuses_when_task_changed_outside_journal() {
+    let dir = tempdir().unwrap();
+    let mut tm = common::manager_with_tasks(dir.path(), &["One", "Two", "Three"]);
+    tm.mark_tasks(vec![2]).unwrap();
+
+    // Hand-edit the file behind rusk's back
+    let mut other = TaskManager::new_with_path(tm.db_path.clone()).unwrap();
+    other.tasks_mut()[1].text = "Edited by hand".to_string();
+    let data = serde_json::json!({"version": 2, "tasks": other.tasks()});
+    std::fs::write(&tm.db_path, data.to_string()).unwrap();
+    tm.reload().unwrap();
+
+    let err = tm.undo(1).unwrap_err().to_string();
+    assert!(err.contains("Cannot undo"), "{err}");
+    assert_eq!(tm.tasks()[1].text, "Edited by hand");
+    assert!(tm.tasks()[1].done);
+}
+
+#[test]
+fn test_journal_keeps_only_the_newest_entries() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("tasks.json");
+    let total = JOURNAL_RETENTION + 150;
+    for _ in 0..total {
+        append(&db, EntryKind::Op, "add x".to_string(), Vec::new()).unwrap();
+    }
+    let entries = read_journal(&db).unwrap();
+    // Trimmed at the last multiple of 100, then appended to.
+    assert_eq!(entries.len() as u64, JOURNAL_RETENTION + 50);
+    assert_eq!(entries.last().unwrap().seq, total);
+
+    // A torn last line does not restart the numbering.
+    let path = journal_path(&db);
+    let mut data = std::fs::read_to_string(&path).unwrap();
+    data.push_str("{\"seq\": 99");
+    std::fs::write(&path, data).unwrap();
+    let entry = append(&db, EntryKind::Op, "add y".to_string(), Vec::new()).unwrap();
+    assert_eq!(entry.seq, total + 1);
+}
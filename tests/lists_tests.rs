@@ -0,0 +1,96 @@
+// Named lists side by side in one database directory, and the global --db option
+
+use std::fs;
+
+use rusk::doctor::{Issue, check};
+use rusk::storage::{list_names, validate_list_name};
+use tempfile::tempdir;
+
+mod common;
+
+#[test]
+fn test_list_names_default_first() {
+    let temp_dir = tempdir().unwrap();
+    let dir = temp_dir.path();
+    for file in [
+        "work.json",
+        "tasks.json",
+        "home.json",
+        "tasks.json.journal",
+        "tasks.json.backup-20250101-120000-000",
+        "notes.txt",
+    ] {
+        fs::write(dir.join(file), "").unwrap();
+    }
+    assert_eq!(list_names(dir).unwrap(), vec!["tasks", "home", "work"]);
+    assert!(list_names(&dir.join("missing")).unwrap().is_empty());
+}
+
+#[test]
+fn test_list_names_must_be_plain_file_names() {
+    for ok in ["work", "side-project", "q3_2025"] {
+        assert!(validate_list_name(ok).is_ok(), "{ok}");
+    }
+    for bad in ["", "../work", "a/b", "work.json", "my list"] {
+        assert!(validate_list_name(bad).is_err(), "{bad:?}");
+    }
+}
+
+#[test]
+fn test_lists_keep_separate_files() {
+    let temp_dir = tempdir().unwrap();
+    let dir = temp_dir.path();
+    // A trailing slash (or an existing directory) means tasks.json inside it.
+    let db = dir.join("db/");
+    assert!(common::run_rusk(&db, &["init"]).status.success());
+    assert!(
+        common::run_rusk(&db, &["add", "default task"])
+            .status
+            .success()
+    );
+    assert!(
+        common::run_rusk(&db, &["--list", "work", "add", "work task"])
+            .status
+            .success()
+    );
+
+    let tasks = fs::read_to_string(db.join("tasks.json")).unwrap();
+    let work = fs::read_to_string(db.join("work.json")).unwrap();
+    assert!(tasks.contains("default task") && !tasks.contains("work task"));
+    assert!(work.contains("work task") && !work.contains("default task"));
+    assert!(db.join("work.json.journal").exists());
+
+    let out = common::run_rusk(&db, &["--list", "work", "lists"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let lines: Vec<_> = stdout.lines().skip(1).collect();
+    assert!(lines[0].starts_with("  tasks"), "{stdout}");
+    assert!(lines[1].starts_with("* work"), "{stdout}");
+    assert!(lines[1].contains("1 open, 1 total"), "{stdout}");
+
+    // Undo only sees the list it is run on.
+    assert!(
+        common::run_rusk(&db, &["--list", "work", "undo"])
+            .status
+            .success()
+    );
+    assert!(
+        fs::read_to_string(db.join("tasks.json"))
+            .unwrap()
+            .contains("default task")
+    );
+}
+
+#[test]
+fn test_doctor_reports_the_draft_of_its_own_list() {
+    let temp_dir = tempdir().unwrap();
+    let dir = temp_dir.path();
+    fs::write(dir.join("work.json"), r#"{"version": 2, "tasks": []}"#).unwrap();
+    fs::write(dir.join("home.json.draft"), "{}").unwrap();
+    assert!(check(&dir.join("work.json")).unwrap().issues.is_empty());
+
+    fs::write(dir.join("work.json.draft"), "{}").unwrap();
+    assert_eq!(
+        check(&dir.join("work.json")).unwrap().issues,
+        vec![Issue::EditorDraft(dir.join("work.json.draft"))]
+    );
+}
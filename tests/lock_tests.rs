@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+use rusk::lock::{DbLock, lock_path, read_holder};
+use tempfile::tempdir;
+
+#[test]
+fn test_lock_is_exclusive_and_released_on_drop() {
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("tasks.json");
+
+    let lock = DbLock::acquire(&db_path, Duration::ZERO).unwrap();
+    assert_eq!(lock.path(), lock_path(&db_path));
+    let holder = read_holder(lock.path()).unwrap();
+    assert_eq!(holder.pid, std::process::id());
+
+    assert!(DbLock::try_acquire(&db_path).unwrap().is_none());
+    let err = DbLock::acquire(&db_path, Duration::from_millis(150)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains(&format!("pid {}", std::process::id())),
+        "{err}"
+    );
+
+    drop(lock);
+    assert!(!lock_path(&db_path).exists());
+    assert!(DbLock::try_acquire(&db_path).unwrap().is_some());
+}
+
+#[test]
+fn test_lock_file_sits_next_to_database() {
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("nested").join("work.json");
+    let _lock = DbLock::acquire(&db_path, Duration::ZERO).unwrap();
+    assert!(
+        temp_dir
+            .path()
+            .join("nested")
+            .join("work.json.lock")
+            .exists()
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_stale_lock_is_taken_over_by_one_waiter() {
+    use rusk::lock::LockHolder;
+    use std::fs;
+    use std::sync::{Arc, Barrier};
+
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("tasks.json");
+    let holder = LockHolder {
+        pid: u32::MAX - 1,
+        command: "rusk add crashed".to_string(),
+        since: "2026-01-01T09:15:00+00:00".to_string(),
+    };
+    for _ in 0..200 {
+        fs::write(lock_path(&db_path), serde_json::to_string(&holder).unwrap()).unwrap();
+        let barrier = Arc::new(Barrier::new(8));
+        let waiters: Vec<_> = (0..8)
+            .map(|_| {
+                let (db_path, barrier) = (db_path.clone(), barrier.clone());
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    let lock = DbLock::try_acquire(&db_path).unwrap();
+                    // Hold on to it until every waiter has tried.
+                    barrier.wait();
+                    lock.is_some()
+                })
+            })
+            .collect();
+        let taken = waiters
+            .into_iter()
+            .map(|w| w.join().unwrap())
+            .filter(|&taken| taken)
+            .count();
+        assert_eq!(taken, 1);
+    }
+}
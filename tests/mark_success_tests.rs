@@ -1,4 +1,4 @@
-use rusk::TaskManager;
+use rusk::{Priority, TaskManager};
 mod common;
 use common::{create_test_task, create_test_task_with_priority};
 
@@ -88,31 +88,36 @@ fn test_mark_priority_toggles_priority_flag() {
     let (marked, not_found) = tm.mark_priority_tasks(vec![1]).unwrap();
     assert_eq!(marked, vec![(1, true)]);
     assert!(not_found.is_empty());
-    assert!(tm.tasks[0].priority);
+    assert_eq!(tm.tasks[0].priority, Priority::High);
     assert!(!tm.tasks[0].done);
 
     let (marked, _) = tm.mark_priority_tasks(vec![1]).unwrap();
     assert_eq!(marked, vec![(1, false)]);
-    assert!(!tm.tasks[0].priority);
+    assert_eq!(tm.tasks[0].priority, Priority::None);
     assert!(!tm.tasks[0].done);
 }
 
 #[test]
 fn test_mark_done_preserves_priority() {
     let mut tm = TaskManager::new_empty().unwrap();
-    tm.tasks = vec![create_test_task_with_priority(1, "Task", false, true)];
+    tm.tasks = vec![create_test_task_with_priority(
+        1,
+        "Task",
+        false,
+        Priority::High,
+    )];
 
     // Priority → Done: priority preserved, done set.
     let (marked, _) = tm.mark_tasks(vec![1]).unwrap();
     assert_eq!(marked, vec![(1, true)]);
     assert!(tm.tasks[0].done);
-    assert!(tm.tasks[0].priority);
+    assert_eq!(tm.tasks[0].priority, Priority::High);
 
     // Done → Priority (not Normal): priority still true, done cleared.
     let (marked, _) = tm.mark_tasks(vec![1]).unwrap();
     assert_eq!(marked, vec![(1, false)]);
     assert!(!tm.tasks[0].done);
-    assert!(tm.tasks[0].priority);
+    assert_eq!(tm.tasks[0].priority, Priority::High);
 }
 
 #[test]
@@ -122,23 +127,28 @@ fn test_mark_done_on_normal_task_leaves_priority_false() {
 
     tm.mark_tasks(vec![1]).unwrap();
     assert!(tm.tasks[0].done);
-    assert!(!tm.tasks[0].priority);
+    assert_eq!(tm.tasks[0].priority, Priority::None);
 
     tm.mark_tasks(vec![1]).unwrap();
     assert!(!tm.tasks[0].done);
-    assert!(!tm.tasks[0].priority);
+    assert_eq!(tm.tasks[0].priority, Priority::None);
 }
 
 #[test]
 fn test_mark_priority_independent_of_done_flag() {
     let mut tm = TaskManager::new_empty().unwrap();
-    tm.tasks = vec![create_test_task_with_priority(1, "Task", true, false)];
+    tm.tasks = vec![create_test_task_with_priority(
+        1,
+        "Task",
+        true,
+        Priority::None,
+    )];
 
     // `-p` on a done task toggles priority without touching done.
     let (marked, _) = tm.mark_priority_tasks(vec![1]).unwrap();
     assert_eq!(marked, vec![(1, true)]);
     assert!(tm.tasks[0].done);
-    assert!(tm.tasks[0].priority);
+    assert_eq!(tm.tasks[0].priority, Priority::High);
 }
 
 #[test]
@@ -150,3 +160,34 @@ fn test_mark_priority_not_found() {
     assert_eq!(marked, vec![(1, true)]);
     assert_eq!(not_found, vec![99]);
 }
+
+#[test]
+fn test_set_priority_levels() {
+    let mut tm = TaskManager::new_empty().unwrap();
+    tm.tasks = vec![
+        create_test_task(1, "Task 1", false),
+        create_test_task(2, "Task 2", false),
+    ];
+
+    let (marked, not_found) = tm.set_priority_tasks(vec![1, 2, 9], Priority::Low).unwrap();
+    assert_eq!(marked, vec![1, 2]);
+    assert_eq!(not_found, vec![9]);
+    assert_eq!(tm.tasks[1].priority, Priority::Low);
+
+    // A toggle from any level clears it
+    tm.mark_priority_tasks(vec![2]).unwrap();
+    assert_eq!(tm.tasks[1].priority, Priority::None);
+
+    tm.set_priority_tasks(vec![1], Priority::None).unwrap();
+    assert_eq!(tm.tasks[0].priority, Priority::None);
+}
+
+#[test]
+fn test_priority_levels_parse_and_order() {
+    assert_eq!("high".parse::<Priority>().unwrap(), Priority::High);
+    assert_eq!("M".parse::<Priority>().unwrap(), Priority::Medium);
+    assert_eq!("low".parse::<Priority>().unwrap(), Priority::Low);
+    assert_eq!("none".parse::<Priority>().unwrap(), Priority::None);
+    assert!("urgent".parse::<Priority>().is_err());
+    assert!(Priority::High > Priority::Medium && Priority::Low > Priority::None);
+}
@@ -0,0 +1,231 @@
+// Three-way merge of task databases (rusk merge-driver)
+
+use std::fs;
+use std::path::Path;
+
+use rusk::db_format::{self, DbMeta};
+use rusk::merge::{ConflictKind, Merged, add_gitattributes, merge, merge_files, render};
+use rusk::{Priority, Task};
+use tempfile::tempdir;
+use uuid::Uuid;
+
+mod common;
+
+fn task(id: u32, text: &str) -> Task {
+    Task {
+        uuid: Uuid::new_v4(),
+        ..common::create_test_task(id, text, false)
+    }
+}
+
+#[test]
+fn test_tasks_added_on_both_branches_are_renumbered() {
+    let base = vec![task(1, "shared")];
+    let mut ours = base.clone();
+    ours.push(task(2, "ours"));
+    let mut theirs = base.clone();
+    theirs.push(task(2, "theirs"));
+
+    let merged = merge(&base, &ours, &theirs).unwrap();
+    let ids: Vec<_> = merged
+        .tasks
+        .iter()
+        .map(|t| (t.id, t.text.as_str()))
+        .collect();
+    assert_eq!(ids, vec![(1, "shared"), (2, "ours"), (3, "theirs")]);
+    assert_eq!(merged.renumbered, vec![(2, 3)]);
+    assert!(merged.conflicts.is_empty());
+}
+
+#[test]
+fn test_different_fields_merge_without_conflict() {
+    let base = vec![task(1, "write report"), task(2, "call bob")];
+    let mut ours = base.clone();
+    ours[0].set_text("write report #work".to_string());
+    ours[1].done = true;
+    let mut theirs = base.clone();
+    theirs[0].priority = Priority::High;
+    theirs.remove(1);
+
+    let merged = merge(&base, &ours, &theirs).unwrap();
+    // Task 2 was changed here and deleted there: a conflict that keeps our version.
+    assert_eq!(merged.conflicts.len(), 1);
+    assert_eq!(merged.conflicts[0].kind, ConflictKind::DeletedByThem);
+    let first = &merged.tasks[0];
+    assert_eq!(first.text, "write report #work");
+    assert_eq!(first.tags, vec!["work"]);
+    assert_eq!(first.priority, Priority::High);
+}
+
+#[test]
+fn test_untouched_task_deleted_on_one_side_goes_away() {
+    let base = vec![task(1, "keep"), task(2, "drop")];
+    let ours = base.clone();
+    let theirs = vec![base[0].clone()];
+    let merged = merge(&base, &ours, &theirs).unwrap();
+    assert_eq!(merged.tasks.len(), 1);
+    assert!(merged.conflicts.is_empty());
+}
+
+#[test]
+fn test_same_task_added_on_both_sides_is_kept_once() {
+    let ours = vec![task(1, "buy milk")];
+    let theirs = vec![task(1, "buy milk")];
+    let merged = merge(&[], &ours, &theirs).unwrap();
+    assert_eq!(merged.tasks.len(), 1);
+    assert!(merged.conflicts.is_empty());
+}
+
+#[test]
+fn test_legacy_tasks_are_matched_by_text() {
+    let legacy = |id, text: &str| common::create_test_task(id, text, false);
+    let base = vec![legacy(1, "a"), legacy(2, "b")];
+    let mut ours = base.clone();
+    ours[1].done = true;
+    let mut theirs = base.clone();
+    theirs[0].priority = Priority::Low;
+    let merged = merge(&base, &ours, &theirs).unwrap();
+    assert!(merged.conflicts.is_empty());
+    assert_eq!(merged.tasks.len(), 2);
+    assert_eq!(merged.tasks[0].priority, Priority::Low);
+    assert!(merged.tasks[1].done);
+}
+
+#[test]
+fn test_conflicting_edit_is_written_with_markers() {
+    let temp_dir = tempdir().unwrap();
+    let base = vec![task(1, "original"), task(2, "other")];
+    let mut ours = base.clone();
+    ours[0].set_text("ours".to_string());
+    ours[1].done = true;
+    let mut theirs = base.clone();
+    theirs[0].set_text("theirs".to_string());
+
+    let write = |name: &str, tasks: &[Task]| {
+        let path = temp_dir.path().join(name);
+        fs::write(&path, db_format::encode(tasks, &DbMeta::default()).unwrap()).unwrap();
+        path
+    };
+    let (b, o, t) = (
+        write("base.json", &base),
+        write("ours.json", &ours),
+        write("theirs.json", &theirs),
+    );
+    let merged = merge_files(&b, &o, &t).unwrap();
+    assert_eq!(merged.conflicts.len(), 1);
+
+    let text = fs::read_to_string(&o).unwrap();
+    let expected = "<<<<<<< ours\n      \"text\": \"ours\",\n=======\n      \"text\": \"theirs\",\n>>>>>>> theirs\n";
+    assert!(text.contains(expected), "{text}");
+    assert_eq!(text.matches("<<<<<<<").count(), 1);
+    // Everything else is merged normally.
+    assert!(text.contains("\"done\": true"));
+}
+
+#[test]
+fn test_gitattributes_rule_is_added_once() {
+    let temp_dir = tempdir().unwrap();
+    fs::write(temp_dir.path().join(".gitattributes"), "*.png binary").unwrap();
+    assert!(add_gitattributes(temp_dir.path(), ".rusk/*.json").unwrap());
+    assert!(!add_gitattributes(temp_dir.path(), ".rusk/*.json").unwrap());
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join(".gitattributes")).unwrap(),
+        "*.png binary\n.rusk/*.json merge=rusk\n"
+    );
+}
+
+/// Runs the merge driver on files holding these versions; returns the result and the text it
+/// left in the `ours` file.
+fn merge_in(dir: &Path, base: &[Task], ours: &[Task], theirs: &[Task]) -> (Merged, String) {
+    let write = |name: &str, tasks: &[Task]| {
+        let path = dir.join(name);
+        fs::write(&path, db_format::encode(tasks, &DbMeta::default()).unwrap()).unwrap();
+        path
+    };
+    let (b, o, t) = (
+        write("base.json", base),
+        write("ours.json", ours),
+        write("theirs.json", theirs),
+    );
+    let merged = merge_files(&b, &o, &t).unwrap();
+    (merged, fs::read_to_string(&o).unwrap())
+}
+
+/// Tasks of a merged file with every conflict resolved to one side.
+fn resolve(text: &str, keep_ours: bool) -> Vec<Task> {
+    let mut section = None;
+    let resolved: String = text
+        .lines()
+        .filter(|line| {
+            match &line[..line.len().min(7)] {
+                "<<<<<<<" => section = Some(true),
+                "=======" => section = Some(false),
+                ">>>>>>>" => section = None,
+                _ => return section.is_none_or(|ours| ours == keep_ours),
+            }
+            false
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let doc = serde_json::from_str(&resolved).unwrap_or_else(|e| panic!("{e}: {resolved}"));
+    db_format::decode(doc).unwrap().0
+}
+
+#[test]
+fn test_conflicting_field_missing_on_one_side() {
+    let temp_dir = tempdir().unwrap();
+    let mut base = vec![task(1, "estimated")];
+    base[0].extra.insert("estimate".into(), 1.into());
+    let mut ours = base.clone();
+    ours[0].extra.insert("estimate".into(), 3.into());
+    let mut theirs = base.clone();
+    theirs[0].extra.remove("estimate");
+
+    let (merged, text) = merge_in(temp_dir.path(), &base, &ours, &theirs);
+    assert_eq!(merged.conflicts.len(), 1);
+    assert_eq!(
+        merged.conflicts[0].kind,
+        ConflictKind::Fields(vec![("estimate".into(), Some(3.into()), None)])
+    );
+
+    // Either resolution leaves a valid database.
+    for keep_ours in [true, false] {
+        let tasks = resolve(&text, keep_ours);
+        assert_eq!(tasks[0].extra.contains_key("estimate"), keep_ours);
+    }
+
+    // A conflict that cannot be marked is an error, not a silently resolved file.
+    let mut lost = merged.clone();
+    lost.conflicts[0].uuid = Uuid::new_v4();
+    assert!(render(&lost, &DbMeta::default()).is_err());
+}
+
+#[test]
+fn test_last_task_deleted_on_one_side_and_edited_on_the_other() {
+    let temp_dir = tempdir().unwrap();
+    let base = vec![task(1, "first"), task(2, "last")];
+    let mut ours = base.clone();
+    ours.pop();
+    let mut theirs = base.clone();
+    theirs[1].set_text("last, edited".to_string());
+
+    let (merged, text) = merge_in(temp_dir.path(), &base, &ours, &theirs);
+    assert_eq!(merged.conflicts[0].kind, ConflictKind::DeletedByUs);
+    let texts = |tasks: Vec<Task>| tasks.into_iter().map(|t| t.text).collect::<Vec<_>>();
+    assert_eq!(texts(resolve(&text, true)), vec!["first"]);
+    assert_eq!(texts(resolve(&text, false)), vec!["first", "last, edited"]);
+}
+
+#[test]
+fn test_dependency_cycle_across_branches_is_broken() {
+    let base = vec![task(1, "design"), task(2, "build")];
+    let mut ours = base.clone();
+    ours[1].blocked_by.push(base[0].uuid);
+    let mut theirs = base.clone();
+    theirs[0].blocked_by.push(base[1].uuid);
+
+    let merged = merge(&base, &ours, &theirs).unwrap();
+    assert_eq!(merged.unblocked, vec![(2, 1)]);
+    assert_eq!(merged.tasks[0].blocked_by, vec![base[1].uuid]);
+    assert!(merged.tasks[1].blocked_by.is_empty());
+}
@@ -1,4 +1,4 @@
-use rusk::parse_flexible_ids;
+use rusk::{TaskId, find_out_of_range_id, parse_edit_args, parse_flexible_ids};
 
 #[test]
 fn test_parse_flexible_ids_single_id() {
@@ -42,7 +42,7 @@ fn test_parse_flexible_ids_invalid_ids_ignored() {
 #[test]
 fn test_parse_flexible_ids_empty_input() {
     let ids = parse_flexible_ids(&[]);
-    assert_eq!(ids, vec![] as Vec<u8>);
+    assert_eq!(ids, vec![] as Vec<TaskId>);
 }
 
 #[test]
@@ -58,6 +58,39 @@ fn test_parse_flexible_ids_max_u8() {
     assert_eq!(ids, vec![255]);
 }
 
+#[test]
+fn test_parse_flexible_ids_above_u8_range() {
+    let ids = parse_flexible_ids(&["256,1000,70000".to_string()]);
+    assert_eq!(ids, vec![256, 1000, 70000]);
+
+    let (ids, text) = parse_edit_args(vec!["4096".to_string(), "text".to_string()]);
+    assert_eq!(ids, vec![4096]);
+    assert_eq!(text, Some(vec!["text".to_string()]));
+}
+
+#[test]
+fn test_find_out_of_range_id() {
+    let too_big = (TaskId::MAX as u64 + 1).to_string();
+    assert_eq!(
+        find_out_of_range_id(std::slice::from_ref(&too_big)),
+        Some(too_big.clone())
+    );
+    assert_eq!(
+        find_out_of_range_id(&[format!("1, {too_big},3")]),
+        Some(too_big.clone())
+    );
+    assert_eq!(
+        find_out_of_range_id(&["1,2".to_string(), "300".to_string()]),
+        None
+    );
+    assert_eq!(find_out_of_range_id(&[TaskId::MAX.to_string()]), None);
+    // Text after the IDs (e.g. `rusk edit 1 call 99999999999`) is not an ID
+    assert_eq!(
+        find_out_of_range_id(&["1".to_string(), "call".to_string(), too_big]),
+        None
+    );
+}
+
 #[test]
 fn test_parse_flexible_ids_comma_separated_with_invalid() {
     let ids = parse_flexible_ids(&["1,abc,2,xyz,3".to_string()]);
@@ -1,8 +1,8 @@
-use rusk::TaskManager;
+use rusk::{Priority, TaskManager};
 use tempfile::tempdir;
 
 #[test]
-fn test_load_tasks_without_priority_defaults_to_false() {
+fn test_load_tasks_without_priority_defaults_to_none() {
     let temp_dir = tempdir().unwrap();
     let db_path = temp_dir.path().join("legacy_no_priority.json");
     std::fs::write(
@@ -12,7 +12,48 @@ fn test_load_tasks_without_priority_defaults_to_false() {
     .unwrap();
     let tasks = TaskManager::load_tasks_from_path(&db_path).unwrap();
     assert_eq!(tasks.len(), 1);
-    assert!(!tasks[0].priority);
+    assert_eq!(tasks[0].priority, Priority::None);
+}
+
+#[test]
+fn test_load_legacy_boolean_priority() {
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("legacy_bool_priority.json");
+    std::fs::write(
+        &db_path,
+        r#"[{"id":1,"text":"Urgent","date":null,"done":false,"priority":true},
+            {"id":2,"text":"Normal","date":null,"done":false,"priority":false},
+            {"id":3,"text":"Later","date":null,"done":false,"priority":"low"}]"#,
+    )
+    .unwrap();
+    let tasks = TaskManager::load_tasks_from_path(&db_path).unwrap();
+    assert_eq!(tasks[0].priority, Priority::High);
+    assert_eq!(tasks[1].priority, Priority::None);
+    assert_eq!(tasks[2].priority, Priority::Low);
+
+    let json = serde_json::to_value(&tasks[0]).unwrap();
+    assert_eq!(json["priority"], "high");
+}
+
+#[test]
+fn test_load_legacy_small_ids_and_save_wide_ids() {
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("legacy_ids.json");
+    std::fs::write(
+        &db_path,
+        r#"[{"id":1,"text":"One","date":null,"done":false,"priority":false},
+            {"id":255,"text":"Max u8","date":null,"done":true,"priority":false}]"#,
+    )
+    .unwrap();
+    let mut tm = TaskManager::new_empty_with_path(db_path.clone());
+    tm.tasks = TaskManager::load_tasks_from_path(&db_path).unwrap();
+    assert_eq!(tm.tasks()[1].id, 255);
+
+    tm.tasks_mut()[0].id = 1000;
+    tm.save().unwrap();
+    let reloaded = TaskManager::load_tasks_from_path(&db_path).unwrap();
+    assert_eq!(reloaded[0].id, 1000);
+    assert_eq!(reloaded[1].id, 255);
 }
 
 #[test]
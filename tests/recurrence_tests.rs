@@ -0,0 +1,120 @@
+use chrono::{Local, NaiveDate};
+use rusk::{NewTaskOptions, Recurrence, RecurrenceMode, TaskManager};
+use tempfile::tempdir;
+
+mod common;
+use common::ymd;
+
+fn add_recurring(
+    tm: &mut TaskManager,
+    text: &str,
+    date: Option<NaiveDate>,
+    every: &str,
+    mode: RecurrenceMode,
+) {
+    let options = NewTaskOptions {
+        recurrence: Some(Recurrence::new(every, mode).unwrap()),
+        ..Default::default()
+    };
+    tm.add_task_with_options(text.to_string(), date, &options)
+        .unwrap();
+}
+
+#[test]
+fn test_recurrence_rejects_invalid_interval() {
+    assert!(Recurrence::new("1m", RecurrenceMode::FromDue).is_ok());
+    assert!(Recurrence::new("2w3d", RecurrenceMode::FromDue).is_ok());
+    assert!(Recurrence::new("0d", RecurrenceMode::FromDue).is_err());
+    assert!(Recurrence::new("monthly", RecurrenceMode::FromDue).is_err());
+    assert!(Recurrence::new("1-11-26", RecurrenceMode::FromDue).is_err());
+}
+
+#[test]
+fn test_next_due_modes() {
+    let from_due = Recurrence::new("1m", RecurrenceMode::FromDue).unwrap();
+    let from_done = Recurrence::new("1m", RecurrenceMode::FromCompletion).unwrap();
+    let due = Some(ymd(2026, 11, 1));
+    let completed = ymd(2026, 11, 5);
+
+    assert_eq!(from_due.next_due(due, completed).unwrap(), ymd(2026, 12, 1));
+    assert_eq!(
+        from_done.next_due(due, completed).unwrap(),
+        ymd(2026, 12, 5)
+    );
+    // No due date: both count from completion
+    assert_eq!(
+        from_due.next_due(None, completed).unwrap(),
+        ymd(2026, 12, 5)
+    );
+}
+
+#[test]
+fn test_mark_done_spawns_next_occurrence() {
+    let temp_dir = tempdir().unwrap();
+    let mut tm = TaskManager::new_empty_with_path(temp_dir.path().join("tasks.json"));
+    add_recurring(
+        &mut tm,
+        "Pay rent #home",
+        Some(ymd(2026, 11, 1)),
+        "1m",
+        RecurrenceMode::FromDue,
+    );
+
+    let (marked, _) = tm.mark_tasks(vec![1]).unwrap();
+    assert_eq!(marked, vec![(1, true)]);
+    assert_eq!(tm.tasks.len(), 2);
+
+    let next = &tm.tasks[1];
+    assert_eq!(next.id, 2);
+    assert_eq!(next.text, "Pay rent #home");
+    assert_eq!(next.tags, vec!["home"]);
+    assert_eq!(next.date, Some(ymd(2026, 12, 1)));
+    assert!(!next.done);
+    assert_ne!(next.uuid, tm.tasks[0].uuid);
+    assert_eq!(next.recurrence.as_ref().unwrap().every, "1m");
+    // The rule moves to the new occurrence
+    assert!(tm.tasks[0].recurrence.is_none());
+
+    // Toggling the completed one again does not spawn a duplicate
+    tm.mark_tasks(vec![1]).unwrap();
+    tm.mark_tasks(vec![1]).unwrap();
+    assert_eq!(tm.tasks.len(), 2);
+
+    let reloaded = TaskManager::load_tasks_from_path(tm.db_path()).unwrap();
+    assert_eq!(reloaded.len(), 2);
+    assert_eq!(reloaded[1].date, Some(ymd(2026, 12, 1)));
+}
+
+#[test]
+fn test_from_completion_counts_from_today() {
+    let temp_dir = tempdir().unwrap();
+    let mut tm = TaskManager::new_empty_with_path(temp_dir.path().join("tasks.json"));
+    add_recurring(
+        &mut tm,
+        "Water plants",
+        Some(ymd(2020, 1, 1)),
+        "3d",
+        RecurrenceMode::FromCompletion,
+    );
+    tm.mark_tasks(vec![1]).unwrap();
+    let today = Local::now().date_naive();
+    assert_eq!(tm.tasks[1].date, Some(today + chrono::Duration::days(3)));
+}
+
+#[test]
+fn test_set_and_clear_recurrence() {
+    let temp_dir = tempdir().unwrap();
+    let mut tm = TaskManager::new_empty_with_path(temp_dir.path().join("tasks.json"));
+    tm.add_task(vec!["Review".to_string()], None).unwrap();
+
+    let rule = Recurrence::new("2w", RecurrenceMode::FromDue).unwrap();
+    assert!(tm.set_recurrence(1, Some(rule.clone())).unwrap());
+    assert!(!tm.set_recurrence(1, Some(rule)).unwrap());
+    assert!(tm.set_recurrence(1, None).unwrap());
+    assert!(tm.tasks[0].recurrence.is_none());
+    assert!(tm.set_recurrence(9, None).is_err());
+
+    // Without a rule, completing the task adds nothing
+    tm.mark_tasks(vec![1]).unwrap();
+    assert_eq!(tm.tasks.len(), 1);
+}
@@ -0,0 +1,118 @@
+// rusk renumber: compacting IDs by position, due date or priority; never-reused IDs
+
+use chrono::NaiveDate;
+use rusk::{Priority, RenumberOrder, TaskManager};
+use tempfile::tempdir;
+
+mod common;
+
+fn setup(dir: &tempfile::TempDir) -> TaskManager {
+    let mut tm = common::manager_with_tasks(dir.path(), &["One", "Two", "Three", "Four", "Five"]);
+    tm.delete_tasks(vec![1, 3]).unwrap();
+    tm
+}
+
+#[test]
+fn test_renumber_by_position_compacts_and_records_the_mapping() {
+    let dir = tempdir().unwrap();
+    let mut tm = setup(&dir);
+    let mapping = tm.renumber(RenumberOrder::Position).unwrap();
+    assert_eq!(mapping, vec![(2, 1), (4, 2), (5, 3)]);
+    assert_eq!(
+        common::ids_and_texts(tm.tasks()),
+        vec![(1, "Two"), (2, "Four"), (3, "Five")]
+    );
+
+    let reloaded = TaskManager::new_with_path(tm.db_path.clone()).unwrap();
+    let history = reloaded.renumberings();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].order, "position");
+    assert_eq!(history[0].ids, mapping);
+
+    // Already compact: nothing changes and nothing is recorded.
+    assert!(tm.renumber(RenumberOrder::Position).unwrap().is_empty());
+    assert_eq!(tm.renumberings().len(), 1);
+}
+
+#[test]
+fn test_renumber_by_due_and_priority_reorders() {
+    let dir = tempdir().unwrap();
+    let mut tm = setup(&dir);
+    for (id, date) in [(5, "2030-01-01"), (4, "2030-06-01")] {
+        let index = tm.find_task_by_id(id).unwrap();
+        tm.tasks[index].date = Some(date.parse::<NaiveDate>().unwrap());
+    }
+    tm.renumber(RenumberOrder::Due).unwrap();
+    assert_eq!(
+        common::ids_and_texts(tm.tasks()),
+        vec![(1, "Five"), (2, "Four"), (3, "Two")]
+    );
+
+    let index = tm.find_task_by_id(3).unwrap();
+    tm.tasks[index].priority = Priority::High;
+    tm.renumber(RenumberOrder::Priority).unwrap();
+    assert_eq!(
+        common::ids_and_texts(tm.tasks()),
+        vec![(1, "Two"), (2, "Five"), (3, "Four")]
+    );
+}
+
+#[test]
+fn test_renumber_moves_the_highest_id_only_when_ids_change() {
+    let dir = tempdir().unwrap();
+    let mut tm = setup(&dir);
+    tm.raise_last_id(9);
+    tm.renumber(RenumberOrder::Position).unwrap();
+    assert_eq!(tm.last_id(), 3);
+
+    tm.raise_last_id(7);
+    assert!(tm.renumber(RenumberOrder::Position).unwrap().is_empty());
+    assert_eq!(tm.last_id(), 7);
+}
+
+#[test]
+fn test_undo_restores_the_old_ids() {
+    let dir = tempdir().unwrap();
+    let mut tm = setup(&dir);
+    tm.renumber(RenumberOrder::Position).unwrap();
+    tm.undo(1).unwrap();
+    assert_eq!(
+        common::ids_and_texts(tm.tasks()),
+        vec![(2, "Two"), (4, "Four"), (5, "Five")]
+    );
+}
+
+#[test]
+fn test_ids_are_not_reused_when_turned_off() {
+    let temp_dir = tempdir().unwrap();
+    let db = temp_dir.path().join("db/");
+    assert!(common::run_rusk(&db, &["init"]).status.success());
+    for args in [
+        &["config", "set", "ids.reuse", "false"][..],
+        &["config", "set", "confirm.delete", "false"],
+        &["add", "one"],
+        &["add", "two"],
+        &["add", "three"],
+        &["del", "3"],
+        &["add", "four"],
+    ] {
+        let out = common::run_rusk(&db, args);
+        assert!(out.status.success(), "{args:?}: {out:?}");
+    }
+    let out = common::run_rusk(&db, &["list"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("4             four"), "{stdout}");
+
+    // Renumbering compacts anyway, and counting continues from there.
+    let out = common::run_rusk(&db, &["renumber"]);
+    assert!(
+        String::from_utf8_lossy(&out.stdout).contains("4 → 3"),
+        "{out:?}"
+    );
+    assert!(common::run_rusk(&db, &["add", "five"]).status.success());
+    let out = common::run_rusk(&db, &["renumber", "--history"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("(by position)") && stdout.contains("4 → 3"));
+    let data = std::fs::read_to_string(db.join("tasks.json")).unwrap();
+    assert!(data.contains("\"last_id\": 4"), "{data}");
+}
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use rusk::TaskManager;
+use rusk::{TaskManager, backup};
 use std::fs;
 use tempfile::TempDir;
 
@@ -96,11 +96,10 @@ fn test_restore_corrupted_backup() -> Result<()> {
 }
 
 #[test]
-fn test_restore_creates_before_restore_backup() -> Result<()> {
+fn test_restore_backs_up_current_database() -> Result<()> {
     let temp_dir = TempDir::new()?;
     let db_path = temp_dir.path().join("test.json");
     let backup_path = temp_dir.path().join("test.json.backup");
-    let before_restore_path = temp_dir.path().join("test.json.before_restore");
 
     // Create TaskManager with current data
     let mut tm = TaskManager::new_empty()?;
@@ -108,7 +107,7 @@ fn test_restore_creates_before_restore_backup() -> Result<()> {
     tm.tasks.push(create_test_task(1, "Current task", false));
     tm.save()?;
 
-    // Create backup with different data
+    // Create a legacy single-file backup with different data
     let backup_tasks = vec![create_test_task(2, "Backup task", false)];
     let backup_json = serde_json::to_string_pretty(&backup_tasks)?;
     fs::write(&backup_path, backup_json)?;
@@ -116,11 +115,11 @@ fn test_restore_creates_before_restore_backup() -> Result<()> {
     // Restore from backup
     tm.restore_from_backup()?;
 
-    // Verify that before_restore backup was created
-    assert!(before_restore_path.exists());
-
-    // Verify before_restore backup contains original data
-    let before_restore_data = fs::read_to_string(&before_restore_path)?;
+    // Verify that the current state was backed up first and is now the newest backup
+    let backups = backup::list_backups(&db_path)?;
+    assert_eq!(backups.len(), 2);
+    assert!(!backups[0].legacy);
+    let before_restore_data = fs::read_to_string(&backups[0].path)?;
     assert!(before_restore_data.contains("Current task"));
 
     // Verify current data is from backup
@@ -180,3 +179,52 @@ fn test_restore_empty_backup() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_backups_rotate_and_older_generation_restores() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_path = temp_dir.path().join("tasks.json");
+
+    let mut tm = TaskManager::new_empty_with_path(db_path.clone());
+    for n in 1..=4 {
+        tm.tasks
+            .push(create_test_task(n, &format!("Task {n}"), false));
+        tm.save()?;
+    }
+    // One backup per save after the first, newest first
+    let backups = backup::list_backups(&db_path)?;
+    assert_eq!(backups.len(), 3);
+    assert_eq!(
+        TaskManager::load_tasks_from_path(&backups[0].path)?.len(),
+        3
+    );
+    assert_eq!(
+        TaskManager::load_tasks_from_path(&backups[2].path)?.len(),
+        1
+    );
+
+    // Saving unchanged content does not add a duplicate backup
+    tm.save()?;
+    assert_eq!(backup::list_backups(&db_path)?.len(), 4);
+    tm.save()?;
+    assert_eq!(backup::list_backups(&db_path)?.len(), 4);
+
+    backup::prune_backups(&db_path, 2)?;
+    let backups = backup::list_backups(&db_path)?;
+    assert_eq!(backups.len(), 2);
+
+    // Restore the older of the two remaining generations (three tasks)
+    let diff = backup::diff_tasks(
+        tm.tasks(),
+        &TaskManager::load_tasks_from_path(&backups[1].path)?,
+    );
+    assert_eq!(diff.removed.len(), 1);
+    assert_eq!(diff.removed[0].text, "Task 4");
+    assert!(diff.added.is_empty() && diff.changed.is_empty());
+
+    tm.restore_from_path(&backups[1].path)?;
+    assert_eq!(tm.tasks.len(), 3);
+    assert_eq!(TaskManager::load_tasks_from_path(&db_path)?.len(), 3);
+
+    Ok(())
+}
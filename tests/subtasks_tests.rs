@@ -0,0 +1,146 @@
+use rusk::{NewTaskOptions, SubtaskPolicy, TaskManager};
+use tempfile::tempdir;
+
+fn manager_with_tree() -> (tempfile::TempDir, TaskManager) {
+    // 1
+    // ├─ 2
+    // │  └─ 3
+    // └─ 4
+    // 5
+    let temp_dir = tempdir().unwrap();
+    let mut tm = TaskManager::new_empty_with_path(temp_dir.path().join("tasks.json"));
+    let add = |tm: &mut TaskManager, text: &str, parent: Option<u32>| {
+        tm.add_task_with_options(
+            text.to_string(),
+            None,
+            &NewTaskOptions {
+                parent,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    };
+    add(&mut tm, "Release", None);
+    add(&mut tm, "Changelog", Some(1));
+    add(&mut tm, "Proofread", Some(2));
+    add(&mut tm, "Tag", Some(1));
+    add(&mut tm, "Other", None);
+    (temp_dir, tm)
+}
+
+fn ids(tm: &TaskManager) -> Vec<u32> {
+    tm.tasks.iter().map(|t| t.id).collect()
+}
+
+#[test]
+fn test_add_subtask_links_parent_uuid() {
+    let (_dir, tm) = manager_with_tree();
+    assert_eq!(tm.tasks[1].parent, Some(tm.tasks[0].uuid));
+    assert_eq!(tm.tasks[2].parent, Some(tm.tasks[1].uuid));
+    assert_eq!(tm.tasks[4].parent, None);
+
+    let reloaded = TaskManager::load_tasks_from_path(tm.db_path()).unwrap();
+    assert_eq!(reloaded[2].parent, Some(reloaded[1].uuid));
+}
+
+#[test]
+fn test_add_subtask_with_missing_parent_fails() {
+    let (_dir, mut tm) = manager_with_tree();
+    let err = tm
+        .add_task_with_options(
+            "Orphan".to_string(),
+            None,
+            &NewTaskOptions {
+                parent: Some(42),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("Parent task 42 not found"));
+    assert_eq!(tm.tasks.len(), 5);
+}
+
+#[test]
+fn test_subtask_and_descendant_lookup() {
+    let (_dir, tm) = manager_with_tree();
+    assert_eq!(tm.subtask_indices(0), vec![1, 3]);
+    let mut all = tm.descendant_indices(0);
+    all.sort_unstable();
+    assert_eq!(all, vec![1, 2, 3]);
+    assert!(tm.descendant_indices(4).is_empty());
+}
+
+#[test]
+fn test_delete_parent_requires_policy() {
+    let (_dir, mut tm) = manager_with_tree();
+    let err = tm.delete_tasks(vec![2]).unwrap_err();
+    assert!(err.to_string().contains("has subtasks"));
+    assert_eq!(tm.tasks.len(), 5);
+
+    // Deleting the parent together with all of its subtasks needs no policy
+    tm.delete_tasks(vec![2, 3]).unwrap();
+    assert_eq!(ids(&tm), vec![1, 4, 5]);
+}
+
+#[test]
+fn test_delete_parent_cascade() {
+    let (_dir, mut tm) = manager_with_tree();
+    let not_found = tm
+        .delete_tasks_with(vec![1], Some(SubtaskPolicy::Cascade))
+        .unwrap();
+    assert!(not_found.is_empty());
+    assert_eq!(ids(&tm), vec![5]);
+}
+
+#[test]
+fn test_delete_parent_reparent() {
+    let (_dir, mut tm) = manager_with_tree();
+    let root_uuid = tm.tasks[0].uuid;
+    tm.delete_tasks_with(vec![2], Some(SubtaskPolicy::Reparent))
+        .unwrap();
+    assert_eq!(ids(&tm), vec![1, 3, 4, 5]);
+    // 3 moves up to 2's parent
+    assert_eq!(tm.tasks[1].parent, Some(root_uuid));
+
+    tm.delete_tasks_with(vec![1], Some(SubtaskPolicy::Reparent))
+        .unwrap();
+    assert!(tm.tasks.iter().all(|t| t.parent.is_none()));
+}
+
+#[test]
+fn test_delete_all_done_relinks_open_subtasks() {
+    let (_dir, mut tm) = manager_with_tree();
+    tm.mark_tasks(vec![2]).unwrap();
+    assert_eq!(tm.delete_all_done().unwrap(), 1);
+    let proofread = tm.tasks.iter().find(|t| t.id == 3).unwrap();
+    assert_eq!(proofread.parent, Some(tm.tasks[0].uuid));
+}
+
+#[test]
+fn test_mark_parent_with_subtasks() {
+    let (_dir, mut tm) = manager_with_tree();
+    assert_eq!(tm.open_subtask_ids(1).len(), 3);
+
+    // Plain mark leaves subtasks open
+    let (marked, _) = tm.mark_tasks(vec![2]).unwrap();
+    assert_eq!(marked, vec![(2, true)]);
+    assert!(!tm.tasks[2].done);
+    tm.mark_tasks(vec![2]).unwrap();
+
+    // Finishing subtasks marks the whole open subtree done
+    let (marked, _) = tm.mark_tasks_with_subtasks(vec![1], &[1]).unwrap();
+    assert_eq!(marked.len(), 4);
+    assert!(tm.tasks[..4].iter().all(|t| t.done));
+    assert!(!tm.tasks[4].done);
+    assert!(tm.open_subtask_ids(1).is_empty());
+}
+
+#[test]
+fn test_mark_undone_does_not_touch_subtasks() {
+    let (_dir, mut tm) = manager_with_tree();
+    tm.mark_tasks_with_subtasks(vec![1], &[1]).unwrap();
+    // Toggling the parent back to undone must not finish (or reopen) anything else
+    let (marked, _) = tm.mark_tasks_with_subtasks(vec![1], &[1]).unwrap();
+    assert_eq!(marked, vec![(1, false)]);
+    assert!(tm.tasks[1].done);
+}
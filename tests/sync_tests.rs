@@ -0,0 +1,330 @@
+// Two-way sync of task databases (rusk sync): tombstones, conflicts by modification time
+
+use std::fs;
+
+use chrono::{DateTime, Duration, Utc};
+use rusk::db_format::Tombstone;
+use rusk::sync::{Change, Direction, Side, sync};
+use rusk::{Task, TaskManager};
+use tempfile::tempdir;
+use uuid::Uuid;
+
+mod common;
+
+fn task(id: u32, text: &str, updated_at: DateTime<Utc>) -> Task {
+    Task {
+        uuid: Uuid::new_v4(),
+        updated_at: Some(updated_at),
+        ..common::create_test_task(id, text, false)
+    }
+}
+
+#[test]
+fn test_new_tasks_go_both_ways_and_take_free_ids() {
+    let now = Utc::now();
+    let local = vec![task(1, "local", now)];
+    let other = vec![task(1, "other", now)];
+    let synced = sync(Side::new(&local, &[]), Side::new(&other, &[]), None).unwrap();
+    assert_eq!(
+        common::ids_and_texts(&synced.local),
+        vec![(1, "local"), (2, "other")]
+    );
+    assert_eq!(
+        common::ids_and_texts(&synced.other),
+        vec![(1, "other"), (2, "local")]
+    );
+    assert_eq!(synced.events.len(), 2);
+    assert!(synced.events.iter().all(|e| e.change == Change::Added));
+    assert!(synced.events.iter().all(|e| !e.conflict));
+
+    // A second sync finds nothing to do.
+    let again = sync(
+        Side::new(&synced.local, &[]),
+        Side::new(&synced.other, &[]),
+        Some(now),
+    )
+    .unwrap();
+    assert!(again.events.is_empty());
+}
+
+#[test]
+fn test_side_without_id_reuse_takes_ids_above_its_highest() {
+    let now = Utc::now();
+    let local = vec![task(1, "local", now)];
+    let other = vec![task(1, "other", now), task(2, "more", now)];
+    let synced = sync(
+        Side {
+            last_id: Some(5),
+            ..Side::new(&local, &[])
+        },
+        Side::new(&other, &[]),
+        None,
+    )
+    .unwrap();
+    // IDs 2 to 5 were handed out here before, so they are not given out again.
+    assert_eq!(
+        common::ids_and_texts(&synced.local),
+        vec![(1, "local"), (6, "other"), (7, "more")]
+    );
+    assert_eq!(synced.local_last_id, Some(7));
+    assert_eq!(synced.other[2].id, 3);
+    assert_eq!(synced.other_last_id, None);
+}
+
+#[test]
+fn test_deletion_travels_instead_of_coming_back() {
+    let last = Utc::now() - Duration::hours(1);
+    let shared = task(1, "shared", last - Duration::hours(1));
+    let gone = Tombstone {
+        uuid: shared.uuid,
+        deleted_at: last + Duration::minutes(5),
+        archived: false,
+    };
+    let synced = sync(
+        Side::new(&[], std::slice::from_ref(&gone)),
+        Side::new(&[shared], &[]),
+        Some(last),
+    )
+    .unwrap();
+    assert!(synced.local.is_empty());
+    assert!(synced.other.is_empty());
+    assert_eq!(synced.deleted, vec![gone]);
+    let event = &synced.events[0];
+    assert_eq!(
+        (event.direction, event.change, event.other_id),
+        (Direction::Pushed, Change::Deleted, Some(1))
+    );
+}
+
+#[test]
+fn test_change_after_deletion_wins_as_conflict() {
+    let last = Utc::now() - Duration::hours(1);
+    let edited = task(4, "edited after the delete", last + Duration::minutes(30));
+    let gone = Tombstone {
+        uuid: edited.uuid,
+        deleted_at: last + Duration::minutes(10),
+        archived: false,
+    };
+    let synced = sync(
+        Side::new(&[edited], &[]),
+        Side::new(&[], &[gone]),
+        Some(last),
+    )
+    .unwrap();
+    assert_eq!(
+        common::ids_and_texts(&synced.other),
+        vec![(4, "edited after the delete")]
+    );
+    assert!(synced.deleted.is_empty());
+    assert!(synced.events[0].conflict);
+    assert_eq!(synced.events[0].direction, Direction::Pushed);
+}
+
+#[test]
+fn test_changes_resolve_by_last_sync_then_by_time() {
+    let last = Utc::now() - Duration::hours(1);
+    let base = task(1, "base", last - Duration::hours(1));
+    let mut local = vec![
+        base.clone(),
+        Task {
+            id: 2,
+            ..task(0, "", last)
+        },
+    ];
+    let mut other = vec![Task {
+        id: 7,
+        ..base.clone()
+    }];
+
+    // Changed only on the other side: pulled, keeping the local ID.
+    other[0].text = "theirs".into();
+    other[0].updated_at = Some(last + Duration::minutes(1));
+    let synced = sync(Side::new(&local, &[]), Side::new(&other, &[]), Some(last)).unwrap();
+    assert_eq!(synced.local[0].id, 1);
+    assert_eq!(synced.local[0].text, "theirs");
+    let event = synced.events.iter().find(|e| e.uuid == base.uuid).unwrap();
+    assert_eq!(
+        (event.direction, event.conflict),
+        (Direction::Pulled, false)
+    );
+
+    // Changed on both sides: the later change wins, on both databases.
+    local[0].text = "ours, later".into();
+    local[0].updated_at = Some(last + Duration::minutes(2));
+    let synced = sync(Side::new(&local, &[]), Side::new(&other, &[]), Some(last)).unwrap();
+    assert_eq!(synced.local[0].text, "ours, later");
+    assert_eq!(synced.other[0].text, "ours, later");
+    assert_eq!(synced.other[0].id, 7);
+    let event = synced.events.iter().find(|e| e.uuid == base.uuid).unwrap();
+    assert_eq!((event.direction, event.conflict), (Direction::Pushed, true));
+}
+
+#[test]
+fn test_dependency_cycle_across_databases_is_broken_on_both() {
+    let last = Utc::now() - Duration::hours(1);
+    let design = task(1, "design", last - Duration::hours(1));
+    let build = task(2, "build", last - Duration::hours(1));
+    let mut ours = vec![design.clone(), build.clone()];
+    ours[1].blocked_by.push(design.uuid);
+    ours[1].updated_at = Some(last + Duration::minutes(5));
+    let mut theirs = vec![design.clone(), build.clone()];
+    theirs[0].blocked_by.push(build.uuid);
+    theirs[0].updated_at = Some(last + Duration::minutes(5));
+
+    let synced = sync(Side::new(&ours, &[]), Side::new(&theirs, &[]), Some(last)).unwrap();
+    assert_eq!(synced.unblocked.len(), 1);
+    for tasks in [&synced.local, &synced.other] {
+        let links: usize = tasks.iter().map(|t| t.blocked_by.len()).sum();
+        assert_eq!(links, 1);
+    }
+    assert_eq!(synced.local, synced.other);
+}
+
+#[test]
+fn test_saving_records_and_clears_tombstones() {
+    let dir = tempdir().unwrap();
+    let mut tm = common::manager_with_tasks(dir.path(), &["One", "Two"]);
+    let uuid = tm.tasks()[0].uuid;
+    tm.delete_tasks(vec![1]).unwrap();
+    let reloaded = TaskManager::new_with_path(tm.db_path.clone()).unwrap();
+    let deleted: Vec<_> = reloaded.tombstones().iter().map(|d| d.uuid).collect();
+    assert_eq!(deleted, vec![uuid]);
+
+    tm.undo(1).unwrap();
+    let reloaded = TaskManager::new_with_path(tm.db_path.clone()).unwrap();
+    assert!(reloaded.tombstones().is_empty());
+    assert!(
+        !fs::read_to_string(&tm.db_path)
+            .unwrap()
+            .contains("\"deleted\"")
+    );
+}
+
+#[test]
+fn test_sync_command_propagates_both_ways() {
+    let temp_dir = tempdir().unwrap();
+    let laptop = temp_dir.path().join("laptop/");
+    let shared = temp_dir.path().join("shared/");
+    for db in [&laptop, &shared] {
+        assert!(common::run_rusk(db, &["init"]).status.success());
+    }
+    assert!(
+        common::run_rusk(&laptop, &["add", "write report"])
+            .status
+            .success()
+    );
+    assert!(
+        common::run_rusk(&shared, &["add", "call bob"])
+            .status
+            .success()
+    );
+
+    let out = common::run_rusk(&laptop, &["sync", shared.to_str().unwrap()]);
+    assert!(out.status.success(), "{out:?}");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("1 pulled, 1 pushed"), "{stdout}");
+    for db in [&laptop, &shared] {
+        let data = fs::read_to_string(db.join("tasks.json")).unwrap();
+        assert!(data.contains("write report") && data.contains("call bob"));
+    }
+    assert!(laptop.join("tasks.json.sync").exists());
+
+    // Deleting on one side removes the task on the other at the next sync.
+    assert!(
+        common::run_rusk(&shared, &["config", "set", "confirm.delete", "false"])
+            .status
+            .success()
+    );
+    assert!(common::run_rusk(&shared, &["del", "1"]).status.success());
+    let out = common::run_rusk(&laptop, &["sync", shared.to_str().unwrap()]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("call bob") && stdout.contains("deleted"),
+        "{stdout}"
+    );
+    let data = fs::read_to_string(laptop.join("tasks.json")).unwrap();
+    assert!(!data.contains("call bob"), "{data}");
+
+    let out = common::run_rusk(&laptop, &["sync", shared.to_str().unwrap()]);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("Already in sync"));
+    assert!(
+        !common::run_rusk(&laptop, &["sync", laptop.to_str().unwrap()])
+            .status
+            .success()
+    );
+}
+
+#[test]
+fn test_archived_task_goes_to_the_other_archive() {
+    let temp_dir = tempdir().unwrap();
+    let laptop = temp_dir.path().join("laptop/");
+    let shared = temp_dir.path().join("shared/");
+    for db in [&laptop, &shared] {
+        assert!(common::run_rusk(db, &["init"]).status.success());
+    }
+    for args in [
+        &["add", "file taxes"][..],
+        &["mark", "1"],
+        &["add", "keep me"],
+    ] {
+        assert!(common::run_rusk(&laptop, args).status.success());
+    }
+    let sync_shared = || common::run_rusk(&laptop, &["sync", shared.to_str().unwrap()]);
+    assert!(sync_shared().status.success());
+
+    assert!(
+        common::run_rusk(&laptop, &["archive", "1"])
+            .status
+            .success()
+    );
+    let out = sync_shared();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("file taxes") && stdout.contains("archived"),
+        "{stdout}"
+    );
+    let data = fs::read_to_string(shared.join("tasks.json")).unwrap();
+    assert!(
+        !data.contains("file taxes") && data.contains("keep me"),
+        "{data}"
+    );
+    let out = common::run_rusk(&shared, &["list", "--archived"]);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("file taxes"));
+    assert!(String::from_utf8_lossy(&sync_shared().stdout).contains("Already in sync"));
+}
+
+#[test]
+fn test_sync_leaves_old_done_tasks_of_the_other_database_alone() {
+    let temp_dir = tempdir().unwrap();
+    let laptop = temp_dir.path().join("laptop/");
+    let shared = temp_dir.path().join("shared/");
+    for db in [&laptop, &shared] {
+        assert!(common::run_rusk(db, &["init"]).status.success());
+    }
+    let long_ago = Utc::now() - Duration::days(40);
+    let old = Task {
+        done: true,
+        completed_at: Some(long_ago),
+        ..task(1, "finished long ago", long_ago)
+    };
+    let shared_db = shared.join("tasks.json");
+    fs::write(
+        &shared_db,
+        rusk::db_format::encode(&[old], &Default::default()).unwrap(),
+    )
+    .unwrap();
+    let out = common::run_rusk(&laptop, &["config", "set", "archive.after_days", "30"]);
+    assert!(out.status.success(), "{out:?}");
+    // Something to push, so the other database is saved.
+    assert!(
+        common::run_rusk(&laptop, &["add", "new task"])
+            .status
+            .success()
+    );
+
+    let out = common::run_rusk(&laptop, &["sync", shared.to_str().unwrap()]);
+    assert!(out.status.success(), "{out:?}");
+    let data = fs::read_to_string(&shared_db).unwrap();
+    assert!(data.contains("finished long ago"), "{data}");
+    assert!(!shared.join("tasks.json.archive").exists());
+}
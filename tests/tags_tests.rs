@@ -0,0 +1,126 @@
+use rusk::cli::ListOptions;
+use rusk::{TaskManager, extract_tags, parse_tag_filter, tag_char_spans};
+use tempfile::tempdir;
+
+mod common;
+use common::create_test_task;
+
+#[test]
+fn test_extract_tags_basic() {
+    assert_eq!(
+        extract_tags("Fix login #backend #Urgent"),
+        vec!["backend", "urgent"]
+    );
+    assert_eq!(extract_tags("#start of text"), vec!["start"]);
+    assert_eq!(
+        extract_tags("nested #area/sub-area_2 ok"),
+        vec!["area/sub-area_2"]
+    );
+    assert!(extract_tags("no tags here").is_empty());
+}
+
+#[test]
+fn test_extract_tags_ignores_non_tags() {
+    // Mid-word `#`, bare `#`, and issue-style references after punctuation are not tags
+    assert!(extract_tags("C# and a#b").is_empty());
+    assert!(extract_tags("a lone # sign").is_empty());
+    assert!(extract_tags("see https://x.io/page#section").is_empty());
+    assert!(extract_tags("special chars: @#$%").is_empty());
+}
+
+#[test]
+fn test_extract_tags_dedup_and_multiline() {
+    assert_eq!(
+        extract_tags("#work first\nsecond line #Work #home"),
+        vec!["work", "home"]
+    );
+}
+
+#[test]
+fn test_tag_char_spans_are_char_based() {
+    // Multi-byte characters before the tag must not shift the span
+    assert_eq!(tag_char_spans("héllo #tag"), vec![(6, 10)]);
+    assert_eq!(tag_char_spans("#a #b."), vec![(0, 2), (3, 5)]);
+}
+
+#[test]
+fn test_parse_tag_filter() {
+    assert_eq!(parse_tag_filter("backend"), ("backend".to_string(), false));
+    assert_eq!(parse_tag_filter("#Backend"), ("backend".to_string(), false));
+    assert_eq!(parse_tag_filter("-backend"), ("backend".to_string(), true));
+    assert_eq!(parse_tag_filter("!#ops"), ("ops".to_string(), true));
+}
+
+#[test]
+fn test_add_and_edit_update_tags() {
+    let temp_dir = tempdir().unwrap();
+    let mut tm = TaskManager::new_empty_with_path(temp_dir.path().join("tasks.json"));
+    tm.add_task(vec!["Deploy".into(), "#ops".into()], None)
+        .unwrap();
+    assert_eq!(tm.tasks[0].tags, vec!["ops"]);
+
+    tm.edit_tasks(vec![1], Some(vec!["Deploy #backend".into()]), None)
+        .unwrap();
+    assert_eq!(tm.tasks[0].tags, vec!["backend"]);
+
+    let reloaded = TaskManager::load_tasks_from_path(tm.db_path()).unwrap();
+    assert_eq!(reloaded[0].tags, vec!["backend"]);
+}
+
+#[test]
+fn test_tags_rederived_on_load() {
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("tasks.json");
+    // Stored tags disagree with the text (hand-edited file); the text wins
+    std::fs::write(
+        &db_path,
+        r#"[{"id":1,"text":"Call #Mom","date":null,"done":false,"priority":false,"tags":["stale"]}]"#,
+    )
+    .unwrap();
+    let tasks = TaskManager::load_tasks_from_path(&db_path).unwrap();
+    assert_eq!(tasks[0].tags, vec!["mom"]);
+}
+
+#[test]
+fn test_tag_counts_sorted_by_count_then_name() {
+    let mut tm = TaskManager::new_empty().unwrap();
+    for (id, text) in [(1, "a #web #ops"), (2, "b #ops"), (3, "c #api"), (4, "d")] {
+        let mut task = create_test_task(id, "", false);
+        task.set_text(text.to_string());
+        tm.tasks.push(task);
+    }
+    assert_eq!(
+        tm.tag_counts(),
+        vec![
+            ("ops".to_string(), 2),
+            ("api".to_string(), 1),
+            ("web".to_string(), 1)
+        ]
+    );
+}
+
+#[test]
+fn test_list_options_tag_filter() {
+    let mut task = create_test_task(1, "", false);
+    task.set_text("Ship #backend #urgent".to_string());
+
+    let options = ListOptions {
+        tags: vec!["backend".into()],
+        ..Default::default()
+    };
+    assert!(options.matches(&task));
+
+    let options = ListOptions {
+        tags: vec!["backend".into(), "frontend".into()],
+        ..Default::default()
+    };
+    assert!(!options.matches(&task));
+
+    let options = ListOptions {
+        exclude_tags: vec!["urgent".into()],
+        ..Default::default()
+    };
+    assert!(!options.matches(&task));
+
+    assert!(ListOptions::default().matches(&task));
+}
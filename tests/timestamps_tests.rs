@@ -0,0 +1,89 @@
+use rusk::TaskManager;
+use tempfile::tempdir;
+
+mod common;
+
+#[test]
+fn test_add_task_sets_created_and_updated() {
+    let dir = tempdir().unwrap();
+    let mut tm = common::manager_with_tasks(dir.path(), &[]);
+    tm.add_task(vec!["New".to_string()], None).unwrap();
+
+    let task = &tm.tasks()[0];
+    assert!(task.created_at.is_some());
+    assert_eq!(task.created_at, task.updated_at);
+    assert!(task.completed_at.is_none());
+}
+
+#[test]
+fn test_mark_tasks_sets_and_clears_completed_at() {
+    let dir = tempdir().unwrap();
+    let mut tm = common::manager_with_tasks(dir.path(), &[]);
+    tm.add_task(vec!["Task".to_string()], None).unwrap();
+    let created = tm.tasks()[0].created_at;
+
+    tm.mark_tasks(vec![1]).unwrap();
+    let task = &tm.tasks()[0];
+    assert!(task.completed_at.is_some());
+    assert!(task.updated_at >= created);
+    assert_eq!(task.created_at, created);
+
+    tm.mark_tasks(vec![1]).unwrap();
+    assert!(tm.tasks()[0].completed_at.is_none());
+}
+
+#[test]
+fn test_edit_tasks_updates_only_changed_tasks() {
+    let dir = tempdir().unwrap();
+    let mut tm = common::manager_with_tasks(dir.path(), &[]);
+    tm.add_task(vec!["Same".to_string()], None).unwrap();
+    tm.add_task(vec!["Other".to_string()], None).unwrap();
+    tm.tasks_mut()[0].updated_at = None;
+    tm.tasks_mut()[1].updated_at = None;
+
+    tm.edit_tasks(vec![1, 2], Some(vec!["Same".to_string()]), None)
+        .unwrap();
+
+    assert!(tm.tasks()[0].updated_at.is_none(), "unchanged task");
+    assert!(tm.tasks()[1].updated_at.is_some(), "edited task");
+}
+
+#[test]
+fn test_mark_priority_updates_timestamp() {
+    let dir = tempdir().unwrap();
+    let mut tm = common::manager_with_tasks(dir.path(), &[]);
+    tm.add_task(vec!["Task".to_string()], None).unwrap();
+    tm.tasks_mut()[0].updated_at = None;
+
+    tm.mark_priority_tasks(vec![1]).unwrap();
+    assert!(tm.tasks()[0].updated_at.is_some());
+    assert!(tm.tasks()[0].completed_at.is_none());
+}
+
+#[test]
+fn test_legacy_tasks_load_without_timestamps() {
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("legacy.json");
+    std::fs::write(
+        &db_path,
+        r#"[{"id":1,"text":"Legacy","date":null,"done":true,"priority":false}]"#,
+    )
+    .unwrap();
+
+    let tasks = TaskManager::load_tasks_from_path(&db_path).unwrap();
+    assert!(tasks[0].created_at.is_none());
+    assert!(tasks[0].updated_at.is_none());
+    assert!(tasks[0].completed_at.is_none());
+}
+
+#[test]
+fn test_timestamps_roundtrip_through_save() {
+    let dir = tempdir().unwrap();
+    let mut tm = common::manager_with_tasks(dir.path(), &[]);
+    tm.add_task(vec!["Task".to_string()], None).unwrap();
+    tm.mark_tasks(vec![1]).unwrap();
+
+    let loaded = TaskManager::load_tasks_from_path(&dir.path().join("tasks.json")).unwrap();
+    assert_eq!(loaded[0].created_at, tm.tasks()[0].created_at);
+    assert_eq!(loaded[0].completed_at, tm.tasks()[0].completed_at);
+}
@@ -0,0 +1,133 @@
+use rusk::{TaskManager, is_uuid_prefix_token};
+use tempfile::tempdir;
+use uuid::Uuid;
+
+mod common;
+use common::create_test_task;
+
+fn task_with_uuid(id: u32, text: &str, uuid: &str) -> rusk::Task {
+    let mut task = create_test_task(id, text, false);
+    task.uuid = Uuid::parse_str(uuid).unwrap();
+    task
+}
+
+#[test]
+fn test_add_task_assigns_unique_uuid() {
+    let temp_dir = tempdir().unwrap();
+    let mut tm = TaskManager::new_empty_with_path(temp_dir.path().join("tasks.json"));
+    tm.add_task(vec!["First".to_string()], None).unwrap();
+    tm.add_task(vec!["Second".to_string()], None).unwrap();
+
+    assert!(!tm.tasks[0].uuid.is_nil());
+    assert!(!tm.tasks[1].uuid.is_nil());
+    assert_ne!(tm.tasks[0].uuid, tm.tasks[1].uuid);
+}
+
+#[test]
+fn test_reused_id_gets_new_uuid() {
+    let temp_dir = tempdir().unwrap();
+    let mut tm = TaskManager::new_empty_with_path(temp_dir.path().join("tasks.json"));
+    tm.add_task(vec!["Old".to_string()], None).unwrap();
+    let old_uuid = tm.tasks[0].uuid;
+
+    tm.delete_tasks(vec![1]).unwrap();
+    tm.add_task(vec!["New".to_string()], None).unwrap();
+
+    assert_eq!(tm.tasks[0].id, 1);
+    assert_ne!(tm.tasks[0].uuid, old_uuid);
+}
+
+#[test]
+fn test_legacy_database_gets_uuids_that_survive_save() {
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("legacy.json");
+    std::fs::write(
+        &db_path,
+        r#"[{"id":1,"text":"Legacy","date":null,"done":false,"priority":false}]"#,
+    )
+    .unwrap();
+
+    let mut tm = TaskManager::new_empty_with_path(db_path.clone());
+    tm.tasks = TaskManager::load_tasks_from_path(&db_path).unwrap();
+    let uuid = tm.tasks[0].uuid;
+    assert!(!uuid.is_nil());
+
+    tm.save().unwrap();
+    let reloaded = TaskManager::load_tasks_from_path(&db_path).unwrap();
+    assert_eq!(reloaded[0].uuid, uuid);
+}
+
+#[test]
+fn test_find_task_by_uuid_prefix() {
+    let mut tm = TaskManager::new_empty().unwrap();
+    tm.tasks = vec![
+        task_with_uuid(1, "A", "3fa85f64-5717-4562-b3fc-2c963f66afa6"),
+        task_with_uuid(2, "B", "3fb00000-0000-4000-8000-000000000000"),
+    ];
+
+    assert_eq!(tm.find_task_by_uuid_prefix("3fa8").unwrap(), Some(0));
+    assert_eq!(tm.find_task_by_uuid_prefix("3FB0").unwrap(), Some(1));
+    assert_eq!(
+        tm.find_task_by_uuid_prefix("3fa85f64-5717").unwrap(),
+        Some(0)
+    );
+    assert_eq!(tm.find_task_by_uuid_prefix("abcd").unwrap(), None);
+
+    let err = tm.find_task_by_uuid_prefix("3f").unwrap_err();
+    assert!(err.to_string().contains("ambiguous"));
+}
+
+#[test]
+fn test_resolve_uuid_prefix_args() {
+    let mut tm = TaskManager::new_empty().unwrap();
+    tm.tasks = vec![
+        task_with_uuid(4, "A", "3fa85f64-5717-4562-b3fc-2c963f66afa6"),
+        task_with_uuid(7, "B", "c0ffee00-0000-4000-8000-000000000000"),
+    ];
+
+    let args = vec!["3fa8".to_string()];
+    assert_eq!(tm.resolve_uuid_prefix_args(&args).unwrap(), vec!["4"]);
+
+    let args = vec!["1,c0ffee".to_string()];
+    assert_eq!(tm.resolve_uuid_prefix_args(&args).unwrap(), vec!["1,7"]);
+
+    // Edit text after the ID is left alone, even when it looks like hex
+    let args = vec!["3fa8".to_string(), "add".to_string(), "c0ffee".to_string()];
+    assert_eq!(
+        tm.resolve_uuid_prefix_args(&args).unwrap(),
+        vec!["4", "add", "c0ffee"]
+    );
+
+    // So is text after a comma list
+    let args = vec![
+        "1,3fa8".to_string(),
+        "added".to_string(),
+        "tests".to_string(),
+    ];
+    assert_eq!(
+        tm.resolve_uuid_prefix_args(&args).unwrap(),
+        vec!["1,4", "added", "tests"]
+    );
+    let args = vec!["3fa8".to_string(), "decade,".to_string()];
+    assert_eq!(
+        tm.resolve_uuid_prefix_args(&args).unwrap(),
+        vec!["4", "decade,"]
+    );
+
+    let args = vec!["dead".to_string()];
+    let err = tm.resolve_uuid_prefix_args(&args).unwrap_err();
+    assert!(err.to_string().contains("No task with UUID prefix 'dead'"));
+}
+
+#[test]
+fn test_is_uuid_prefix_token() {
+    assert!(is_uuid_prefix_token("3fa8"));
+    assert!(is_uuid_prefix_token("3fa85f64-5717"));
+    assert!(is_uuid_prefix_token("ABCD"));
+    // All-digit tokens are short IDs
+    assert!(!is_uuid_prefix_token("1234"));
+    // Too short to be unambiguous
+    assert!(!is_uuid_prefix_token("3fa"));
+    assert!(!is_uuid_prefix_token("-3fa8"));
+    assert!(!is_uuid_prefix_token("task"));
+}
@@ -0,0 +1,56 @@
+use chrono::{Duration, Local};
+use rusk::{NewTaskOptions, Recurrence, RecurrenceMode, Task, TaskManager};
+use tempfile::tempdir;
+
+mod common;
+use common::ymd;
+
+#[test]
+fn test_is_waiting_until_start_date() {
+    let task = Task {
+        wait: Some(ymd(2026, 11, 1)),
+        ..Default::default()
+    };
+    assert!(task.is_waiting(ymd(2026, 10, 31)));
+    assert!(!task.is_waiting(ymd(2026, 11, 1)));
+    assert!(!Task::default().is_waiting(ymd(2026, 10, 31)));
+}
+
+#[test]
+fn test_add_with_wait_and_set_wait() {
+    let temp_dir = tempdir().unwrap();
+    let mut tm = TaskManager::new_empty_with_path(temp_dir.path().join("tasks.json"));
+    let today = Local::now().date_naive();
+    let options = NewTaskOptions {
+        wait: Some(today + Duration::days(10)),
+        ..Default::default()
+    };
+    tm.add_task_with_options("Renew passport".to_string(), None, &options)
+        .unwrap();
+    assert!(tm.tasks[0].is_waiting(today));
+
+    assert!(tm.set_wait(1, None).unwrap());
+    assert!(!tm.set_wait(1, None).unwrap());
+    assert!(!tm.tasks[0].is_waiting(today));
+    assert!(tm.set_wait(2, None).is_err());
+
+    let reloaded = TaskManager::load_tasks_from_path(tm.db_path()).unwrap();
+    assert_eq!(reloaded[0].wait, None);
+}
+
+#[test]
+fn test_next_occurrence_keeps_lead_time() {
+    let temp_dir = tempdir().unwrap();
+    let mut tm = TaskManager::new_empty_with_path(temp_dir.path().join("tasks.json"));
+    let options = NewTaskOptions {
+        recurrence: Some(Recurrence::new("1m", RecurrenceMode::FromDue).unwrap()),
+        wait: Some(ymd(2026, 10, 25)),
+        ..Default::default()
+    };
+    tm.add_task_with_options("Pay rent".to_string(), Some(ymd(2026, 11, 1)), &options)
+        .unwrap();
+
+    tm.mark_tasks(vec![1]).unwrap();
+    assert_eq!(tm.tasks[1].date, Some(ymd(2026, 12, 1)));
+    assert_eq!(tm.tasks[1].wait, Some(ymd(2026, 11, 24)));
+}